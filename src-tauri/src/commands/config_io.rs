@@ -84,6 +84,9 @@ pub async fn import_config(
                 let mut current = state.settings.lock().map_err(|e| e.to_string())?;
                 *current = imported.settings.clone();
             }
+            if let Some(engine) = state.engine.lock().map_err(|e| e.to_string())?.as_ref() {
+                engine.osc_tcp.retain_configured(&imported.settings);
+            }
             config::save_settings(&imported.settings)?;
 
             // Update mappings
@@ -1,11 +1,17 @@
-use crate::midi_engine;
-use crate::models::{EngineStatus, OscListenProtocol};
+use crate::macros::{MacroRunner, TimedOutput};
+use crate::midi_engine::{self, MidiInputs, MidiOutputs};
+use crate::models::{EngineStatus, OscConnectionStatus, OscListenProtocol, Settings};
+use crate::mtc::{MtcMaster, MtcPublisher};
 use crate::osc_engine;
-use crate::router::{IncomingMessage, Router};
+use crate::osc_tcp::TcpSenderPool;
+use crate::router::{IncomingMessage, Routed, Router};
+use crate::slew::{Dispatch, Slewer};
 use crate::state::{AppState, EngineHandle};
 
 use log::{info, warn};
-use tauri::{AppHandle, Emitter, Manager, State};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, State};
 use tokio::sync::mpsc;
 use tokio_util::sync::CancellationToken;
 
@@ -31,8 +37,8 @@ pub async fn start_engine(state: State<'_, AppState>, app: AppHandle) -> Result<
         settings.osc_send_host,
         settings.osc_send_port,
         settings.osc_send_protocol,
-        settings.midi_input_port_name,
-        settings.midi_output_port_name,
+        settings.midi_input_ports(),
+        settings.midi_output_ports(),
     );
 
     let settings_arc = state.settings.clone();
@@ -41,45 +47,35 @@ pub async fn start_engine(state: State<'_, AppState>, app: AppHandle) -> Result<
     let token = CancellationToken::new();
     let (tx, mut rx) = mpsc::unbounded_channel::<IncomingMessage>();
 
-    // Open MIDI input
-    let midi_input_conn = if let Some(ref port_name) = settings.midi_input_port_name {
-        match midi_engine::open_input(port_name, tx.clone()) {
-            Ok(conn) => Some(conn),
-            Err(e) => {
-                warn!("MIDI input warning: {}", e);
-                let _ = app.emit(
-                    "engine-status",
-                    EngineStatus {
-                        running: true,
-                        error: Some(format!("MIDI input warning: {}", e)),
-                    },
-                );
-                None
-            }
+    // Open MIDI ports. A port that fails to open is reported and retried
+    // by the hot-plug task when the device list changes.
+    let midi_inputs = Arc::new(MidiInputs::default());
+    for port_name in settings.midi_input_ports() {
+        if let Err(e) = midi_inputs.open(&port_name, tx.clone()) {
+            warn!("MIDI input warning: {}", e);
+            let _ = app.emit(
+                "engine-status",
+                EngineStatus {
+                    running: true,
+                    error: Some(format!("MIDI input warning: {}", e)),
+                },
+            );
         }
-    } else {
-        None
-    };
-
-    // Open MIDI output
-    let midi_output_conn = if let Some(ref port_name) = settings.midi_output_port_name {
-        match midi_engine::open_output(port_name) {
-            Ok(conn) => Some(conn),
-            Err(e) => {
-                warn!("MIDI output warning: {}", e);
-                let _ = app.emit(
-                    "engine-status",
-                    EngineStatus {
-                        running: true,
-                        error: Some(format!("MIDI output warning: {}", e)),
-                    },
-                );
-                None
-            }
+    }
+    let midi_output_ports = settings.midi_output_ports();
+    let midi_outputs = Arc::new(MidiOutputs::new(midi_output_ports.first().cloned()));
+    for port_name in &midi_output_ports {
+        if let Err(e) = midi_outputs.open(port_name) {
+            warn!("MIDI output warning: {}", e);
+            let _ = app.emit(
+                "engine-status",
+                EngineStatus {
+                    running: true,
+                    error: Some(format!("MIDI output warning: {}", e)),
+                },
+            );
         }
-    } else {
-        None
-    };
+    }
 
     // Start OSC listeners
     match settings.osc_listen_protocol {
@@ -91,33 +87,108 @@ pub async fn start_engine(state: State<'_, AppState>, app: AppHandle) -> Result<
     }
     match settings.osc_listen_protocol {
         OscListenProtocol::Tcp | OscListenProtocol::Both => {
-            osc_engine::start_tcp_listener(settings.osc_listen_port, tx.clone(), token.clone())
-                .await?;
+            osc_engine::start_tcp_listener(
+                settings.osc_listen_port,
+                settings.osc_tcp_listen_framing.clone(),
+                tx.clone(),
+                token.clone(),
+            )
+            .await?;
         }
         _ => {}
     }
 
+    // Persistent TCP connections for OSC output, with state forwarded to the frontend
+    let (status_tx, mut status_rx) = mpsc::unbounded_channel::<OscConnectionStatus>();
+    let osc_tcp = Arc::new(TcpSenderPool::new(token.child_token(), status_tx));
+    let status_app = app.clone();
+    tokio::spawn(async move {
+        while let Some(status) = status_rx.recv().await {
+            let _ = status_app.emit("osc-connection-status", status);
+        }
+    });
+
     // Spawn router task
-    let router = Router::new(mappings_arc, app.clone());
-    let midi_out_for_router = midi_output_conn;
+    // Only persisted toggle/latch states survive a restart
+    if let Ok(mappings) = mappings_arc.lock() {
+        state.mapping_states.retain_persistent(&mappings);
+    }
+    tokio::spawn(crate::mapping_state::save_changes(
+        state.mapping_states.clone(),
+        mappings_arc.clone(),
+        token.clone(),
+    ));
+    state.drop_counts.reset();
+    state.loop_guard.start(settings.loop_max_rate_hz);
+    let router = Router::new(
+        mappings_arc,
+        state.mapping_states.clone(),
+        state.drop_counts.clone(),
+        state.loop_guard.clone(),
+        app.clone(),
+    );
+    let midi_out_for_router = midi_outputs.clone();
+    let osc_tcp_for_router = osc_tcp.clone();
     let router_token = token.clone();
+    let learn = state.learn.clone();
 
     let rt = tokio::runtime::Handle::current();
+    let loop_guard = state.loop_guard.clone();
+    let dispatch: Dispatch = Arc::new(move |action| {
+        loop_guard.record_sent(action, std::time::Instant::now());
+        osc_engine::dispatch_output(
+            action,
+            &settings_arc,
+            &midi_out_for_router,
+            &osc_tcp_for_router,
+            &rt,
+        );
+    });
+    let slewer = Slewer::new(dispatch.clone(), token.clone());
+    let macros = MacroRunner::new(dispatch.clone(), token.clone());
+    let mut mtc = MtcPublisher::new(settings.mtc_output.clone());
+    // Runs on its own thread until the router task drops it
+    let mtc_master = settings
+        .mtc_generator
+        .enabled
+        .then(|| MtcMaster::start(&settings.mtc_generator, midi_outputs.clone()))
+        .transpose()
+        .unwrap_or_else(|e| {
+            warn!("{}", e);
+            None
+        });
     tokio::spawn(async move {
         loop {
+            let next_flush = router.next_flush();
+            let mtc_dropout = mtc.dropout_deadline();
             tokio::select! {
                 _ = router_token.cancelled() => break,
+                _ = sleep_until(next_flush) => {
+                    for routed in router.flush_held() {
+                        send(&slewer, &macros, &dispatch, routed);
+                    }
+                }
+                _ = sleep_until(mtc_dropout) => {
+                    if let Some(action) = mtc.check_dropout(std::time::Instant::now()) {
+                        dispatch(&action);
+                    }
+                }
                 msg = rx.recv() => {
                     match msg {
+                        // Timecode is published directly rather than routed
+                        Some(IncomingMessage::Mtc { time, running, .. }) => {
+                            let now = std::time::Instant::now();
+                            if let Some(action) = mtc.update(time, running, now) {
+                                dispatch(&action);
+                            }
+                        }
+                        // Transport commands drive the MTC generator instead
+                        Some(incoming)
+                            if mtc_master.as_ref().is_some_and(|m| m.handle(&incoming)) => {}
                         Some(incoming) => {
-                            let actions = router.route(&incoming);
-                            for action in &actions {
-                                osc_engine::dispatch_output(
-                                    action,
-                                    &settings_arc,
-                                    &midi_out_for_router,
-                                    &rt,
-                                );
+                            learn.offer(&incoming);
+                            for routed in router.route(&incoming) {
+                                send(&slewer, &macros, &dispatch, routed);
                             }
                         }
                         None => break,
@@ -131,8 +202,14 @@ pub async fn start_engine(state: State<'_, AppState>, app: AppHandle) -> Result<
     let hotplug_token = token.clone();
     let hotplug_app = app.clone();
     let hotplug_settings = state.settings.clone();
+    let hotplug_inputs = midi_inputs.clone();
+    let hotplug_outputs = midi_outputs;
+    let hotplug_tx = tx.clone();
     tokio::spawn(async move {
         let (mut last_inputs, mut last_outputs) = midi_engine::enumerate_ports_hash();
+        // Ports whose last open attempt failed, so each failure is logged once
+        let mut failed_inputs = HashSet::new();
+        let mut failed_outputs = HashSet::new();
 
         loop {
             tokio::select! {
@@ -144,47 +221,63 @@ pub async fn start_engine(state: State<'_, AppState>, app: AppHandle) -> Result<
                         last_inputs = inputs.clone();
                         last_outputs = outputs.clone();
                         let _ = hotplug_app.emit("midi-devices-changed", ());
+                    }
 
-                        // Check if active port disappeared
-                        let current_settings = match hotplug_settings.lock() {
-                            Ok(guard) => guard.clone(),
-                            Err(e) => {
-                                warn!("Settings mutex poisoned in hotplug loop: {}", e);
-                                continue;
-                            }
-                        };
-                        let mut disconnected = false;
-                        if let Some(ref name) = current_settings.midi_input_port_name {
-                            if !inputs.contains(name) {
-                                warn!("MIDI input disconnected: {}", name);
-                                let _ = hotplug_app.emit("engine-status", EngineStatus {
-                                    running: false,
-                                    error: Some(format!("MIDI input disconnected: {}", name)),
-                                });
-                                disconnected = true;
-                            }
+                    // Close ports that disappeared and (re)open configured ports that
+                    // are present but not open, each on its own, on every tick so a
+                    // port that was busy is retried. The engine keeps running either way.
+                    let mut changed = false;
+                    let current_settings = match hotplug_settings.lock() {
+                        Ok(guard) => guard.clone(),
+                        Err(e) => {
+                            warn!("Settings mutex poisoned in hotplug loop: {}", e);
+                            continue;
                         }
-                        if let Some(ref name) = current_settings.midi_output_port_name {
-                            if !outputs.contains(name) {
-                                warn!("MIDI output disconnected: {}", name);
-                                let _ = hotplug_app.emit("engine-status", EngineStatus {
-                                    running: false,
-                                    error: Some(format!("MIDI output disconnected: {}", name)),
-                                });
-                                disconnected = true;
+                    };
+                    for name in current_settings.midi_input_ports() {
+                        let present = inputs.contains(&name);
+                        if !present && hotplug_inputs.close(&name) {
+                            warn!("MIDI input disconnected: {}", name);
+                            changed = true;
+                        } else if present && !hotplug_inputs.is_open(&name) {
+                            match hotplug_inputs.open(&name, hotplug_tx.clone()) {
+                                Ok(()) => {
+                                    info!("MIDI input reconnected: {}", name);
+                                    failed_inputs.remove(&name);
+                                    changed = true;
+                                }
+                                Err(e) => {
+                                    if failed_inputs.insert(name) {
+                                        warn!("MIDI input warning: {}", e);
+                                    }
+                                }
                             }
                         }
-                        if disconnected {
-                            // Use app handle to access state and stop engine
-                            let state: State<AppState> = hotplug_app.state();
-                            if let Ok(mut eng) = state.engine.lock() {
-                                if let Some(handle) = eng.take() {
-                                    handle.cancel_token.cancel();
+                    }
+                    for name in current_settings.midi_output_ports() {
+                        let present = outputs.contains(&name);
+                        if !present && hotplug_outputs.close(&name) {
+                            warn!("MIDI output disconnected: {}", name);
+                            changed = true;
+                        } else if present && !hotplug_outputs.is_open(&name) {
+                            match hotplug_outputs.open(&name) {
+                                Ok(()) => {
+                                    info!("MIDI output reconnected: {}", name);
+                                    failed_outputs.remove(&name);
+                                    changed = true;
+                                }
+                                Err(e) => {
+                                    if failed_outputs.insert(name) {
+                                        warn!("MIDI output warning: {}", e);
+                                    }
                                 }
                             }
-                            break;
                         }
                     }
+                    if changed {
+                        let error = missing_ports(&current_settings, &hotplug_inputs, &hotplug_outputs);
+                        emit_port_status(&hotplug_app, error);
+                    }
                 }
             }
         }
@@ -195,7 +288,8 @@ pub async fn start_engine(state: State<'_, AppState>, app: AppHandle) -> Result<
         let mut engine = state.engine.lock().map_err(|e| e.to_string())?;
         *engine = Some(EngineHandle {
             cancel_token: token,
-            _midi_input: midi_input_conn,
+            midi_inputs,
+            osc_tcp,
         });
     }
 
@@ -210,6 +304,66 @@ pub async fn start_engine(state: State<'_, AppState>, app: AppHandle) -> Result<
     Ok(())
 }
 
+/// Describe every configured MIDI port that is not open, or `None` when all are.
+fn missing_ports(
+    settings: &Settings,
+    inputs: &MidiInputs,
+    outputs: &MidiOutputs,
+) -> Option<String> {
+    let missing: Vec<String> = settings
+        .midi_input_ports()
+        .into_iter()
+        .filter(|name| !inputs.is_open(name))
+        .map(|name| format!("MIDI input disconnected: {}", name))
+        .chain(
+            settings
+                .midi_output_ports()
+                .into_iter()
+                .filter(|name| !outputs.is_open(name))
+                .map(|name| format!("MIDI output disconnected: {}", name)),
+        )
+        .collect();
+    (!missing.is_empty()).then(|| missing.join("; "))
+}
+
+/// Report a MIDI port change without stopping the engine. `None` clears an
+/// earlier disconnect warning once every port is back.
+fn emit_port_status(app: &AppHandle, error: Option<String>) {
+    let _ = app.emit(
+        "engine-status",
+        EngineStatus {
+            running: true,
+            error,
+        },
+    );
+}
+
+/// Send a routed output: as a sequence when the mapping has macro steps,
+/// through the slewer when it has a slew, otherwise straight away.
+fn send(slewer: &Slewer, macros: &MacroRunner, dispatch: &Dispatch, routed: Routed) {
+    if !routed.steps.is_empty() {
+        let mut steps = vec![TimedOutput {
+            delay: std::time::Duration::ZERO,
+            action: routed.action,
+        }];
+        steps.extend(routed.steps);
+        macros.trigger(&routed.mapping_id, routed.retrigger, steps);
+        return;
+    }
+    match &routed.slew {
+        Some(slew) => slewer.submit(&routed.mapping_id, slew, routed.action),
+        None => dispatch(&routed.action),
+    }
+}
+
+/// Wait until `deadline`, or forever when there is none.
+async fn sleep_until(deadline: Option<std::time::Instant>) {
+    match deadline {
+        Some(deadline) => tokio::time::sleep_until(deadline.into()).await,
+        None => std::future::pending().await,
+    }
+}
+
 #[tauri::command]
 pub fn stop_engine(state: State<AppState>, app: AppHandle) -> Result<(), String> {
     info!("Stopping engine");
@@ -236,6 +390,29 @@ pub fn get_engine_status(state: State<AppState>) -> Result<EngineStatus, String>
     })
 }
 
+/// Mappings disabled by a detected feedback loop, with the reason, keyed by
+/// mapping ID.
+#[tauri::command]
+pub fn get_loop_breakers(state: State<AppState>) -> Result<HashMap<String, String>, String> {
+    Ok(state.loop_guard.tripped())
+}
+
+/// Re-enable one mapping disabled by a loop breaker, or all of them when
+/// `id` is omitted.
+#[tauri::command]
+pub fn reset_loop_breakers(id: Option<String>, state: State<AppState>) -> Result<(), String> {
+    state.loop_guard.reset(id.as_deref())
+}
+
+#[tauri::command]
+pub fn get_osc_connections(state: State<AppState>) -> Result<Vec<OscConnectionStatus>, String> {
+    let engine = state.engine.lock().map_err(|e| e.to_string())?;
+    Ok(engine
+        .as_ref()
+        .map(|handle| handle.osc_tcp.statuses())
+        .unwrap_or_default())
+}
+
 #[tauri::command]
 pub async fn send_osc_test_value(
     state: State<'_, AppState>,
@@ -259,15 +436,22 @@ pub async fn send_osc_test_value(
             )
             .await
         }
+        // TCP goes through the engine's connection to the default target
         crate::models::OscSendProtocol::Tcp => {
-            osc_engine::send_osc_tcp(
-                &settings.osc_send_host,
-                settings.osc_send_port,
-                &address,
-                &args,
-                settings.osc_tcp_send_timeout_ms,
-            )
-            .await
+            let osc_tcp = {
+                let engine = state.engine.lock().map_err(|e| e.to_string())?;
+                engine
+                    .as_ref()
+                    .map(|handle| handle.osc_tcp.clone())
+                    .ok_or("Start the engine to send test values over TCP")?
+            };
+            let packet = osc_engine::encode_osc_message(&address, &args)?;
+            osc_tcp.send(
+                &settings.default_osc_destination(),
+                &packet,
+                &tokio::runtime::Handle::current(),
+            );
+            Ok(())
         }
     }
 }
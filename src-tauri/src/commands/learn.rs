@@ -0,0 +1,139 @@
+use crate::config;
+use crate::learn::{self, LearnTarget, DEFAULT_LEARN_TIMEOUT_MS};
+use crate::models::{Direction, Mapping, MidiMessageType};
+use crate::router::IncomingMessage;
+use crate::state::AppState;
+use log::info;
+use std::time::Duration;
+use tauri::State;
+
+/// Arm MIDI learn and wait for the next MIDI or MSC message, optionally of
+/// one type. With `mapping_id` the MIDI side of that mapping is filled in and
+/// saved; otherwise an unsaved `MidiToOsc` draft is returned. Routing carries
+/// on while learning.
+#[tauri::command]
+pub async fn start_midi_learn(
+    state: State<'_, AppState>,
+    filter: Option<MidiMessageType>,
+    mapping_id: Option<String>,
+    timeout_ms: Option<u64>,
+) -> Result<Mapping, String> {
+    let target = LearnTarget::Midi {
+        filter: filter.clone(),
+    };
+    check_learnable(&state, &target, mapping_id.as_deref())?;
+    info!("MIDI learn armed (filter: {:?})", filter);
+    let msgs = wait_for_learn(&state, target, timeout_ms, "MIDI").await?;
+    let Some(msg) = msgs.first() else {
+        return Err("MIDI learn captured nothing".to_string());
+    };
+    match mapping_id {
+        Some(id) => update_learned(&state, &id, |m| learn::apply_learned(m, msg)),
+        None => Ok(learn::draft_mapping(msg)),
+    }
+}
+
+/// Arm OSC learn and wait for `count` messages (default 1) to the first
+/// address seen. If the timeout passes after at least one message, the
+/// messages so far are used. The draft or `mapping_id` gets the address and
+/// argument types, and `OscToOsc` mappings the observed input range.
+#[tauri::command]
+pub async fn start_osc_learn(
+    state: State<'_, AppState>,
+    direction: Option<Direction>,
+    count: Option<usize>,
+    mapping_id: Option<String>,
+    timeout_ms: Option<u64>,
+) -> Result<Mapping, String> {
+    let count = count.unwrap_or(1).max(1);
+    let target = LearnTarget::Osc { count };
+    check_learnable(&state, &target, mapping_id.as_deref())?;
+    info!("OSC learn armed ({} messages)", count);
+    let msgs = wait_for_learn(&state, target, timeout_ms, "OSC").await?;
+    match mapping_id {
+        Some(id) => update_learned(&state, &id, |m| learn::apply_osc_learned(m, &msgs)),
+        None => Ok(learn::draft_osc_mapping(
+            direction.unwrap_or(Direction::OscToMidi),
+            &msgs,
+        )),
+    }
+}
+
+#[tauri::command]
+pub fn cancel_learn(state: State<AppState>) -> Result<(), String> {
+    if state.learn.cancel() {
+        info!("Learn cancelled");
+    }
+    Ok(())
+}
+
+/// Check that the engine runs and that `mapping_id`, if any, exists and has
+/// the side `target` learns, before anything is captured.
+fn check_learnable(
+    state: &AppState,
+    target: &LearnTarget,
+    mapping_id: Option<&str>,
+) -> Result<(), String> {
+    {
+        let engine = state.engine.lock().map_err(|e| e.to_string())?;
+        if engine.is_none() {
+            return Err("Engine is not running".to_string());
+        }
+    }
+    if let Some(id) = mapping_id {
+        let mappings = state.mappings.lock().map_err(|e| e.to_string())?;
+        let mapping = mappings
+            .iter()
+            .find(|m| m.id == id)
+            .ok_or_else(|| format!("Mapping not found: {}", id))?;
+        match (target, &mapping.direction) {
+            (LearnTarget::Midi { .. }, Direction::OscToOsc) => {
+                return Err("OSC → OSC mappings have no MIDI side to learn".to_string());
+            }
+            (LearnTarget::Osc { .. }, Direction::MidiToMidi) => {
+                return Err("MIDI → MIDI mappings have no OSC side to learn".to_string());
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+async fn wait_for_learn(
+    state: &AppState,
+    target: LearnTarget,
+    timeout_ms: Option<u64>,
+    kind: &str,
+) -> Result<Vec<IncomingMessage>, String> {
+    let rx = state.learn.arm(target);
+    let timeout = Duration::from_millis(timeout_ms.unwrap_or(DEFAULT_LEARN_TIMEOUT_MS));
+    match tokio::time::timeout(timeout, rx).await {
+        Ok(Ok(msgs)) => Ok(msgs),
+        Ok(Err(_)) => Err(format!("{} learn cancelled", kind)),
+        Err(_) => {
+            let partial = state.learn.take_partial();
+            if partial.is_empty() {
+                Err(format!("{} learn timed out", kind))
+            } else {
+                Ok(partial)
+            }
+        }
+    }
+}
+
+/// Apply a learn result to a saved mapping and persist it.
+fn update_learned(
+    state: &AppState,
+    id: &str,
+    apply: impl FnOnce(&mut Mapping),
+) -> Result<Mapping, String> {
+    let mut mappings = state.mappings.lock().map_err(|e| e.to_string())?;
+    let mapping = mappings
+        .iter_mut()
+        .find(|m| m.id == id)
+        .ok_or_else(|| format!("Mapping not found: {}", id))?;
+    apply(mapping);
+    let learned = mapping.clone();
+    config::save_mappings(&mappings)?;
+    Ok(learned)
+}
@@ -1,6 +1,8 @@
 use crate::config;
 use crate::models::Mapping;
+use crate::output_filter::DropCount;
 use crate::state::AppState;
+use std::collections::HashMap;
 use tauri::State;
 use uuid::Uuid;
 
@@ -75,3 +77,24 @@ pub fn reorder_mappings(ids: Vec<String>, state: State<AppState>) -> Result<(),
     *mappings = reordered;
     config::save_mappings(&mappings)
 }
+
+/// Current toggle/latch states, keyed by mapping ID or state group.
+#[tauri::command]
+pub fn get_mapping_states(state: State<AppState>) -> Result<HashMap<String, bool>, String> {
+    Ok(state.mapping_states.snapshot())
+}
+
+/// Turn one state off, or every state when `key` is omitted.
+#[tauri::command]
+pub fn reset_mapping_states(key: Option<String>, state: State<AppState>) -> Result<(), String> {
+    state.mapping_states.reset(key.as_deref())?;
+    let mappings = state.mappings.lock().map_err(|e| e.to_string())?;
+    config::save_mapping_states(&state.mapping_states.persistent_snapshot(&mappings))
+}
+
+/// Outputs dropped by rate limits, dedupe and deadbands since the engine
+/// started, keyed by mapping ID.
+#[tauri::command]
+pub fn get_drop_counts(state: State<AppState>) -> Result<HashMap<String, DropCount>, String> {
+    Ok(state.drop_counts.snapshot())
+}
@@ -1,5 +1,6 @@
 pub mod config_io;
 pub mod engine;
+pub mod learn;
 pub mod mappings;
 pub mod midi;
 pub mod settings;
@@ -37,5 +37,8 @@ pub fn update_settings(
 
     info!("Settings updated");
     *current = settings.clone();
+    if let Some(engine) = state.engine.lock().map_err(|e| e.to_string())?.as_ref() {
+        engine.osc_tcp.retain_configured(&settings);
+    }
     config::save_settings(&settings)
 }
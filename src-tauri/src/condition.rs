@@ -0,0 +1,306 @@
+use crate::models::{Comparison, Condition, ConditionValue};
+use crate::router::OscArgValue;
+use log::warn;
+use regex::Regex;
+use std::collections::HashMap;
+
+/// The parts of an incoming message a condition can read.
+#[derive(Debug, Default)]
+pub struct ConditionInput<'a> {
+    pub args: &'a [OscArgValue],
+    pub midi_value: Option<u16>,
+    pub midi_note: Option<u16>,
+}
+
+/// A condition operand read from the message or taken from a constant.
+#[derive(Debug, Clone, PartialEq)]
+enum Operand {
+    Number(f64),
+    Text(String),
+}
+
+impl Operand {
+    fn as_text(&self) -> String {
+        match self {
+            Operand::Number(n) => n.to_string(),
+            Operand::Text(s) => s.clone(),
+        }
+    }
+}
+
+/// Evaluates mapping conditions, caching compiled regexes by pattern.
+#[derive(Default)]
+pub struct ConditionChecker {
+    /// `None` for patterns that failed to compile
+    regexes: HashMap<String, Option<Regex>>,
+}
+
+impl ConditionChecker {
+    /// Check every condition in order. On failure, returns a description
+    /// of the first condition that did not pass.
+    pub fn check(
+        &mut self,
+        conditions: &[Condition],
+        input: &ConditionInput,
+    ) -> Result<(), String> {
+        for condition in conditions {
+            if !self.eval(condition, input) {
+                return Err(format!("Condition failed: {}", describe(condition)));
+            }
+        }
+        Ok(())
+    }
+
+    fn eval(&mut self, condition: &Condition, input: &ConditionInput) -> bool {
+        match condition {
+            Condition::Range { value, min, max } => {
+                matches!(resolve(value, input), Some(Operand::Number(n)) if *min <= n && n <= *max)
+            }
+            Condition::Compare { left, cmp, right } => {
+                match (resolve(left, input), resolve(right, input)) {
+                    (Some(l), Some(r)) => compare(&l, *cmp, &r),
+                    _ => false,
+                }
+            }
+            Condition::Regex { value, pattern } => {
+                let Some(text) = resolve(value, input).map(|v| v.as_text()) else {
+                    return false;
+                };
+                let regex = self.regexes.entry(pattern.clone()).or_insert_with(|| {
+                    Regex::new(pattern)
+                        .map_err(|e| warn!("Invalid condition regex {}: {}", pattern, e))
+                        .ok()
+                });
+                regex.as_ref().is_some_and(|re| re.is_match(&text))
+            }
+        }
+    }
+}
+
+/// Read an operand from the message. `None` when the argument is missing
+/// or has no usable value (blobs, nil, arrays, …).
+fn resolve(value: &ConditionValue, input: &ConditionInput) -> Option<Operand> {
+    match value {
+        ConditionValue::OscArg { index } => match input.args.get(*index)? {
+            OscArgValue::Int(i) => Some(Operand::Number(f64::from(*i))),
+            // Go through the shortest decimal form so 0.1f32 equals 0.1
+            OscArgValue::Float(f) => f.to_string().parse().ok().map(Operand::Number),
+            OscArgValue::Double(d) => Some(Operand::Number(*d)),
+            OscArgValue::Int64(i) => Some(Operand::Number(*i as f64)),
+            OscArgValue::Bool(b) => Some(Operand::Number(if *b { 1.0 } else { 0.0 })),
+            OscArgValue::String(s) => Some(Operand::Text(s.clone())),
+            OscArgValue::Char(c) => Some(Operand::Text(c.to_string())),
+            _ => None,
+        },
+        ConditionValue::MidiValue => input.midi_value.map(|v| Operand::Number(f64::from(v))),
+        ConditionValue::MidiNote => input.midi_note.map(|n| Operand::Number(f64::from(n))),
+        ConditionValue::Static { value } => match value {
+            serde_json::Value::Number(n) => n.as_f64().map(Operand::Number),
+            serde_json::Value::String(s) => Some(Operand::Text(s.clone())),
+            serde_json::Value::Bool(b) => Some(Operand::Number(if *b { 1.0 } else { 0.0 })),
+            _ => None,
+        },
+    }
+}
+
+/// Numbers compare numerically and strings lexically. A number and a
+/// string compare numerically when the string parses as a number;
+/// otherwise they are only ever unequal.
+fn compare(left: &Operand, cmp: Comparison, right: &Operand) -> bool {
+    let number = |s: &str| s.trim().parse::<f64>().ok();
+    let ordering = match (left, right) {
+        (Operand::Number(a), Operand::Number(b)) => a.partial_cmp(b),
+        (Operand::Text(a), Operand::Text(b)) => Some(a.cmp(b)),
+        (Operand::Number(a), Operand::Text(b)) => number(b).and_then(|b| a.partial_cmp(&b)),
+        (Operand::Text(a), Operand::Number(b)) => number(a).and_then(|a| a.partial_cmp(b)),
+    };
+    let Some(ordering) = ordering else {
+        return cmp == Comparison::Neq;
+    };
+    match cmp {
+        Comparison::Eq => ordering.is_eq(),
+        Comparison::Neq => ordering.is_ne(),
+        Comparison::Lt => ordering.is_lt(),
+        Comparison::Le => ordering.is_le(),
+        Comparison::Gt => ordering.is_gt(),
+        Comparison::Ge => ordering.is_ge(),
+    }
+}
+
+/// Short human-readable form of a condition, e.g. `arg0 > 0.5`.
+fn describe(condition: &Condition) -> String {
+    match condition {
+        Condition::Range { value, min, max } => {
+            format!("{} in {}–{}", describe_value(value), min, max)
+        }
+        Condition::Compare { left, cmp, right } => {
+            let op = match cmp {
+                Comparison::Eq => "==",
+                Comparison::Neq => "!=",
+                Comparison::Lt => "<",
+                Comparison::Le => "<=",
+                Comparison::Gt => ">",
+                Comparison::Ge => ">=",
+            };
+            format!("{} {} {}", describe_value(left), op, describe_value(right))
+        }
+        Condition::Regex { value, pattern } => {
+            format!("{} matches /{}/", describe_value(value), pattern)
+        }
+    }
+}
+
+fn describe_value(value: &ConditionValue) -> String {
+    match value {
+        ConditionValue::OscArg { index } => format!("arg{}", index),
+        ConditionValue::MidiValue => "value".to_string(),
+        ConditionValue::MidiNote => "note".to_string(),
+        ConditionValue::Static { value } => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn arg(index: usize) -> ConditionValue {
+        ConditionValue::OscArg { index }
+    }
+
+    fn constant(value: serde_json::Value) -> ConditionValue {
+        ConditionValue::Static { value }
+    }
+
+    fn check(conditions: &[Condition], args: &[OscArgValue]) -> Result<(), String> {
+        let input = ConditionInput {
+            args,
+            ..Default::default()
+        };
+        ConditionChecker::default().check(conditions, &input)
+    }
+
+    #[test]
+    fn test_fader_threshold() {
+        let above = Condition::Compare {
+            left: arg(0),
+            cmp: Comparison::Gt,
+            right: constant(serde_json::json!(0.5)),
+        };
+        assert!(check(std::slice::from_ref(&above), &[OscArgValue::Float(0.75)]).is_ok());
+        assert_eq!(
+            check(&[above], &[OscArgValue::Float(0.5)]).unwrap_err(),
+            "Condition failed: arg0 > 0.5"
+        );
+    }
+
+    #[test]
+    fn test_range_is_inclusive() {
+        let range = [Condition::Range {
+            value: arg(0),
+            min: 0.1,
+            max: 0.5,
+        }];
+        assert!(check(&range, &[OscArgValue::Float(0.1)]).is_ok());
+        assert!(check(&range, &[OscArgValue::Int(0)]).is_err());
+        // Missing or non-numeric arguments fail
+        assert!(check(&range, &[]).is_err());
+        assert!(check(&range, &[OscArgValue::String("0.2".into())]).is_err());
+    }
+
+    #[test]
+    fn test_eq_and_neq() {
+        let eq = |value: serde_json::Value| Condition::Compare {
+            left: arg(0),
+            cmp: Comparison::Eq,
+            right: constant(value),
+        };
+        assert!(check(
+            &[eq(serde_json::json!("go"))],
+            &[OscArgValue::String("go".into())]
+        )
+        .is_ok());
+        assert!(check(
+            &[eq(serde_json::json!(3))],
+            &[OscArgValue::String("3".into())]
+        )
+        .is_ok());
+        assert!(check(&[eq(serde_json::json!(true))], &[OscArgValue::Bool(true)]).is_ok());
+        let neq = Condition::Compare {
+            left: arg(0),
+            cmp: Comparison::Neq,
+            right: constant(serde_json::json!(3)),
+        };
+        assert!(check(
+            std::slice::from_ref(&neq),
+            &[OscArgValue::String("stop".into())]
+        )
+        .is_ok());
+        assert!(check(&[neq], &[OscArgValue::Int(3)]).is_err());
+    }
+
+    #[test]
+    fn test_compare_two_args() {
+        let cond = [Condition::Compare {
+            left: arg(0),
+            cmp: Comparison::Ge,
+            right: arg(1),
+        }];
+        assert!(check(&cond, &[OscArgValue::Int(5), OscArgValue::Float(4.5)]).is_ok());
+        assert!(check(&cond, &[OscArgValue::Int(4), OscArgValue::Float(4.5)]).is_err());
+    }
+
+    #[test]
+    fn test_regex() {
+        let cond = [Condition::Regex {
+            value: arg(0),
+            pattern: "^cue-[0-9]+$".to_string(),
+        }];
+        assert!(check(&cond, &[OscArgValue::String("cue-12".into())]).is_ok());
+        assert!(check(&cond, &[OscArgValue::String("cue-x".into())]).is_err());
+
+        // An invalid pattern never matches
+        let bad = [Condition::Regex {
+            value: arg(0),
+            pattern: "(".to_string(),
+        }];
+        assert!(check(&bad, &[OscArgValue::String("(".into())]).is_err());
+    }
+
+    #[test]
+    fn test_midi_value_condition() {
+        let cond = [Condition::Range {
+            value: ConditionValue::MidiValue,
+            min: 64.0,
+            max: 127.0,
+        }];
+        let input = ConditionInput {
+            midi_value: Some(100),
+            ..Default::default()
+        };
+        assert!(ConditionChecker::default().check(&cond, &input).is_ok());
+        let input = ConditionInput {
+            midi_value: Some(10),
+            ..Default::default()
+        };
+        assert_eq!(
+            ConditionChecker::default()
+                .check(&cond, &input)
+                .unwrap_err(),
+            "Condition failed: value in 64–127"
+        );
+    }
+
+    #[test]
+    fn test_condition_json() {
+        let json = r#"{"op": "compare", "left": {"type": "osc_arg", "index": 0}, "cmp": "le", "right": {"type": "static", "value": 0.5}}"#;
+        let cond: Condition = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            cond,
+            Condition::Compare {
+                left: arg(0),
+                cmp: Comparison::Le,
+                right: constant(serde_json::json!(0.5)),
+            }
+        );
+    }
+}
@@ -1,5 +1,6 @@
 use crate::models::{Mapping, Settings};
 use log::{error, info};
+use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
 
@@ -76,6 +77,39 @@ pub fn save_mappings(mappings: &[Mapping]) -> Result<(), String> {
     })
 }
 
+/// Saved toggle/latch states, keyed by mapping ID or state group. A missing
+/// file means nothing has been saved yet.
+pub fn load_mapping_states() -> Result<HashMap<String, bool>, String> {
+    let path = config_dir()?.join("mapping_states.json");
+    if !path.exists() {
+        return Ok(HashMap::new());
+    }
+    let data = fs::read_to_string(&path).map_err(|e| {
+        error!("Failed to read mapping states: {}", e);
+        format!("Failed to read mapping states: {}", e)
+    })?;
+    serde_json::from_str(&data).map_err(|e| {
+        error!("Failed to parse mapping states: {}", e);
+        format!("Failed to parse mapping states: {}", e)
+    })
+}
+
+pub fn save_mapping_states(states: &HashMap<String, bool>) -> Result<(), String> {
+    let dir = config_dir()?;
+    let path = dir.join("mapping_states.json");
+    let tmp_path = dir.join("mapping_states.json.tmp");
+    let data = serde_json::to_string_pretty(states)
+        .map_err(|e| format!("Failed to serialize mapping states: {}", e))?;
+    fs::write(&tmp_path, &data).map_err(|e| {
+        error!("Failed to write temp mapping states file: {}", e);
+        format!("Failed to write temp mapping states file: {}", e)
+    })?;
+    fs::rename(&tmp_path, &path).map_err(|e| {
+        error!("Failed to rename mapping states file: {}", e);
+        format!("Failed to rename mapping states file: {}", e)
+    })
+}
+
 // Functions that accept a custom dir for testing
 #[cfg(test)]
 fn load_settings_from(dir: &PathBuf) -> Result<Settings, String> {
@@ -147,8 +181,10 @@ mod tests {
     fn test_save_and_load_settings() {
         let tmp = tempfile::tempdir().unwrap();
         let dir = tmp.path().join(".sndwrks-conduit");
-        let mut settings = Settings::default();
-        settings.osc_listen_port = 9999;
+        let settings = Settings {
+            osc_listen_port: 9999,
+            ..Default::default()
+        };
         save_settings_to(&dir, &settings).unwrap();
         let loaded = load_settings_from(&dir).unwrap();
         assert_eq!(loaded.osc_listen_port, 9999);
@@ -174,17 +210,37 @@ mod tests {
             direction: Direction::OscToMidi,
             osc_address: "/test".to_string(),
             osc_arg_types: vec![],
+            osc_arg_match: OscArgMatch::Any,
             midi_message_type: MidiMessageType::NoteOn,
-            midi_channel: 1,
-            midi_note_or_cc: 60,
+            midi_channel: MidiField::Fixed(1),
+            midi_note_or_cc: MidiField::Fixed(60),
             midi_velocity_or_value: ValueSource::Static { value: 127 },
             midi_input_velocity: None,
+            midi_input_port: None,
+            midi_output_port: None,
             osc_args: vec![],
             osc_output_address: String::new(),
             osc_transform: None,
+            osc_destinations: vec![],
             msc_device_id: None,
             msc_command_format: None,
             msc_command: None,
+            msc_cue_number: None,
+            msc_cue_list: None,
+            msc_cue_path: None,
+            cc_mode: CcMode::SevenBit,
+            midi_parameter: 0,
+            clock_input: None,
+            transport_state: None,
+            midi_remap: MidiRemap::default(),
+            conditions: vec![],
+            mode: MappingMode::Momentary,
+            state_group: None,
+            persist_state: false,
+            slew: None,
+            output_filter: OutputFilter::default(),
+            steps: vec![],
+            retrigger: Retrigger::default(),
         }];
         save_mappings_to(&dir, &mappings).unwrap();
         let loaded = load_mappings_from(&dir).unwrap();
@@ -202,17 +258,37 @@ mod tests {
             direction: Direction::MidiToOsc,
             osc_address: "/atomic".to_string(),
             osc_arg_types: vec![],
+            osc_arg_match: OscArgMatch::Any,
             midi_message_type: MidiMessageType::Cc,
-            midi_channel: 1,
-            midi_note_or_cc: 7,
+            midi_channel: MidiField::Fixed(1),
+            midi_note_or_cc: MidiField::Fixed(7),
             midi_velocity_or_value: ValueSource::Static { value: 0 },
             midi_input_velocity: None,
+            midi_input_port: None,
+            midi_output_port: None,
             osc_args: vec![],
             osc_output_address: String::new(),
             osc_transform: None,
+            osc_destinations: vec![],
             msc_device_id: None,
             msc_command_format: None,
             msc_command: None,
+            msc_cue_number: None,
+            msc_cue_list: None,
+            msc_cue_path: None,
+            cc_mode: CcMode::SevenBit,
+            midi_parameter: 0,
+            clock_input: None,
+            transport_state: None,
+            midi_remap: MidiRemap::default(),
+            conditions: vec![],
+            mode: MappingMode::Momentary,
+            state_group: None,
+            persist_state: false,
+            slew: None,
+            output_filter: OutputFilter::default(),
+            steps: vec![],
+            retrigger: Retrigger::default(),
         }];
         save_mappings_to(&dir, &mappings).unwrap();
         let tmp_path = dir.join("mappings.json.tmp");
@@ -0,0 +1,485 @@
+use crate::models::{
+    CcMode, ClockInput, Direction, Mapping, MappingMode, MidiField, MidiMessageType, MidiRemap,
+    OscArgDef, OscArgMatch, OscArgSource, OscArgType, OscOutputType, OscTransform, OutputFilter,
+    Retrigger, TransformCurve, ValueSource,
+};
+use crate::router::{msc_command_from_byte, osc_arg_type_of, IncomingMessage, OscArgValue};
+use std::sync::Mutex;
+use tokio::sync::oneshot;
+
+pub const DEFAULT_LEARN_TIMEOUT_MS: u64 = 10_000;
+
+/// What a learn request is waiting for.
+pub enum LearnTarget {
+    /// The next MIDI or MSC message, optionally of one type
+    Midi { filter: Option<MidiMessageType> },
+    /// The next `count` OSC messages sent to the first address seen
+    Osc { count: usize },
+}
+
+struct PendingLearn {
+    target: LearnTarget,
+    captured: Vec<IncomingMessage>,
+    tx: oneshot::Sender<Vec<IncomingMessage>>,
+}
+
+/// A single armed learn request. The router task offers every incoming
+/// message here before routing it; once enough are captured they are handed
+/// to the waiting command and the slot disarms.
+#[derive(Default)]
+pub struct LearnSlot {
+    pending: Mutex<Option<PendingLearn>>,
+}
+
+impl LearnSlot {
+    /// Arm learn mode. A request that is already waiting is cancelled.
+    pub fn arm(&self, target: LearnTarget) -> oneshot::Receiver<Vec<IncomingMessage>> {
+        let (tx, rx) = oneshot::channel();
+        if let Ok(mut pending) = self.pending.lock() {
+            *pending = Some(PendingLearn {
+                target,
+                captured: vec![],
+                tx,
+            });
+        }
+        rx
+    }
+
+    /// Disarm and return whatever was captured so far, for a timed-out OSC
+    /// learn that saw fewer than `count` messages.
+    pub fn take_partial(&self) -> Vec<IncomingMessage> {
+        self.pending
+            .lock()
+            .ok()
+            .and_then(|mut pending| pending.take())
+            .map(|p| p.captured)
+            .unwrap_or_default()
+    }
+
+    /// Disarm learn mode. Returns whether a request was waiting.
+    pub fn cancel(&self) -> bool {
+        self.pending
+            .lock()
+            .map(|mut pending| pending.take().is_some())
+            .unwrap_or(false)
+    }
+
+    /// Capture `msg` if the waiting request wants it.
+    pub fn offer(&self, msg: &IncomingMessage) {
+        let Ok(mut pending) = self.pending.lock() else {
+            return;
+        };
+        let Some(p) = pending.as_mut() else {
+            return;
+        };
+        let done = match (&p.target, msg) {
+            (LearnTarget::Midi { filter }, _) => {
+                if !accepts_midi(filter.as_ref(), msg) {
+                    return;
+                }
+                p.captured.push(msg.clone());
+                true
+            }
+            (LearnTarget::Osc { count }, IncomingMessage::Osc { address, .. }) => {
+                if let Some(IncomingMessage::Osc { address: first, .. }) = p.captured.first() {
+                    if first != address {
+                        return;
+                    }
+                }
+                p.captured.push(msg.clone());
+                p.captured.len() >= *count
+            }
+            (LearnTarget::Osc { .. }, _) => return,
+        };
+        if done {
+            if let Some(p) = pending.take() {
+                let _ = p.tx.send(p.captured);
+            }
+        }
+    }
+}
+
+/// Whether a message can be MIDI-learned. Only single MIDI, MSC and
+/// transport messages are candidates; `filter` restricts the MIDI message
+/// type and excludes transport.
+fn accepts_midi(filter: Option<&MidiMessageType>, msg: &IncomingMessage) -> bool {
+    let message_type = match msg {
+        IncomingMessage::Midi { message_type, .. } => message_type,
+        IncomingMessage::Msc { .. } => &MidiMessageType::Msc,
+        IncomingMessage::Transport { .. } => return filter.is_none(),
+        _ => return false,
+    };
+    filter.is_none_or(|f| f == message_type)
+}
+
+/// Fill the MIDI side of `mapping` from a learned message.
+pub fn apply_learned(mapping: &mut Mapping, msg: &IncomingMessage) {
+    match msg {
+        IncomingMessage::Midi {
+            message_type,
+            channel,
+            note_or_cc,
+            ..
+        } => {
+            mapping.midi_message_type = message_type.clone();
+            mapping.clock_input = None;
+            mapping.midi_channel = MidiField::Fixed(*channel);
+            mapping.midi_note_or_cc = MidiField::Fixed(*note_or_cc);
+        }
+        IncomingMessage::Msc {
+            device_id, command, ..
+        } => {
+            mapping.midi_message_type = MidiMessageType::Msc;
+            mapping.clock_input = None;
+            mapping.msc_device_id = Some(*device_id);
+            mapping.msc_command = msc_command_from_byte(*command);
+        }
+        IncomingMessage::Transport { state, .. } => {
+            mapping.clock_input = Some(ClockInput::Transport);
+            mapping.transport_state = Some(*state);
+        }
+        _ => {}
+    }
+}
+
+/// Fill the OSC side of `mapping` from learned OSC messages: the address,
+/// the first message's argument types, and for `OscToOsc` the transform's
+/// input range from the observed numeric values.
+pub fn apply_osc_learned(mapping: &mut Mapping, msgs: &[IncomingMessage]) {
+    let Some(IncomingMessage::Osc { address, args }) = msgs.first() else {
+        return;
+    };
+    mapping.osc_address = address.clone();
+    if mapping.direction == Direction::MidiToOsc {
+        return;
+    }
+    mapping.osc_arg_types = args.iter().map_while(osc_arg_type_of).collect();
+    if mapping.direction != Direction::OscToOsc {
+        return;
+    }
+    let Some((min, max)) = observed_range(msgs) else {
+        return;
+    };
+    let transform = mapping.osc_transform.get_or_insert_with(|| OscTransform {
+        curve: TransformCurve::Linear,
+        input_min: 0.0,
+        input_max: 1.0,
+        output_min: 0.0,
+        output_max: 1.0,
+        calibration_points: vec![],
+        output_type: OscOutputType::default(),
+        smoothing: 1.0,
+    });
+    transform.input_min = min;
+    transform.input_max = max;
+}
+
+/// Smallest and largest numeric argument across the messages. `None` when
+/// there are no numbers or they never vary, since a zero-width input range
+/// can't be scaled.
+fn observed_range(msgs: &[IncomingMessage]) -> Option<(f64, f64)> {
+    let values = msgs.iter().flat_map(|msg| match msg {
+        IncomingMessage::Osc { args, .. } => args.as_slice(),
+        _ => &[],
+    });
+    let (min, max) = values
+        .filter_map(|arg| match arg {
+            OscArgValue::Int(i) => Some(*i as f64),
+            OscArgValue::Float(f) => Some(*f as f64),
+            OscArgValue::Double(d) => Some(*d),
+            OscArgValue::Int64(i) => Some(*i as f64),
+            _ => None,
+        })
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), v| {
+            (lo.min(v), hi.max(v))
+        });
+    (min < max).then_some((min, max))
+}
+
+fn blank_mapping(direction: Direction) -> Mapping {
+    Mapping {
+        id: String::new(),
+        enabled: true,
+        direction,
+        osc_address: String::new(),
+        osc_arg_types: vec![],
+        osc_arg_match: OscArgMatch::default(),
+        midi_message_type: MidiMessageType::NoteOn,
+        midi_channel: MidiField::Fixed(1),
+        midi_note_or_cc: MidiField::Fixed(60),
+        midi_velocity_or_value: ValueSource::OscArg { index: 0 },
+        midi_input_velocity: None,
+        midi_input_port: None,
+        midi_output_port: None,
+        osc_args: vec![],
+        osc_output_address: String::new(),
+        osc_transform: None,
+        osc_destinations: vec![],
+        msc_device_id: None,
+        msc_command_format: None,
+        msc_command: None,
+        msc_cue_number: None,
+        msc_cue_list: None,
+        msc_cue_path: None,
+        cc_mode: CcMode::SevenBit,
+        midi_parameter: 0,
+        clock_input: None,
+        transport_state: None,
+        midi_remap: MidiRemap::default(),
+        conditions: vec![],
+        mode: MappingMode::Momentary,
+        state_group: None,
+        persist_state: false,
+        slew: None,
+        output_filter: OutputFilter::default(),
+        steps: vec![],
+        retrigger: Retrigger::default(),
+    }
+}
+
+/// A new, unsaved mapping for learned OSC messages.
+pub fn draft_osc_mapping(direction: Direction, msgs: &[IncomingMessage]) -> Mapping {
+    let mut mapping = blank_mapping(direction);
+    apply_osc_learned(&mut mapping, msgs);
+    mapping
+}
+
+/// A new, unsaved `MidiToOsc` mapping for a learned message.
+pub fn draft_mapping(msg: &IncomingMessage) -> Mapping {
+    let (osc_address, osc_args) = match msg {
+        IncomingMessage::Midi {
+            message_type: MidiMessageType::ProgramChange,
+            channel,
+            ..
+        } => (
+            format!("/midi/{}/program_change", channel),
+            vec![OscArgDef {
+                arg_type: OscArgType::Int,
+                source: OscArgSource::MidiNote,
+            }],
+        ),
+        IncomingMessage::Midi {
+            message_type,
+            channel,
+            note_or_cc,
+            ..
+        } => {
+            let kind = serde_json::to_value(message_type)
+                .ok()
+                .and_then(|v| v.as_str().map(str::to_string))
+                .unwrap_or_default();
+            let address = match message_type {
+                MidiMessageType::PitchBend | MidiMessageType::ChannelPressure => {
+                    format!("/midi/{}/{}", channel, kind)
+                }
+                _ => format!("/midi/{}/{}/{}", channel, kind, note_or_cc),
+            };
+            (
+                address,
+                vec![OscArgDef {
+                    arg_type: OscArgType::Float,
+                    source: OscArgSource::MidiValue,
+                }],
+            )
+        }
+        IncomingMessage::Transport { state, .. } => {
+            (format!("/transport/{}", state.as_str()), vec![])
+        }
+        _ => (
+            "/msc".to_string(),
+            vec![OscArgDef {
+                arg_type: OscArgType::String,
+                source: OscArgSource::MscCueNumber { template: None },
+            }],
+        ),
+    };
+    let mut mapping = Mapping {
+        osc_address,
+        osc_args,
+        ..blank_mapping(Direction::MidiToOsc)
+    };
+    apply_learned(&mut mapping, msg);
+    mapping
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{MscCommand, TransportState};
+
+    fn cc(channel: u8, cc: u8) -> IncomingMessage {
+        IncomingMessage::Midi {
+            port: "Keys".to_string(),
+            message_type: MidiMessageType::Cc,
+            channel,
+            note_or_cc: cc,
+            value: 64,
+        }
+    }
+
+    fn msc_go() -> IncomingMessage {
+        IncomingMessage::Msc {
+            port: "Keys".to_string(),
+            device_id: 3,
+            command_format: 0x01,
+            command: 0x01,
+            cue_number: "5".to_string(),
+            cue_list: None,
+            cue_path: None,
+            time: None,
+            set_control: None,
+            set_value: None,
+        }
+    }
+
+    #[test]
+    fn test_learn_captures_first_accepted_message() {
+        let slot = LearnSlot::default();
+        let mut rx = slot.arm(LearnTarget::Midi {
+            filter: Some(MidiMessageType::Msc),
+        });
+        slot.offer(&cc(1, 7));
+        assert!(rx.try_recv().is_err());
+        slot.offer(&msc_go());
+        let captured = rx.try_recv().unwrap();
+        assert!(matches!(captured[..], [IncomingMessage::Msc { .. }]));
+        // Disarmed after one capture
+        assert!(!slot.cancel());
+    }
+
+    #[test]
+    fn test_learn_cancel_and_rearm() {
+        let slot = LearnSlot::default();
+        let mut first = slot.arm(LearnTarget::Midi { filter: None });
+        let mut second = slot.arm(LearnTarget::Osc { count: 1 });
+        assert!(matches!(
+            first.try_recv(),
+            Err(oneshot::error::TryRecvError::Closed)
+        ));
+        assert!(slot.cancel());
+        assert!(matches!(
+            second.try_recv(),
+            Err(oneshot::error::TryRecvError::Closed)
+        ));
+        // Nothing armed: offers are ignored
+        slot.offer(&cc(1, 7));
+    }
+
+    #[test]
+    fn test_draft_from_midi() {
+        let m = draft_mapping(&cc(2, 7));
+        assert_eq!(m.direction, Direction::MidiToOsc);
+        assert_eq!(m.midi_message_type, MidiMessageType::Cc);
+        assert_eq!(m.midi_channel, MidiField::Fixed(2));
+        assert_eq!(m.midi_note_or_cc, MidiField::Fixed(7));
+        assert_eq!(m.osc_address, "/midi/2/cc/7");
+        assert_eq!(m.osc_args[0].source, OscArgSource::MidiValue);
+    }
+
+    #[test]
+    fn test_draft_from_msc() {
+        let m = draft_mapping(&msc_go());
+        assert_eq!(m.midi_message_type, MidiMessageType::Msc);
+        assert_eq!(m.msc_device_id, Some(3));
+        assert_eq!(m.msc_command, Some(MscCommand::Go));
+    }
+
+    #[test]
+    fn test_draft_from_transport() {
+        let m = draft_mapping(&IncomingMessage::Transport {
+            port: "Clock".to_string(),
+            state: TransportState::Start,
+        });
+        assert_eq!(m.clock_input, Some(ClockInput::Transport));
+        assert_eq!(m.transport_state, Some(TransportState::Start));
+        assert_eq!(m.osc_address, "/transport/start");
+        assert!(m.osc_args.is_empty());
+    }
+
+    #[test]
+    fn test_apply_keeps_osc_side() {
+        let mut m = draft_mapping(&cc(1, 1));
+        m.osc_address = "/fader/1".to_string();
+        apply_learned(
+            &mut m,
+            &IncomingMessage::Midi {
+                port: "Keys".to_string(),
+                message_type: MidiMessageType::NoteOn,
+                channel: 10,
+                note_or_cc: 36,
+                value: 100,
+            },
+        );
+        assert_eq!(m.osc_address, "/fader/1");
+        assert_eq!(m.midi_message_type, MidiMessageType::NoteOn);
+        assert_eq!(m.midi_channel, MidiField::Fixed(10));
+        assert_eq!(m.midi_note_or_cc, MidiField::Fixed(36));
+    }
+
+    fn osc(address: &str, args: Vec<OscArgValue>) -> IncomingMessage {
+        IncomingMessage::Osc {
+            address: address.to_string(),
+            args,
+        }
+    }
+
+    #[test]
+    fn test_osc_learn_collects_one_address() {
+        let slot = LearnSlot::default();
+        let mut rx = slot.arm(LearnTarget::Osc { count: 3 });
+        slot.offer(&cc(1, 7));
+        slot.offer(&osc("/fader/1", vec![OscArgValue::Float(0.2)]));
+        slot.offer(&osc("/fader/2", vec![OscArgValue::Float(0.9)]));
+        slot.offer(&osc("/fader/1", vec![OscArgValue::Float(0.4)]));
+        assert!(rx.try_recv().is_err());
+        slot.offer(&osc("/fader/1", vec![OscArgValue::Float(0.3)]));
+        let captured = rx.try_recv().unwrap();
+        assert_eq!(captured.len(), 3);
+        assert!(!slot.cancel());
+    }
+
+    #[test]
+    fn test_osc_learn_partial_on_timeout() {
+        let slot = LearnSlot::default();
+        let _rx = slot.arm(LearnTarget::Osc { count: 10 });
+        slot.offer(&osc("/x", vec![]));
+        assert_eq!(slot.take_partial().len(), 1);
+        assert!(slot.take_partial().is_empty());
+    }
+
+    #[test]
+    fn test_osc_draft_types_and_range() {
+        let msgs = vec![
+            osc(
+                "/fader/1",
+                vec![OscArgValue::Int(12), OscArgValue::String("a".into())],
+            ),
+            osc(
+                "/fader/1",
+                vec![OscArgValue::Int(-4), OscArgValue::String("b".into())],
+            ),
+            osc(
+                "/fader/1",
+                vec![OscArgValue::Int(100), OscArgValue::String("c".into())],
+            ),
+        ];
+        let m = draft_osc_mapping(Direction::OscToOsc, &msgs);
+        assert_eq!(m.osc_address, "/fader/1");
+        assert_eq!(m.osc_arg_types, vec![OscArgType::Int, OscArgType::String]);
+        let t = m.osc_transform.unwrap();
+        assert_eq!((t.input_min, t.input_max), (-4.0, 100.0));
+        assert_eq!((t.output_min, t.output_max), (0.0, 1.0));
+
+        // OSC → MIDI drafts get types but no transform
+        let m = draft_osc_mapping(Direction::OscToMidi, &msgs);
+        assert_eq!(m.osc_arg_types.len(), 2);
+        assert!(m.osc_transform.is_none());
+
+        // A single value gives no range; an existing transform is kept
+        let mut m = draft_osc_mapping(Direction::OscToOsc, &msgs[..1]);
+        assert!(m.osc_transform.is_none());
+        m.osc_transform = draft_osc_mapping(Direction::OscToOsc, &msgs).osc_transform;
+        apply_osc_learned(&mut m, &msgs[..1]);
+        assert_eq!(m.osc_transform.unwrap().input_max, 100.0);
+    }
+}
@@ -1,11 +1,23 @@
 mod commands;
+mod condition;
 mod config;
+mod learn;
+mod loop_guard;
+mod macros;
+mod mapping_state;
 mod midi_engine;
 mod models;
+mod mtc;
 mod osc_engine;
+mod osc_pattern;
+mod osc_tcp;
+mod output_filter;
 mod router;
+mod slew;
 mod state;
 
+use learn::LearnSlot;
+use mapping_state::MappingStates;
 use log::info;
 use state::{AppState, EngineHandle};
 use std::sync::{Arc, Mutex};
@@ -18,6 +30,8 @@ use tauri_plugin_log::{Target, TargetKind, TimezoneStrategy, RotationStrategy};
 pub fn run() {
     let settings = config::load_settings().unwrap_or_default();
     let mappings = config::load_mappings().unwrap_or_default();
+    let mapping_states = MappingStates::new(config::load_mapping_states().unwrap_or_default());
+    mapping_states.retain_persistent(&mappings);
 
     let launch_on_startup = settings.launch_on_startup;
     let mapping_count = mappings.len();
@@ -26,6 +40,10 @@ pub fn run() {
         settings: Arc::new(Mutex::new(settings)),
         mappings: Arc::new(Mutex::new(mappings)),
         engine: Mutex::new(None),
+        learn: Arc::new(LearnSlot::default()),
+        mapping_states: Arc::new(mapping_states),
+        drop_counts: Arc::default(),
+        loop_guard: Arc::default(),
     };
 
     tauri::Builder::default()
@@ -56,12 +74,21 @@ pub fn run() {
             commands::mappings::update_mapping,
             commands::mappings::delete_mapping,
             commands::mappings::reorder_mappings,
+            commands::mappings::get_mapping_states,
+            commands::mappings::reset_mapping_states,
+            commands::mappings::get_drop_counts,
+            commands::engine::get_loop_breakers,
+            commands::engine::reset_loop_breakers,
             commands::midi::list_midi_inputs,
             commands::midi::list_midi_outputs,
             commands::engine::start_engine,
             commands::engine::stop_engine,
             commands::engine::get_engine_status,
+            commands::engine::get_osc_connections,
             commands::engine::send_osc_test_value,
+            commands::learn::start_midi_learn,
+            commands::learn::start_osc_learn,
+            commands::learn::cancel_learn,
             commands::config_io::export_config,
             commands::config_io::import_config,
         ])
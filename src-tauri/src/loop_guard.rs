@@ -0,0 +1,341 @@
+use crate::router::{IncomingMessage, OutputAction};
+use log::error;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// How long after we send a message an identical incoming one counts as
+/// its echo.
+const ECHO_WINDOW: Duration = Duration::from_millis(50);
+/// Most sent messages remembered for echo matching.
+const MAX_SENT: usize = 4096;
+const RATE_WINDOW: Duration = Duration::from_secs(1);
+
+#[derive(Default)]
+struct Inner {
+    /// Fingerprints of recently sent messages, oldest first
+    sent: VecDeque<(String, Instant)>,
+    /// Start of the current rate window and messages seen in it, per input
+    rates: HashMap<String, (Instant, u32)>,
+    max_rate_hz: u32,
+    /// Mappings disabled by a detected loop, with the reason
+    tripped: HashMap<String, String>,
+}
+
+/// Feedback-loop protection shared by the router and the output dispatch.
+/// Lives in `AppState` so the loop breakers can be read and reset from
+/// commands; cleared when the engine starts.
+#[derive(Default)]
+pub struct LoopGuard {
+    inner: Mutex<Inner>,
+}
+
+impl LoopGuard {
+    /// Forget everything and set the per-address rate that counts as a loop.
+    pub fn start(&self, max_rate_hz: u32) {
+        match self.inner.lock() {
+            Ok(mut guard) => {
+                *guard = Inner {
+                    max_rate_hz,
+                    ..Default::default()
+                }
+            }
+            Err(e) => error!("Loop guard mutex poisoned in start(): {}", e),
+        }
+    }
+
+    /// Remember a message we sent so its echo can be recognized.
+    pub fn record_sent(&self, action: &OutputAction, now: Instant) {
+        let Some(fingerprint) = sent_fingerprint(action) else {
+            return;
+        };
+        let Ok(mut guard) = self.inner.lock() else {
+            error!("Loop guard mutex poisoned in record_sent()");
+            return;
+        };
+        prune_sent(&mut guard.sent, now);
+        if guard.sent.len() >= MAX_SENT {
+            guard.sent.pop_front();
+        }
+        guard.sent.push_back((fingerprint, now));
+    }
+
+    /// Whether `msg` is an echo of a message we sent within the echo window.
+    /// Each sent message excuses at most one echo.
+    pub fn is_echo(&self, msg: &IncomingMessage, now: Instant) -> bool {
+        let Some(fingerprint) = received_fingerprint(msg) else {
+            return false;
+        };
+        let Ok(mut guard) = self.inner.lock() else {
+            error!("Loop guard mutex poisoned in is_echo()");
+            return false;
+        };
+        prune_sent(&mut guard.sent, now);
+        match guard.sent.iter().position(|(f, _)| *f == fingerprint) {
+            Some(index) => {
+                guard.sent.remove(index);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Count `msg` against its address and report whether the address is
+    /// above the loop rate in the current window.
+    pub fn is_runaway(&self, msg: &IncomingMessage, now: Instant) -> bool {
+        let Ok(mut guard) = self.inner.lock() else {
+            error!("Loop guard mutex poisoned in is_runaway()");
+            return false;
+        };
+        if guard.max_rate_hz == 0 {
+            return false;
+        }
+        if guard.rates.len() > 1024 {
+            guard
+                .rates
+                .retain(|_, (start, _)| now.duration_since(*start) < RATE_WINDOW);
+        }
+        let max = guard.max_rate_hz;
+        let (start, count) = guard.rates.entry(rate_key(msg)).or_insert((now, 0));
+        if now.duration_since(*start) >= RATE_WINDOW {
+            *start = now;
+            *count = 0;
+        }
+        *count += 1;
+        *count > max
+    }
+
+    /// Disable a mapping. Returns false if it was already tripped.
+    pub fn trip(&self, mapping_id: &str, reason: String) -> bool {
+        match self.inner.lock() {
+            Ok(mut guard) => {
+                if guard.tripped.contains_key(mapping_id) {
+                    return false;
+                }
+                guard.tripped.insert(mapping_id.to_string(), reason);
+                true
+            }
+            Err(e) => {
+                error!("Loop guard mutex poisoned in trip(): {}", e);
+                false
+            }
+        }
+    }
+
+    pub fn is_tripped(&self, mapping_id: &str) -> bool {
+        match self.inner.lock() {
+            Ok(guard) => guard.tripped.contains_key(mapping_id),
+            Err(e) => {
+                error!("Loop guard mutex poisoned in is_tripped(): {}", e);
+                false
+            }
+        }
+    }
+
+    /// Tripped mappings and why, keyed by mapping ID.
+    pub fn tripped(&self) -> HashMap<String, String> {
+        match self.inner.lock() {
+            Ok(guard) => guard.tripped.clone(),
+            Err(e) => {
+                error!("Loop guard mutex poisoned in tripped(): {}", e);
+                HashMap::new()
+            }
+        }
+    }
+
+    /// Re-enable one tripped mapping, or all of them when `mapping_id` is
+    /// `None`.
+    pub fn reset(&self, mapping_id: Option<&str>) -> Result<(), String> {
+        let mut guard = self.inner.lock().map_err(|e| e.to_string())?;
+        match mapping_id {
+            Some(id) => {
+                guard.tripped.remove(id);
+            }
+            None => guard.tripped.clear(),
+        }
+        guard.rates.clear();
+        Ok(())
+    }
+}
+
+fn prune_sent(sent: &mut VecDeque<(String, Instant)>, now: Instant) {
+    while sent
+        .front()
+        .is_some_and(|(_, at)| now.duration_since(*at) > ECHO_WINDOW)
+    {
+        sent.pop_front();
+    }
+}
+
+/// Identifies a message on the wire, ignoring ports so a message looped
+/// through a virtual cable matches what we sent. MSC and MTC are not
+/// tracked.
+fn sent_fingerprint(action: &OutputAction) -> Option<String> {
+    match action {
+        OutputAction::Osc { address, args, .. } => Some(format!("osc {} {:?}", address, args)),
+        OutputAction::Midi {
+            message_type,
+            channel,
+            note_or_cc,
+            value,
+            ..
+        } => Some(format!(
+            "midi {:?} {} {} {}",
+            message_type, channel, note_or_cc, value
+        )),
+        OutputAction::Cc14 {
+            mode,
+            channel,
+            parameter,
+            value,
+            ..
+        } => Some(format!(
+            "cc14 {:?} {} {} {}",
+            mode, channel, parameter, value
+        )),
+        OutputAction::Msc { .. } => None,
+    }
+}
+
+fn received_fingerprint(msg: &IncomingMessage) -> Option<String> {
+    match msg {
+        IncomingMessage::Osc { address, args } => Some(format!("osc {} {:?}", address, args)),
+        IncomingMessage::Midi {
+            message_type,
+            channel,
+            note_or_cc,
+            value,
+            ..
+        } => Some(format!(
+            "midi {:?} {} {} {}",
+            message_type, channel, note_or_cc, value
+        )),
+        IncomingMessage::Cc14 {
+            mode,
+            channel,
+            parameter,
+            value,
+            ..
+        } => Some(format!(
+            "cc14 {:?} {} {} {}",
+            mode, channel, parameter, value
+        )),
+        IncomingMessage::Msc { .. }
+        | IncomingMessage::Mtc { .. }
+        | IncomingMessage::Tempo { .. }
+        | IncomingMessage::Beat { .. }
+        | IncomingMessage::Transport { .. } => None,
+    }
+}
+
+/// The "address" an incoming message's rate is measured on.
+fn rate_key(msg: &IncomingMessage) -> String {
+    match msg {
+        IncomingMessage::Osc { address, .. } => address.clone(),
+        IncomingMessage::Midi {
+            port,
+            message_type,
+            channel,
+            note_or_cc,
+            ..
+        } => format!("{} {:?} {} {}", port, message_type, channel, note_or_cc),
+        IncomingMessage::Cc14 {
+            port,
+            mode,
+            channel,
+            parameter,
+            ..
+        } => format!("{} {:?} {} {}", port, mode, channel, parameter),
+        IncomingMessage::Msc { port, .. } => format!("{} msc", port),
+        IncomingMessage::Mtc { port, .. } => format!("{} mtc", port),
+        IncomingMessage::Tempo { port, .. }
+        | IncomingMessage::Beat { port, .. }
+        | IncomingMessage::Transport { port, .. } => format!("{} clock", port),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::MidiMessageType;
+    use crate::router::OscArgValue;
+
+    fn osc_in(value: f32) -> IncomingMessage {
+        IncomingMessage::Osc {
+            address: "/fader".to_string(),
+            args: vec![OscArgValue::Float(value)],
+        }
+    }
+
+    fn osc_out(value: f32) -> OutputAction {
+        OutputAction::Osc {
+            address: "/fader".to_string(),
+            args: vec![OscArgValue::Float(value)],
+            destinations: vec!["desk".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_echo_is_ignored_once_within_window() {
+        let guard = LoopGuard::default();
+        let t0 = Instant::now();
+        guard.record_sent(&osc_out(0.5), t0);
+
+        assert!(!guard.is_echo(&osc_in(0.4), t0));
+        assert!(guard.is_echo(&osc_in(0.5), t0 + Duration::from_millis(5)));
+        // Only one echo per message sent
+        assert!(!guard.is_echo(&osc_in(0.5), t0 + Duration::from_millis(6)));
+
+        guard.record_sent(&osc_out(0.5), t0);
+        assert!(!guard.is_echo(&osc_in(0.5), t0 + Duration::from_millis(100)));
+    }
+
+    #[test]
+    fn test_midi_echo_ignores_ports() {
+        let guard = LoopGuard::default();
+        let now = Instant::now();
+        guard.record_sent(
+            &OutputAction::Midi {
+                port: Some("Out".to_string()),
+                message_type: MidiMessageType::Cc,
+                channel: 1,
+                note_or_cc: 7,
+                value: 64,
+            },
+            now,
+        );
+        assert!(guard.is_echo(
+            &IncomingMessage::Midi {
+                port: "IAC Bus 1".to_string(),
+                message_type: MidiMessageType::Cc,
+                channel: 1,
+                note_or_cc: 7,
+                value: 64,
+            },
+            now
+        ));
+    }
+
+    #[test]
+    fn test_runaway_rate() {
+        let guard = LoopGuard::default();
+        guard.start(100);
+        let t0 = Instant::now();
+        for i in 0..100 {
+            assert!(!guard.is_runaway(&osc_in(i as f32), t0));
+        }
+        assert!(guard.is_runaway(&osc_in(0.0), t0));
+        // A new window starts the count over
+        assert!(!guard.is_runaway(&osc_in(0.0), t0 + RATE_WINDOW));
+    }
+
+    #[test]
+    fn test_trip_and_reset() {
+        let guard = LoopGuard::default();
+        assert!(guard.trip("m", "loop".to_string()));
+        assert!(!guard.trip("m", "loop".to_string()));
+        assert!(guard.is_tripped("m"));
+        guard.reset(Some("m")).unwrap();
+        assert!(!guard.is_tripped("m"));
+    }
+}
@@ -0,0 +1,179 @@
+use crate::models::Retrigger;
+use crate::router::OutputAction;
+use crate::slew::Dispatch;
+use log::error;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+
+/// An output sent `delay` after the one before it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimedOutput {
+    pub delay: Duration,
+    pub action: OutputAction,
+}
+
+/// A sequence waiting its turn, with the token that cancels it.
+type Sequence = (CancellationToken, Vec<TimedOutput>);
+
+struct MacroRun {
+    /// Cancels the sequences started since the last `Cancel` retrigger
+    cancel: CancellationToken,
+    /// Feeds the mapping's worker, which plays sequences in trigger order
+    queue: mpsc::UnboundedSender<Sequence>,
+}
+
+/// Runs the output sequences of macro mappings, one at a time per mapping.
+pub struct MacroRunner {
+    runs: Mutex<HashMap<String, MacroRun>>,
+    dispatch: Dispatch,
+    token: CancellationToken,
+}
+
+impl MacroRunner {
+    pub fn new(dispatch: Dispatch, token: CancellationToken) -> Self {
+        Self {
+            runs: Mutex::new(HashMap::new()),
+            dispatch,
+            token,
+        }
+    }
+
+    /// Start a mapping's sequence. With `Retrigger::Cancel` a sequence still
+    /// running for the mapping stops first; with `Retrigger::Queue` this one
+    /// starts when the earlier ones are done.
+    pub fn trigger(&self, mapping_id: &str, retrigger: Retrigger, steps: Vec<TimedOutput>) {
+        let mut runs = match self.runs.lock() {
+            Ok(guard) => guard,
+            Err(e) => {
+                error!("Macro runs mutex poisoned in trigger(): {}", e);
+                return;
+            }
+        };
+        let run = runs.entry(mapping_id.to_string()).or_insert_with(|| {
+            let (queue, sequences) = mpsc::unbounded_channel();
+            tokio::spawn(run_sequences(
+                sequences,
+                self.dispatch.clone(),
+                self.token.clone(),
+            ));
+            MacroRun {
+                cancel: self.token.child_token(),
+                queue,
+            }
+        });
+        if retrigger == Retrigger::Cancel {
+            run.cancel.cancel();
+            run.cancel = self.token.child_token();
+        }
+        let _ = run.queue.send((run.cancel.clone(), steps));
+    }
+}
+
+/// Play one mapping's sequences in the order they were triggered. Cancelled
+/// sequences are skipped or cut short.
+async fn run_sequences(
+    mut sequences: mpsc::UnboundedReceiver<Sequence>,
+    dispatch: Dispatch,
+    token: CancellationToken,
+) {
+    loop {
+        let (cancel, steps) = tokio::select! {
+            _ = token.cancelled() => return,
+            next = sequences.recv() => match next {
+                Some(sequence) => sequence,
+                None => return,
+            },
+        };
+        for step in steps {
+            if !step.delay.is_zero() {
+                tokio::select! {
+                    _ = cancel.cancelled() => break,
+                    _ = tokio::time::sleep(step.delay) => {}
+                }
+            }
+            if cancel.is_cancelled() {
+                break;
+            }
+            dispatch(&step.action);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::router::OscArgValue;
+    use std::sync::Arc;
+
+    fn step(delay_ms: u64, address: &str) -> TimedOutput {
+        TimedOutput {
+            delay: Duration::from_millis(delay_ms),
+            action: OutputAction::Osc {
+                address: address.to_string(),
+                args: vec![OscArgValue::Int(1)],
+                destinations: vec![],
+            },
+        }
+    }
+
+    fn runner() -> (MacroRunner, Arc<Mutex<Vec<String>>>) {
+        let sent: Arc<Mutex<Vec<String>>> = Arc::default();
+        let sink = sent.clone();
+        let dispatch: Dispatch = Arc::new(move |action| {
+            if let OutputAction::Osc { address, .. } = action {
+                sink.lock().unwrap().push(address.clone());
+            }
+        });
+        (MacroRunner::new(dispatch, CancellationToken::new()), sent)
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_steps_run_in_order() {
+        let (runner, sent) = runner();
+        runner.trigger(
+            "m",
+            Retrigger::Cancel,
+            vec![step(0, "/eos/go"), step(20, "/qlab/go"), step(0, "/cc")],
+        );
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        assert_eq!(*sent.lock().unwrap(), vec!["/eos/go"]);
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        assert_eq!(*sent.lock().unwrap(), vec!["/eos/go", "/qlab/go", "/cc"]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_cancel_retrigger_stops_running_sequence() {
+        let (runner, sent) = runner();
+        runner.trigger("m", Retrigger::Cancel, vec![step(0, "/a"), step(30, "/b")]);
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        runner.trigger("m", Retrigger::Cancel, vec![step(0, "/c"), step(30, "/d")]);
+        tokio::time::sleep(Duration::from_millis(80)).await;
+        assert_eq!(*sent.lock().unwrap(), vec!["/a", "/c", "/d"]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_queue_retrigger_waits_for_running_sequence() {
+        let (runner, sent) = runner();
+        runner.trigger("m", Retrigger::Queue, vec![step(0, "/a"), step(30, "/b")]);
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        runner.trigger("m", Retrigger::Queue, vec![step(0, "/c")]);
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert_eq!(*sent.lock().unwrap(), vec!["/a"]);
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        assert_eq!(*sent.lock().unwrap(), vec!["/a", "/b", "/c"]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_queued_sequences_keep_trigger_order() {
+        let (runner, sent) = runner();
+        let addresses: Vec<String> = (0..20).map(|i| format!("/{}", i)).collect();
+        for address in &addresses {
+            runner.trigger("m", Retrigger::Queue, vec![step(0, address)]);
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(*sent.lock().unwrap(), addresses);
+    }
+}
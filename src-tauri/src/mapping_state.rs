@@ -0,0 +1,266 @@
+use crate::config;
+use crate::models::{Mapping, MappingMode};
+use log::{error, warn};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::Notify;
+use tokio_util::sync::CancellationToken;
+
+/// How long to wait after a persisted state changes before writing it, so a
+/// burst of toggles becomes one write.
+const SAVE_DELAY: Duration = Duration::from_millis(500);
+
+/// What a press or release does to a stateful mapping's output.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Transition {
+    /// Momentary: send the incoming value unchanged
+    Passthrough,
+    /// Send the new state as on/off
+    Set(bool),
+    /// Send nothing
+    Ignore,
+}
+
+/// On/off state of every mapping, keyed by mapping ID or `state_group`.
+/// Lives in `AppState` so it outlives the engine.
+#[derive(Default)]
+pub struct MappingStates {
+    states: Mutex<HashMap<String, bool>>,
+    /// Signalled when a persisted state changed and needs saving
+    unsaved: Notify,
+}
+
+impl MappingStates {
+    pub fn new(saved: HashMap<String, bool>) -> Self {
+        Self {
+            states: Mutex::new(saved),
+            unsaved: Notify::new(),
+        }
+    }
+
+    /// Ask `save_changes` to write the states to disk soon.
+    pub fn mark_unsaved(&self) {
+        self.unsaved.notify_one();
+    }
+
+    /// Apply a press (`true`) or release (`false`) to the mapping's state.
+    pub fn update(&self, mapping: &Mapping, press: bool) -> Transition {
+        // Momentary mappings have no state to track
+        if mapping.mode == MappingMode::Momentary {
+            return Transition::Passthrough;
+        }
+        let mut states = match self.states.lock() {
+            Ok(guard) => guard,
+            Err(e) => {
+                error!("Mapping states mutex poisoned in update(): {}", e);
+                return Transition::Ignore;
+            }
+        };
+        let key = state_key(mapping);
+        let current = states.get(key).copied().unwrap_or(false);
+        let transition = next_state(mapping.mode, current, press);
+        if let Transition::Set(on) = transition {
+            states.insert(key.to_string(), on);
+        }
+        transition
+    }
+
+    pub fn snapshot(&self) -> HashMap<String, bool> {
+        match self.states.lock() {
+            Ok(guard) => guard.clone(),
+            Err(e) => {
+                error!("Mapping states mutex poisoned in snapshot(): {}", e);
+                HashMap::new()
+            }
+        }
+    }
+
+    /// The states owned by `persist_state` mappings, as written to disk.
+    pub fn persistent_snapshot(&self, mappings: &[Mapping]) -> HashMap<String, bool> {
+        let keep = persistent_keys(mappings);
+        let mut states = self.snapshot();
+        states.retain(|key, _| keep.contains(key.as_str()));
+        states
+    }
+
+    /// Turn one state off, or all of them when `key` is `None`.
+    pub fn reset(&self, key: Option<&str>) -> Result<(), String> {
+        let mut states = self.states.lock().map_err(|e| e.to_string())?;
+        match key {
+            Some(key) => {
+                states.remove(key);
+            }
+            None => states.clear(),
+        }
+        Ok(())
+    }
+
+    /// Drop every state not owned by a `persist_state` mapping. Called when
+    /// the engine starts so only persisted states survive a restart.
+    pub fn retain_persistent(&self, mappings: &[Mapping]) {
+        let keep = persistent_keys(mappings);
+        match self.states.lock() {
+            Ok(mut guard) => guard.retain(|key, _| keep.contains(key.as_str())),
+            Err(e) => error!(
+                "Mapping states mutex poisoned in retain_persistent(): {}",
+                e
+            ),
+        }
+    }
+}
+
+/// Write the states to disk shortly after `mark_unsaved`, off the router
+/// task. A pending save is still written when the engine stops.
+pub async fn save_changes(
+    states: Arc<MappingStates>,
+    mappings: Arc<Mutex<Vec<Mapping>>>,
+    token: CancellationToken,
+) {
+    loop {
+        tokio::select! {
+            biased;
+            _ = states.unsaved.notified() => {}
+            _ = token.cancelled() => return,
+        }
+        tokio::select! {
+            _ = token.cancelled() => {}
+            _ = tokio::time::sleep(SAVE_DELAY) => {}
+        }
+        let snapshot = match mappings.lock() {
+            Ok(guard) => states.persistent_snapshot(&guard),
+            Err(e) => {
+                error!("Mappings mutex poisoned in save_changes(): {}", e);
+                continue;
+            }
+        };
+        match tokio::task::spawn_blocking(move || config::save_mapping_states(&snapshot)).await {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => warn!("Failed to save mapping state: {}", e),
+            Err(e) => error!("Mapping state save task failed: {}", e),
+        }
+        if token.is_cancelled() {
+            return;
+        }
+    }
+}
+
+/// The key a mapping's state is stored under.
+pub fn state_key(mapping: &Mapping) -> &str {
+    mapping.state_group.as_deref().unwrap_or(&mapping.id)
+}
+
+fn persistent_keys(mappings: &[Mapping]) -> HashSet<&str> {
+    mappings
+        .iter()
+        .filter(|m| m.persist_state && m.mode != MappingMode::Momentary)
+        .map(state_key)
+        .collect()
+}
+
+fn next_state(mode: MappingMode, current: bool, press: bool) -> Transition {
+    match mode {
+        MappingMode::Momentary => Transition::Passthrough,
+        MappingMode::Toggle if press => Transition::Set(!current),
+        MappingMode::LatchOn if press && !current => Transition::Set(true),
+        MappingMode::LatchOff if press && current => Transition::Set(false),
+        _ => Transition::Ignore,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::*;
+
+    fn mapping(id: &str, mode: MappingMode) -> Mapping {
+        Mapping {
+            id: id.to_string(),
+            enabled: true,
+            direction: Direction::OscToMidi,
+            osc_address: "/pad".to_string(),
+            osc_arg_types: vec![],
+            osc_arg_match: OscArgMatch::Any,
+            midi_message_type: MidiMessageType::NoteOn,
+            midi_channel: MidiField::Fixed(1),
+            midi_note_or_cc: MidiField::Fixed(60),
+            midi_velocity_or_value: ValueSource::Static { value: 127 },
+            midi_input_velocity: None,
+            midi_input_port: None,
+            midi_output_port: None,
+            osc_args: vec![],
+            osc_output_address: String::new(),
+            osc_transform: None,
+            osc_destinations: vec![],
+            msc_device_id: None,
+            msc_command_format: None,
+            msc_command: None,
+            msc_cue_number: None,
+            msc_cue_list: None,
+            msc_cue_path: None,
+            cc_mode: CcMode::SevenBit,
+            midi_parameter: 0,
+            clock_input: None,
+            transport_state: None,
+            midi_remap: MidiRemap::default(),
+            conditions: vec![],
+            mode,
+            state_group: None,
+            persist_state: false,
+            slew: None,
+            output_filter: OutputFilter::default(),
+            steps: vec![],
+            retrigger: Retrigger::default(),
+        }
+    }
+
+    #[test]
+    fn test_toggle_flips_on_press_only() {
+        let states = MappingStates::default();
+        let m = mapping("a", MappingMode::Toggle);
+        assert_eq!(states.update(&m, true), Transition::Set(true));
+        assert_eq!(states.update(&m, false), Transition::Ignore);
+        assert_eq!(states.update(&m, true), Transition::Set(false));
+        assert_eq!(states.snapshot().get("a"), Some(&false));
+    }
+
+    #[test]
+    fn test_latches_share_a_group() {
+        let states = MappingStates::default();
+        let mut on = mapping("on", MappingMode::LatchOn);
+        let mut off = mapping("off", MappingMode::LatchOff);
+        on.state_group = Some("rec".to_string());
+        off.state_group = Some("rec".to_string());
+
+        assert_eq!(states.update(&off, true), Transition::Ignore);
+        assert_eq!(states.update(&on, true), Transition::Set(true));
+        assert_eq!(states.update(&on, true), Transition::Ignore);
+        assert_eq!(states.update(&off, true), Transition::Set(false));
+        assert_eq!(states.snapshot().get("rec"), Some(&false));
+    }
+
+    #[test]
+    fn test_reset_and_retain_persistent() {
+        let mut kept = mapping("kept", MappingMode::Toggle);
+        kept.persist_state = true;
+        let dropped = mapping("dropped", MappingMode::Toggle);
+        let states = MappingStates::default();
+        states.update(&kept, true);
+        states.update(&dropped, true);
+        assert_eq!(
+            states.persistent_snapshot(&[kept.clone(), dropped.clone()]),
+            HashMap::from([("kept".to_string(), true)])
+        );
+
+        states.retain_persistent(&[kept.clone(), dropped]);
+        assert_eq!(
+            states.snapshot(),
+            HashMap::from([("kept".to_string(), true)])
+        );
+
+        states.reset(Some("kept")).unwrap();
+        assert!(states.snapshot().is_empty());
+        // Reset state reads as off, so the next toggle turns it on
+        assert_eq!(states.update(&kept, true), Transition::Set(true));
+    }
+}
@@ -1,6 +1,7 @@
-use crate::models::MidiMessageType;
+use crate::models::{CcMode, MidiMessageType, MtcRate, TransportState};
 use crate::router::IncomingMessage;
 use midir::{MidiInput, MidiInputConnection, MidiOutput, MidiOutputConnection};
+use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 use tokio::sync::mpsc;
 
@@ -8,9 +9,21 @@ pub struct ParsedMidi {
     pub message_type: MidiMessageType,
     pub channel: u8,
     pub note_or_cc: u8,
-    pub value: u8,
+    /// 0–127, or 0–16383 for pitch bend
+    pub value: u16,
 }
 
+/// A 14-bit controller value assembled from several CC messages.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParsedCc14 {
+    pub mode: CcMode,
+    pub channel: u8,
+    /// MSB controller number (0–31), or the NRPN/RPN parameter number
+    pub parameter: u16,
+    pub value: u16,
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct ParsedMsc {
     pub device_id: u8,
     pub command_format: u8,
@@ -18,11 +31,91 @@ pub struct ParsedMsc {
     pub cue_number: String,
     pub cue_list: Option<String>,
     pub cue_path: Option<String>,
+    /// TIMED_GO, SET and SET_CLOCK time
+    pub time: Option<MscTime>,
+    /// SET control number and value (14-bit each)
+    pub set_control: Option<u16>,
+    pub set_value: Option<u16>,
+}
+
+/// MSC time field: `hr mn sc fr ff`, the same layout as an MTC full frame.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MscTime {
+    pub hours: u8,
+    pub minutes: u8,
+    pub seconds: u8,
+    pub frames: u8,
+    pub subframes: u8,
+    /// Frame rate code from the hour byte: 0 = 24, 1 = 25, 2 = 29.97 drop, 3 = 30
+    pub rate: u8,
+}
+
+impl MscTime {
+    const ZERO: MscTime = MscTime {
+        hours: 0,
+        minutes: 0,
+        seconds: 0,
+        frames: 0,
+        subframes: 0,
+        rate: 0,
+    };
+
+    fn parse(bytes: &[u8]) -> Option<Self> {
+        let [hr, mn, sc, fr, ff] = *bytes.get(..5)? else {
+            return None;
+        };
+        Some(Self {
+            hours: hr & 0x1F,
+            minutes: mn & 0x3F,
+            seconds: sc & 0x3F,
+            frames: fr & 0x1F,
+            // With bit 5 of `fr` set, `ff` carries status bits instead of subframes
+            subframes: if fr & 0x20 == 0 { ff & 0x7F } else { 0 },
+            rate: (hr >> 5) & 0x03,
+        })
+    }
+
+    pub fn fps(&self) -> f64 {
+        match self.rate {
+            0 => 24.0,
+            1 => 25.0,
+            2 => 29.97,
+            _ => 30.0,
+        }
+    }
+
+    /// Position in seconds, including frames and subframes (1/100 frame).
+    pub fn as_seconds(&self) -> f64 {
+        let whole = self.hours as f64 * 3600.0 + self.minutes as f64 * 60.0 + self.seconds as f64;
+        whole + (self.frames as f64 + self.subframes as f64 / 100.0) / self.fps()
+    }
+}
+
+impl std::fmt::Display for MscTime {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:02}:{:02}:{:02}:{:02}.{:02}",
+            self.hours, self.minutes, self.seconds, self.frames, self.subframes
+        )
+    }
+}
+
+/// Commands whose only data is a cue list (START_CLOCK … CLOSE_CUE_LIST).
+fn msc_takes_cue_list(command: u8) -> bool {
+    (0x15..=0x1C).contains(&command)
+}
+
+/// OPEN_CUE_PATH / CLOSE_CUE_PATH carry only a cue path.
+fn msc_takes_cue_path(command: u8) -> bool {
+    matches!(command, 0x1D | 0x1E)
 }
 
 /// Parse an MSC (MIDI Show Control) SysEx message.
-/// Format: F0 7F <device_id> 02 <cmd_format> <command> [cue data...] F7
-/// Cue data fields are ASCII strings separated by 0x00.
+/// Format: F0 7F <device_id> 02 <cmd_format> <command> [data...] F7
+/// Cue data fields are ASCII strings separated by 0x00. TIMED_GO and
+/// SET_CLOCK start with a 5-byte time; SET carries a 14-bit control number
+/// and value (LSB first) followed by an optional time.
 pub fn parse_msc_sysex(bytes: &[u8]) -> Option<ParsedMsc> {
     // Minimum: F0 7F id 02 fmt cmd F7 = 7 bytes
     if bytes.len() < 7 {
@@ -41,32 +134,130 @@ pub fn parse_msc_sysex(bytes: &[u8]) -> Option<ParsedMsc> {
     let device_id = bytes[2];
     let command_format = bytes[4];
     let command = bytes[5];
+    let mut data = &bytes[6..bytes.len() - 1];
 
-    // Cue data is bytes[6..len-1], split by 0x00 delimiters
-    let cue_data = &bytes[6..bytes.len() - 1];
-    let parts: Vec<&[u8]> = cue_data.split(|&b| b == 0x00).collect();
-
-    let cue_number = parts
-        .first()
-        .map(|p| String::from_utf8_lossy(p).to_string())
-        .unwrap_or_default();
-    let cue_list = parts
-        .get(1)
-        .filter(|p| !p.is_empty())
-        .map(|p| String::from_utf8_lossy(p).to_string());
-    let cue_path = parts
-        .get(2)
-        .filter(|p| !p.is_empty())
-        .map(|p| String::from_utf8_lossy(p).to_string());
-
-    Some(ParsedMsc {
+    let mut msc = ParsedMsc {
         device_id,
         command_format,
         command,
-        cue_number,
-        cue_list,
-        cue_path,
-    })
+        cue_number: String::new(),
+        cue_list: None,
+        cue_path: None,
+        time: None,
+        set_control: None,
+        set_value: None,
+    };
+
+    match command {
+        // TIMED_GO, SET_CLOCK
+        0x04 | 0x18 => {
+            msc.time = MscTime::parse(data);
+            data = data.get(5..).unwrap_or_default();
+        }
+        // SET
+        0x06 => {
+            let [c_lsb, c_msb, v_lsb, v_msb] = *data.get(..4)? else {
+                return None;
+            };
+            msc.set_control = Some(u16::from(c_lsb & 0x7F) | u16::from(c_msb & 0x7F) << 7);
+            msc.set_value = Some(u16::from(v_lsb & 0x7F) | u16::from(v_msb & 0x7F) << 7);
+            msc.time = MscTime::parse(&data[4..]);
+            return Some(msc);
+        }
+        // FIRE carries a single macro number byte
+        0x07 => {
+            if let Some(macro_number) = data.first() {
+                msc.cue_number = (macro_number & 0x7F).to_string();
+            }
+            return Some(msc);
+        }
+        _ => {}
+    }
+
+    // Cue data is split by 0x00 delimiters
+    let parts: Vec<String> = data
+        .split(|&b| b == 0x00)
+        .map(|p| String::from_utf8_lossy(p).to_string())
+        .collect();
+    let field = |i: usize| parts.get(i).filter(|p| !p.is_empty()).cloned();
+
+    if msc_takes_cue_list(command) {
+        msc.cue_list = field(0);
+    } else if msc_takes_cue_path(command) {
+        msc.cue_path = field(0);
+    } else {
+        msc.cue_number = parts.first().cloned().unwrap_or_default();
+        msc.cue_list = field(1);
+        msc.cue_path = field(2);
+    }
+    Some(msc)
+}
+
+/// Build an MSC SysEx message, the inverse of `parse_msc_sysex`.
+/// A cue list is only written after a cue number, and a cue path after a
+/// list. TIMED_GO and SET_CLOCK without a time send 00:00:00:00.00.
+pub fn build_msc_sysex(msc: &ParsedMsc) -> Vec<u8> {
+    let command = msc.command;
+    let mut bytes = vec![
+        0xF0,
+        0x7F,
+        msc.device_id & 0x7F,
+        0x02,
+        msc.command_format,
+        command,
+    ];
+    let push_time = |bytes: &mut Vec<u8>, time: &MscTime| {
+        bytes.extend_from_slice(&[
+            (time.rate & 0x03) << 5 | (time.hours & 0x1F),
+            time.minutes & 0x3F,
+            time.seconds & 0x3F,
+            time.frames & 0x1F,
+            time.subframes & 0x7F,
+        ]);
+    };
+    match command {
+        0x04 | 0x18 => push_time(&mut bytes, msc.time.as_ref().unwrap_or(&MscTime::ZERO)),
+        0x06 => {
+            let control = msc.set_control.unwrap_or(0);
+            let value = msc.set_value.unwrap_or(0);
+            bytes.extend_from_slice(&[
+                (control & 0x7F) as u8,
+                (control >> 7 & 0x7F) as u8,
+                (value & 0x7F) as u8,
+                (value >> 7 & 0x7F) as u8,
+            ]);
+            if let Some(time) = &msc.time {
+                push_time(&mut bytes, time);
+            }
+        }
+        0x07 => {
+            if let Ok(macro_number) = msc.cue_number.parse::<u8>() {
+                bytes.push(macro_number & 0x7F);
+            }
+        }
+        _ => {}
+    }
+    if msc_takes_cue_list(command) {
+        if let Some(list) = &msc.cue_list {
+            bytes.extend_from_slice(list.as_bytes());
+        }
+    } else if msc_takes_cue_path(command) {
+        if let Some(path) = &msc.cue_path {
+            bytes.extend_from_slice(path.as_bytes());
+        }
+    } else if !matches!(command, 0x06 | 0x07) && !msc.cue_number.is_empty() {
+        bytes.extend_from_slice(msc.cue_number.as_bytes());
+        if let Some(list) = &msc.cue_list {
+            bytes.push(0x00);
+            bytes.extend_from_slice(list.as_bytes());
+            if let Some(path) = &msc.cue_path {
+                bytes.push(0x00);
+                bytes.extend_from_slice(path.as_bytes());
+            }
+        }
+    }
+    bytes.push(0xF7);
+    bytes
 }
 
 /// Parse a MIDI message from raw bytes.
@@ -108,7 +299,7 @@ pub fn parse_midi_message(bytes: &[u8], last_status: &mut Option<u8>) -> Option<
                     message_type: MidiMessageType::NoteOn,
                     channel,
                     note_or_cc: note,
-                    value: velocity,
+                    value: velocity as u16,
                 })
             }
         }
@@ -120,7 +311,7 @@ pub fn parse_midi_message(bytes: &[u8], last_status: &mut Option<u8>) -> Option<
                 message_type: MidiMessageType::NoteOff,
                 channel,
                 note_or_cc: note,
-                value: velocity,
+                value: velocity as u16,
             })
         }
         0xB0 => {
@@ -131,7 +322,7 @@ pub fn parse_midi_message(bytes: &[u8], last_status: &mut Option<u8>) -> Option<
                 message_type: MidiMessageType::Cc,
                 channel,
                 note_or_cc: cc,
-                value,
+                value: value as u16,
             })
         }
         0xC0 => {
@@ -144,6 +335,38 @@ pub fn parse_midi_message(bytes: &[u8], last_status: &mut Option<u8>) -> Option<
                 value: 0,
             })
         }
+        0xA0 => {
+            // Poly Aftertouch
+            let note = *bytes.get(data_start)?;
+            let pressure = *bytes.get(data_start + 1)?;
+            Some(ParsedMidi {
+                message_type: MidiMessageType::PolyAftertouch,
+                channel,
+                note_or_cc: note,
+                value: pressure as u16,
+            })
+        }
+        0xD0 => {
+            // Channel Pressure (2-byte message: status + pressure)
+            let pressure = *bytes.get(data_start)?;
+            Some(ParsedMidi {
+                message_type: MidiMessageType::ChannelPressure,
+                channel,
+                note_or_cc: 0,
+                value: pressure as u16,
+            })
+        }
+        0xE0 => {
+            // Pitch Bend, 14-bit LSB first
+            let lsb = *bytes.get(data_start)?;
+            let msb = *bytes.get(data_start + 1)?;
+            Some(ParsedMidi {
+                message_type: MidiMessageType::PitchBend,
+                channel,
+                note_or_cc: 0,
+                value: (msb as u16 & 0x7F) << 7 | (lsb as u16 & 0x7F),
+            })
+        }
         _ => {
             // Unsupported message type
             None
@@ -151,6 +374,471 @@ pub fn parse_midi_message(bytes: &[u8], last_status: &mut Option<u8>) -> Option<
     }
 }
 
+/// Encode a channel message, the inverse of `parse_midi_message`.
+/// `channel` is 1-indexed. Returns `None` for MSC, which is SysEx.
+pub fn encode_midi_message(
+    message_type: &MidiMessageType,
+    channel: u8,
+    note_or_cc: u8,
+    value: u16,
+) -> Option<Vec<u8>> {
+    let ch = (channel.clamp(1, 16) - 1) & 0x0F;
+    let note = note_or_cc & 0x7F;
+    let data = (value.min(127) & 0x7F) as u8;
+    Some(match message_type {
+        MidiMessageType::NoteOn => vec![0x90 | ch, note, data],
+        MidiMessageType::NoteOff => vec![0x80 | ch, note, data],
+        MidiMessageType::Cc => vec![0xB0 | ch, note, data],
+        MidiMessageType::ProgramChange => vec![0xC0 | ch, note],
+        MidiMessageType::PolyAftertouch => vec![0xA0 | ch, note, data],
+        MidiMessageType::ChannelPressure => vec![0xD0 | ch, data],
+        MidiMessageType::PitchBend => {
+            let value = value.min(16383);
+            vec![0xE0 | ch, (value & 0x7F) as u8, (value >> 7) as u8]
+        }
+        MidiMessageType::Msc => return None,
+    })
+}
+
+#[derive(Default, Clone, Copy)]
+struct Cc14Channel {
+    msb: [Option<u8>; 32],
+    nrpn: (Option<u8>, Option<u8>),
+    rpn: (Option<u8>, Option<u8>),
+    /// Parameter kind selected by the last CC 99/98/101/100
+    selected: Option<CcMode>,
+    data_msb: Option<u8>,
+}
+
+/// Joins CC MSB/LSB pairs and NRPN/RPN sequences into 14-bit values.
+///
+/// Values are emitted on both the MSB and the LSB, since many devices only
+/// send the MSB for coarse moves: a CC pair on its MSB (CC 0–31, LSB reset
+/// to 0) and LSB (CC 32–63), NRPN/RPN data on data entry MSB (CC 6) and
+/// LSB (CC 38). The raw 7-bit CCs are still routed as usual.
+#[derive(Default)]
+pub struct Cc14Decoder {
+    channels: [Cc14Channel; 16],
+}
+
+impl Cc14Decoder {
+    pub fn push(&mut self, msg: &ParsedMidi) -> Option<ParsedCc14> {
+        if msg.message_type != MidiMessageType::Cc {
+            return None;
+        }
+        let state = self
+            .channels
+            .get_mut(usize::from(msg.channel).checked_sub(1)?)?;
+        let value = (msg.value & 0x7F) as u8;
+        let cc14 = |mode, parameter, value| {
+            Some(ParsedCc14 {
+                mode,
+                channel: msg.channel,
+                parameter,
+                value,
+            })
+        };
+        match msg.note_or_cc {
+            98..=101 => {
+                let (mode, param) = if msg.note_or_cc >= 100 {
+                    (CcMode::Rpn, &mut state.rpn)
+                } else {
+                    (CcMode::Nrpn, &mut state.nrpn)
+                };
+                if msg.note_or_cc % 2 == 1 {
+                    param.0 = Some(value);
+                } else {
+                    param.1 = Some(value);
+                }
+                state.selected = Some(mode);
+                state.data_msb = None;
+                None
+            }
+            6 | 38 => {
+                let mode = state.selected?;
+                let (Some(msb), Some(lsb)) = (if mode == CcMode::Rpn {
+                    state.rpn
+                } else {
+                    state.nrpn
+                }) else {
+                    return None;
+                };
+                let parameter = u16::from(msb) << 7 | u16::from(lsb);
+                // RPN 127/127 is the null parameter that deselects
+                if mode == CcMode::Rpn && parameter == 0x3FFF {
+                    return None;
+                }
+                let data = if msg.note_or_cc == 6 {
+                    state.data_msb = Some(value);
+                    u16::from(value) << 7
+                } else {
+                    u16::from(state.data_msb?) << 7 | u16::from(value)
+                };
+                cc14(mode, parameter, data)
+            }
+            cc @ 0..=31 => {
+                state.msb[cc as usize] = Some(value);
+                cc14(CcMode::FourteenBit, u16::from(cc), u16::from(value) << 7)
+            }
+            cc @ 32..=63 => {
+                let msb = state.msb[(cc - 32) as usize]?;
+                cc14(
+                    CcMode::FourteenBit,
+                    u16::from(cc - 32),
+                    u16::from(msb) << 7 | u16::from(value),
+                )
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Encode a 14-bit controller as a sequence of 3-byte CC messages.
+/// `channel` is 1-indexed. Fails for a 14-bit CC controller above 31, which
+/// has no LSB partner.
+pub fn encode_cc14(
+    mode: &CcMode,
+    channel: u8,
+    parameter: u16,
+    value: u16,
+) -> Result<Vec<[u8; 3]>, String> {
+    let status = 0xB0 | ((channel.clamp(1, 16) - 1) & 0x0F);
+    let value = value.min(16383);
+    let (value_msb, value_lsb) = ((value >> 7) as u8, (value & 0x7F) as u8);
+    let parameter = parameter.min(16383);
+    let (param_msb, param_lsb) = ((parameter >> 7) as u8, (parameter & 0x7F) as u8);
+    Ok(match mode {
+        CcMode::SevenBit => vec![[status, (parameter & 0x7F) as u8, value.min(127) as u8]],
+        CcMode::FourteenBit => {
+            if parameter > 31 {
+                return Err(format!(
+                    "14-bit CC needs a controller number 0–31, got {}",
+                    parameter
+                ));
+            }
+            vec![[status, param_lsb, value_msb], [status, param_lsb + 32, value_lsb]]
+        }
+        CcMode::Nrpn | CcMode::Rpn => {
+            let (select_msb, select_lsb) = if *mode == CcMode::Rpn {
+                (101, 100)
+            } else {
+                (99, 98)
+            };
+            vec![
+                [status, select_msb, param_msb],
+                [status, select_lsb, param_lsb],
+                [status, 6, value_msb],
+                [status, 38, value_lsb],
+            ]
+        }
+    })
+}
+
+/// An SMPTE position carried by MIDI timecode.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Timecode {
+    pub hours: u8,
+    pub minutes: u8,
+    pub seconds: u8,
+    pub frames: u8,
+    pub rate: MtcRate,
+}
+
+/// Frames in a 29.97 drop-frame ten-minute block and minute.
+const DF_TEN_MINUTES: i64 = 17982;
+const DF_MINUTE: i64 = 1798;
+
+impl Timecode {
+    pub fn from_rate_code(code: u8) -> MtcRate {
+        match code & 0x03 {
+            0 => MtcRate::Fps24,
+            1 => MtcRate::Fps25,
+            2 => MtcRate::Fps2997Drop,
+            _ => MtcRate::Fps30,
+        }
+    }
+
+    pub fn rate_code(rate: MtcRate) -> u8 {
+        match rate {
+            MtcRate::Fps24 => 0,
+            MtcRate::Fps25 => 1,
+            MtcRate::Fps2997Drop => 2,
+            MtcRate::Fps30 => 3,
+        }
+    }
+
+    /// Frames per second as counted in the frames field.
+    pub fn nominal_fps(rate: MtcRate) -> i64 {
+        match rate {
+            MtcRate::Fps24 => 24,
+            MtcRate::Fps25 => 25,
+            MtcRate::Fps2997Drop | MtcRate::Fps30 => 30,
+        }
+    }
+
+    /// Real frames per second.
+    pub fn fps(rate: MtcRate) -> f64 {
+        match rate {
+            MtcRate::Fps2997Drop => 30000.0 / 1001.0,
+            _ => Self::nominal_fps(rate) as f64,
+        }
+    }
+
+    fn frames_per_day(rate: MtcRate) -> i64 {
+        match rate {
+            MtcRate::Fps2997Drop => 24 * 6 * DF_TEN_MINUTES,
+            _ => 24 * 3600 * Self::nominal_fps(rate),
+        }
+    }
+
+    /// Frames since 00:00:00:00, skipping the numbers drop-frame leaves out.
+    pub fn frame_count(&self) -> i64 {
+        let fps = Self::nominal_fps(self.rate);
+        let minutes = i64::from(self.hours) * 60 + i64::from(self.minutes);
+        let count = (minutes * 60 + i64::from(self.seconds)) * fps + i64::from(self.frames);
+        match self.rate {
+            MtcRate::Fps2997Drop => count - 2 * (minutes - minutes / 10),
+            _ => count,
+        }
+    }
+
+    /// The timecode `count` frames after midnight, wrapping at 24 hours.
+    pub fn from_frame_count(count: i64, rate: MtcRate) -> Self {
+        let mut count = count.rem_euclid(Self::frames_per_day(rate));
+        if rate == MtcRate::Fps2997Drop {
+            // Add back the two numbers skipped in every minute but each tenth
+            let blocks = count / DF_TEN_MINUTES;
+            let rest = count % DF_TEN_MINUTES;
+            let skipped = if rest < 2 { 0 } else { (rest - 2) / DF_MINUTE };
+            count += 18 * blocks + 2 * skipped;
+        }
+        let fps = Self::nominal_fps(rate);
+        Self {
+            hours: (count / (fps * 3600)) as u8,
+            minutes: (count / (fps * 60) % 60) as u8,
+            seconds: (count / fps % 60) as u8,
+            frames: (count % fps) as u8,
+            rate,
+        }
+    }
+
+    pub fn add_frames(&self, frames: i64) -> Self {
+        Self::from_frame_count(self.frame_count() + frames, self.rate)
+    }
+
+    /// Position in seconds of real time.
+    pub fn as_seconds(&self) -> f64 {
+        self.frame_count() as f64 / Self::fps(self.rate)
+    }
+}
+
+impl std::fmt::Display for Timecode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let separator = if self.rate == MtcRate::Fps2997Drop {
+            ';'
+        } else {
+            ':'
+        };
+        write!(
+            f,
+            "{:02}:{:02}:{:02}{}{:02}",
+            self.hours, self.minutes, self.seconds, separator, self.frames
+        )
+    }
+}
+
+/// Rebuilds timecode from quarter-frame (`F1`) and full-frame
+/// (`F0 7F <device> 01 01 hr mn sc fr F7`) messages.
+#[derive(Default)]
+pub struct MtcDecoder {
+    pieces: [u8; 8],
+    /// Bit n is set once piece n arrived in the current run
+    seen: u8,
+    last_piece: Option<u8>,
+}
+
+impl MtcDecoder {
+    /// Feed one message. Returns the position and whether it came from
+    /// running quarter frames (a full frame is a locate).
+    pub fn push(&mut self, bytes: &[u8]) -> Option<(Timecode, bool)> {
+        match bytes {
+            [0xF1, data] => self.quarter_frame(*data).map(|tc| (tc, true)),
+            [0xF0, 0x7F, _, 0x01, 0x01, hr, mn, sc, fr, 0xF7] => {
+                self.seen = 0;
+                self.last_piece = None;
+                let tc = Timecode {
+                    hours: hr & 0x1F,
+                    minutes: mn & 0x3F,
+                    seconds: sc & 0x3F,
+                    frames: fr & 0x1F,
+                    rate: Timecode::from_rate_code(hr >> 5),
+                };
+                Some((tc, false))
+            }
+            _ => None,
+        }
+    }
+
+    /// Only forward playback is decoded: a full time is ready after piece 7,
+    /// two frames after piece 0 described it.
+    fn quarter_frame(&mut self, data: u8) -> Option<Timecode> {
+        let piece = (data >> 4) & 0x07;
+        if self.last_piece.is_some_and(|last| piece != (last + 1) % 8) {
+            self.seen = 0;
+        }
+        self.last_piece = Some(piece);
+        self.pieces[usize::from(piece)] = data & 0x0F;
+        self.seen |= 1 << piece;
+        if piece != 7 || self.seen != 0xFF {
+            return None;
+        }
+        self.seen = 0;
+        let p = &self.pieces;
+        let tc = Timecode {
+            hours: p[6] | (p[7] & 0x01) << 4,
+            minutes: p[4] | p[5] << 4,
+            seconds: p[2] | p[3] << 4,
+            frames: p[0] | p[1] << 4,
+            rate: Timecode::from_rate_code(p[7] >> 1),
+        };
+        Some(tc.add_frames(2))
+    }
+}
+
+/// Quarter frame `piece` (0–7) of the eight that describe `tc`.
+pub fn encode_quarter_frame(tc: &Timecode, piece: u8) -> [u8; 2] {
+    let hr = tc.hours | Timecode::rate_code(tc.rate) << 5;
+    let field = [tc.frames, tc.seconds, tc.minutes, hr][usize::from(piece / 2 % 4)];
+    let nibble = if piece.is_multiple_of(2) {
+        field & 0x0F
+    } else {
+        field >> 4
+    };
+    [0xF1, (piece & 0x07) << 4 | nibble]
+}
+
+/// Full-frame SysEx locating receivers to `tc`, sent to all devices.
+pub fn encode_full_frame(tc: &Timecode) -> [u8; 10] {
+    let hr = tc.hours | Timecode::rate_code(tc.rate) << 5;
+    [
+        0xF0, 0x7F, 0x7F, 0x01, 0x01, hr, tc.minutes, tc.seconds, tc.frames, 0xF7,
+    ]
+}
+
+/// MIDI clocks per quarter note.
+const CLOCKS_PER_BEAT: u64 = 24;
+/// MIDI clock carries no time signature, so bars are counted in 4/4.
+const BEATS_PER_BAR: u64 = 4;
+/// Weight of each new clock interval in the smoothed tempo.
+const TEMPO_SMOOTHING: f64 = 0.05;
+/// A longer gap between clocks is a pause, not a tempo (under 2.5 BPM).
+const MAX_CLOCK_GAP_US: f64 = 1_000_000.0;
+
+/// What the clock follower reports.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ClockEvent {
+    /// Smoothed tempo in BPM, rounded to 0.1
+    Tempo(f64),
+    /// Beat within the bar and bar number, both from 1
+    Beat {
+        beat: u32,
+        bar: u32,
+    },
+    Transport(TransportState),
+}
+
+/// Follows MIDI clock (`F8`), Start, Continue, Stop and Song Position
+/// (`F2`) on one input.
+#[derive(Default)]
+pub struct ClockFollower {
+    /// Timestamp of the previous clock, in microseconds
+    last_clock: Option<u64>,
+    /// Smoothed time between clocks, in microseconds
+    interval: Option<f64>,
+    /// Clocks received, so tempo is reported once a beat
+    clocks: u64,
+    /// Clocks since song position 0
+    position: u64,
+    running: bool,
+    reported_bpm: Option<f64>,
+}
+
+impl ClockFollower {
+    pub fn accepts(bytes: &[u8]) -> bool {
+        matches!(bytes.first(), Some(0xF2 | 0xF8 | 0xFA | 0xFB | 0xFC))
+    }
+
+    /// Feed one message with its timestamp in microseconds.
+    pub fn push(&mut self, bytes: &[u8], timestamp_us: u64) -> Vec<ClockEvent> {
+        match bytes {
+            [0xF8, ..] => self.clock(timestamp_us),
+            [0xFA, ..] => {
+                self.running = true;
+                self.position = 0;
+                vec![ClockEvent::Transport(TransportState::Start)]
+            }
+            [0xFB, ..] => {
+                self.running = true;
+                vec![ClockEvent::Transport(TransportState::Continue)]
+            }
+            [0xFC, ..] => {
+                self.running = false;
+                vec![ClockEvent::Transport(TransportState::Stop)]
+            }
+            [0xF2, lsb, msb, ..] => {
+                // Song position counts sixteenth notes of six clocks each
+                let sixteenths = u64::from(lsb & 0x7F) | u64::from(msb & 0x7F) << 7;
+                self.position = sixteenths * 6;
+                vec![]
+            }
+            _ => vec![],
+        }
+    }
+
+    fn clock(&mut self, timestamp_us: u64) -> Vec<ClockEvent> {
+        if let Some(last) = self.last_clock {
+            let gap = timestamp_us.saturating_sub(last) as f64;
+            self.interval = match self.interval {
+                _ if gap <= 0.0 || gap > MAX_CLOCK_GAP_US => self.interval,
+                // Follow small changes smoothly and jump to large ones
+                Some(interval) if gap > interval / 2.0 && gap < interval * 2.0 => {
+                    Some(interval + TEMPO_SMOOTHING * (gap - interval))
+                }
+                _ => Some(gap),
+            };
+        }
+        self.last_clock = Some(timestamp_us);
+
+        let mut events = Vec::new();
+        if self.running {
+            // Per the spec, the first clock after Start is beat 1
+            if self.position.is_multiple_of(CLOCKS_PER_BEAT) {
+                let beat = self.position / CLOCKS_PER_BEAT;
+                events.push(ClockEvent::Beat {
+                    beat: (beat % BEATS_PER_BAR + 1) as u32,
+                    bar: (beat / BEATS_PER_BAR + 1) as u32,
+                });
+            }
+            self.position += 1;
+        }
+        self.clocks += 1;
+        if self.clocks.is_multiple_of(CLOCKS_PER_BEAT) {
+            if let Some(bpm) = self.bpm().filter(|bpm| self.reported_bpm != Some(*bpm)) {
+                self.reported_bpm = Some(bpm);
+                events.push(ClockEvent::Tempo(bpm));
+            }
+        }
+        events
+    }
+
+    /// Smoothed tempo in BPM, rounded to 0.1.
+    pub fn bpm(&self) -> Option<f64> {
+        let per_minute = 60_000_000.0 / CLOCKS_PER_BEAT as f64;
+        self.interval
+            .map(|interval| (per_minute / interval * 10.0).round() / 10.0)
+    }
+}
+
 pub fn open_input(
     port_name: &str,
     tx: mpsc::UnboundedSender<IncomingMessage>,
@@ -164,34 +852,75 @@ pub fn open_input(
         .ok_or_else(|| format!("MIDI input port not found: {}", port_name))?
         .clone();
 
+    let source = port_name.to_string();
     let mut last_status: Option<u8> = None;
+    let mut cc14 = Cc14Decoder::default();
+    let mut mtc = MtcDecoder::default();
+    let mut clock = ClockFollower::default();
     midi_in
         .connect(
             &port,
             "conduit-in",
-            move |_timestamp, bytes, _| {
-                if bytes.first() == Some(&0xF0) {
+            move |timestamp, bytes, _| {
+                if ClockFollower::accepts(bytes) {
+                    for event in clock.push(bytes, timestamp) {
+                        let port = source.clone();
+                        let _ = tx.send(match event {
+                            ClockEvent::Tempo(bpm) => IncomingMessage::Tempo { port, bpm },
+                            ClockEvent::Beat { beat, bar } => {
+                                IncomingMessage::Beat { port, beat, bar }
+                            }
+                            ClockEvent::Transport(state) => {
+                                IncomingMessage::Transport { port, state }
+                            }
+                        });
+                    }
+                } else if let Some((time, running)) = mtc.push(bytes) {
+                    let _ = tx.send(IncomingMessage::Mtc {
+                        port: source.clone(),
+                        time,
+                        running,
+                    });
+                } else if bytes.first() == Some(&0xF1) {
+                    // Quarter frame still being assembled; it must not
+                    // reach parse_midi_message and change running status
+                } else if bytes.first() == Some(&0xF0) {
                     // SysEx — try MSC parse
                     if let Some(msc) = parse_msc_sysex(bytes) {
                         let msg = IncomingMessage::Msc {
+                            port: source.clone(),
                             device_id: msc.device_id,
                             command_format: msc.command_format,
                             command: msc.command,
                             cue_number: msc.cue_number,
                             cue_list: msc.cue_list,
                             cue_path: msc.cue_path,
+                            time: msc.time,
+                            set_control: msc.set_control,
+                            set_value: msc.set_value,
                         };
                         let _ = tx.send(msg);
                     }
                     // Don't update last_status for SysEx
                 } else if let Some(parsed) = parse_midi_message(bytes, &mut last_status) {
+                    let assembled = cc14.push(&parsed);
                     let msg = IncomingMessage::Midi {
+                        port: source.clone(),
                         message_type: parsed.message_type,
                         channel: parsed.channel,
                         note_or_cc: parsed.note_or_cc,
                         value: parsed.value,
                     };
                     let _ = tx.send(msg);
+                    if let Some(c) = assembled {
+                        let _ = tx.send(IncomingMessage::Cc14 {
+                            port: source.clone(),
+                            mode: c.mode,
+                            channel: c.channel,
+                            parameter: c.parameter,
+                            value: c.value,
+                        });
+                    }
                 }
             },
             (),
@@ -217,6 +946,106 @@ pub fn open_output(
     Ok(Arc::new(Mutex::new(conn)))
 }
 
+/// An open input connection, kept alive until dropped.
+struct InputConnection {
+    _conn: MidiInputConnection<()>,
+}
+
+// SAFETY: MidiInputConnection<()> is not Send because the underlying platform MIDI handle
+// types (e.g. CoreMIDI's MIDIPortRef on macOS) are raw pointers. However, this is safe because:
+// 1. Platform MIDI handles (CoreMIDI, ALSA, WinMM) are thread-safe in practice — they are
+//    opaque handles managed by the OS and do not carry thread-affine mutable state.
+// 2. We only store the connection here to keep it alive and drop it on close or engine stop.
+//    No cross-thread method calls are made on the connection itself.
+// 3. The callback closure captured by midir only captures an mpsc::UnboundedSender<IncomingMessage>,
+//    which is Send + Sync.
+unsafe impl Send for InputConnection {}
+
+/// The engine's open MIDI input ports, keyed by port name.
+#[derive(Default)]
+pub struct MidiInputs {
+    ports: Mutex<HashMap<String, InputConnection>>,
+}
+
+impl MidiInputs {
+    pub fn open(&self, port_name: &str, tx: mpsc::UnboundedSender<IncomingMessage>) -> Result<(), String> {
+        let conn = open_input(port_name, tx)?;
+        let mut ports = self.ports.lock().map_err(|e| e.to_string())?;
+        ports.insert(port_name.to_string(), InputConnection { _conn: conn });
+        Ok(())
+    }
+
+    /// Drop a port's connection. Returns whether it was open.
+    pub fn close(&self, port_name: &str) -> bool {
+        self.ports
+            .lock()
+            .map(|mut ports| ports.remove(port_name).is_some())
+            .unwrap_or(false)
+    }
+
+    pub fn is_open(&self, port_name: &str) -> bool {
+        self.ports
+            .lock()
+            .map(|ports| ports.contains_key(port_name))
+            .unwrap_or(false)
+    }
+}
+
+/// The engine's open MIDI output ports. Mappings without an output port
+/// send to `default_port`, the first configured one.
+#[derive(Default)]
+pub struct MidiOutputs {
+    ports: Mutex<HashMap<String, Arc<Mutex<MidiOutputConnection>>>>,
+    default_port: Option<String>,
+}
+
+impl MidiOutputs {
+    pub fn new(default_port: Option<String>) -> Self {
+        Self {
+            ports: Mutex::new(HashMap::new()),
+            default_port,
+        }
+    }
+
+    pub fn open(&self, port_name: &str) -> Result<(), String> {
+        let conn = open_output(port_name)?;
+        let mut ports = self.ports.lock().map_err(|e| e.to_string())?;
+        ports.insert(port_name.to_string(), conn);
+        Ok(())
+    }
+
+    pub fn close(&self, port_name: &str) -> bool {
+        self.ports
+            .lock()
+            .map(|mut ports| ports.remove(port_name).is_some())
+            .unwrap_or(false)
+    }
+
+    pub fn is_open(&self, port_name: &str) -> bool {
+        self.ports
+            .lock()
+            .map(|ports| ports.contains_key(port_name))
+            .unwrap_or(false)
+    }
+
+    /// Send raw bytes to `port`, or the default output when `None`. Without
+    /// any output configured this does nothing.
+    pub fn send(&self, port: Option<&str>, bytes: &[u8]) -> Result<(), String> {
+        let Some(name) = port.or(self.default_port.as_deref()) else {
+            return Ok(());
+        };
+        let conn = {
+            let ports = self.ports.lock().map_err(|e| e.to_string())?;
+            ports
+                .get(name)
+                .cloned()
+                .ok_or_else(|| format!("MIDI output port not open: {}", name))?
+        };
+        let mut conn = conn.lock().map_err(|e| e.to_string())?;
+        conn.send(bytes).map_err(|e| format!("MIDI send error ({}): {}", name, e))
+    }
+}
+
 pub fn enumerate_ports_hash() -> (Vec<String>, Vec<String>) {
     let inputs = MidiInput::new("Conduit-enum")
         .map(|m| {
@@ -328,11 +1157,155 @@ mod tests {
     #[test]
     fn test_parse_unsupported_type() {
         let mut last = None;
-        // Pitch bend - still unsupported
-        let result = parse_midi_message(&[0xE0, 0, 64], &mut last);
+        // Song Position Pointer is a system message, not a channel message
+        let result = parse_midi_message(&[0xF2, 0, 64], &mut last);
         assert!(result.is_none());
     }
 
+    #[test]
+    fn test_parse_pitch_bend() {
+        let mut last = None;
+        let p = parse_midi_message(&[0xE3, 0x00, 0x40], &mut last).unwrap();
+        assert!(matches!(p.message_type, MidiMessageType::PitchBend));
+        assert_eq!(p.channel, 4);
+        assert_eq!(p.value, 8192);
+        let p = parse_midi_message(&[0xE0, 0x7F, 0x7F], &mut last).unwrap();
+        assert_eq!(p.value, 16383);
+    }
+
+    #[test]
+    fn test_parse_channel_pressure() {
+        let mut last = None;
+        let p = parse_midi_message(&[0xD1, 90], &mut last).unwrap();
+        assert!(matches!(p.message_type, MidiMessageType::ChannelPressure));
+        assert_eq!(p.channel, 2);
+        assert_eq!(p.value, 90);
+    }
+
+    #[test]
+    fn test_parse_poly_aftertouch() {
+        let mut last = None;
+        let p = parse_midi_message(&[0xA0, 60, 45], &mut last).unwrap();
+        assert!(matches!(p.message_type, MidiMessageType::PolyAftertouch));
+        assert_eq!(p.note_or_cc, 60);
+        assert_eq!(p.value, 45);
+        // Running status
+        let p = parse_midi_message(&[61, 50], &mut last).unwrap();
+        assert_eq!(p.note_or_cc, 61);
+        assert_eq!(p.value, 50);
+    }
+
+    fn cc(channel: u8, cc: u8, value: u8) -> ParsedMidi {
+        ParsedMidi {
+            message_type: MidiMessageType::Cc,
+            channel,
+            note_or_cc: cc,
+            value: value as u16,
+        }
+    }
+
+    #[test]
+    fn test_cc14_pair() {
+        let mut dec = Cc14Decoder::default();
+        assert_eq!(dec.push(&cc(1, 7, 0x40)).unwrap().value, 0x40 << 7);
+        let out = dec.push(&cc(1, 39, 0x01)).unwrap();
+        assert_eq!(out.mode, CcMode::FourteenBit);
+        assert_eq!((out.channel, out.parameter, out.value), (1, 7, 8193));
+        // LSB without a prior MSB on that channel is ignored
+        assert_eq!(dec.push(&cc(2, 39, 0x01)), None);
+        // Fine moves reuse the stored MSB
+        assert_eq!(dec.push(&cc(1, 39, 0x7F)).unwrap().value, 0x40 << 7 | 0x7F);
+    }
+
+    #[test]
+    fn test_cc14_msb_only_moves() {
+        let mut dec = Cc14Decoder::default();
+        let coarse = dec.push(&cc(1, 7, 0x20)).unwrap();
+        assert_eq!(coarse.mode, CcMode::FourteenBit);
+        assert_eq!((coarse.parameter, coarse.value), (7, 0x20 << 7));
+        // A new MSB resets the LSB to 0
+        dec.push(&cc(1, 39, 0x11));
+        assert_eq!(dec.push(&cc(1, 7, 0x21)).unwrap().value, 0x21 << 7);
+    }
+
+    #[test]
+    fn test_nrpn_assembly() {
+        let mut dec = Cc14Decoder::default();
+        assert_eq!(dec.push(&cc(3, 99, 0x01)), None);
+        assert_eq!(dec.push(&cc(3, 98, 0x02)), None);
+        let coarse = dec.push(&cc(3, 6, 0x10)).unwrap();
+        assert_eq!(coarse.mode, CcMode::Nrpn);
+        assert_eq!((coarse.parameter, coarse.value), (130, 0x10 << 7));
+        let fine = dec.push(&cc(3, 38, 0x05)).unwrap();
+        assert_eq!((fine.parameter, fine.value), (130, 0x10 << 7 | 0x05));
+        // Data entry without a selected parameter is ignored
+        assert_eq!(dec.push(&cc(4, 6, 0x10)), None);
+    }
+
+    #[test]
+    fn test_rpn_null_deselects() {
+        let mut dec = Cc14Decoder::default();
+        dec.push(&cc(1, 101, 0));
+        dec.push(&cc(1, 100, 0));
+        let out = dec.push(&cc(1, 6, 2)).unwrap();
+        assert_eq!((out.mode, out.parameter), (CcMode::Rpn, 0));
+        dec.push(&cc(1, 101, 127));
+        dec.push(&cc(1, 100, 127));
+        assert_eq!(dec.push(&cc(1, 6, 2)), None);
+    }
+
+    #[test]
+    fn test_encode_cc14_round_trip() {
+        for (mode, parameter) in [
+            (CcMode::FourteenBit, 1),
+            (CcMode::Nrpn, 1234),
+            (CcMode::Rpn, 2),
+        ] {
+            for value in [0, 1, 8192, 16383] {
+                let mut dec = Cc14Decoder::default();
+                let mut last = None;
+                let mut out = None;
+                for bytes in encode_cc14(&mode, 5, parameter, value).unwrap() {
+                    let parsed = parse_midi_message(&bytes, &mut last).unwrap();
+                    out = dec.push(&parsed).or(out);
+                }
+                let out = out.unwrap();
+                assert_eq!((out.mode, out.channel), (mode, 5));
+                assert_eq!((out.parameter, out.value), (parameter, value));
+            }
+        }
+    }
+
+    #[test]
+    fn test_encode_cc14_rejects_controller_above_31() {
+        assert!(encode_cc14(&CcMode::FourteenBit, 1, 31, 0).is_ok());
+        assert!(encode_cc14(&CcMode::FourteenBit, 1, 32, 0).is_err());
+    }
+
+    #[test]
+    fn test_encode_round_trip() {
+        let cases = [
+            (MidiMessageType::NoteOn, 1, 60, 100),
+            (MidiMessageType::Cc, 16, 7, 0),
+            (MidiMessageType::ProgramChange, 3, 12, 0),
+            (MidiMessageType::PitchBend, 2, 0, 0),
+            (MidiMessageType::PitchBend, 2, 0, 8192),
+            (MidiMessageType::PitchBend, 2, 0, 16383),
+            (MidiMessageType::ChannelPressure, 10, 0, 64),
+            (MidiMessageType::PolyAftertouch, 5, 72, 127),
+        ];
+        for (message_type, channel, note_or_cc, value) in cases {
+            let bytes = encode_midi_message(&message_type, channel, note_or_cc, value).unwrap();
+            let p = parse_midi_message(&bytes, &mut None).unwrap();
+            assert_eq!(p.message_type, message_type);
+            assert_eq!(
+                (p.channel, p.note_or_cc, p.value),
+                (channel, note_or_cc, value)
+            );
+        }
+        assert!(encode_midi_message(&MidiMessageType::Msc, 1, 0, 0).is_none());
+    }
+
     #[test]
     fn test_parse_empty() {
         let mut last = None;
@@ -393,4 +1366,280 @@ mod tests {
         let bytes = [0xF0, 0x7F, 0x01, 0x02, 0x7F, 0xF7];
         assert!(parse_msc_sysex(&bytes).is_none());
     }
+
+    fn msc(
+        device_id: u8,
+        command: u8,
+        cue_number: &str,
+        cue_list: Option<&str>,
+        cue_path: Option<&str>,
+    ) -> ParsedMsc {
+        ParsedMsc {
+            device_id,
+            command_format: 0x01,
+            command,
+            cue_number: cue_number.to_string(),
+            cue_list: cue_list.map(String::from),
+            cue_path: cue_path.map(String::from),
+            time: None,
+            set_control: None,
+            set_value: None,
+        }
+    }
+
+    fn time(hours: u8, minutes: u8, seconds: u8, frames: u8, subframes: u8, rate: u8) -> MscTime {
+        MscTime {
+            hours,
+            minutes,
+            seconds,
+            frames,
+            subframes,
+            rate,
+        }
+    }
+
+    #[test]
+    fn test_build_msc_go_cue_10() {
+        let bytes = build_msc_sysex(&msc(0x01, 0x01, "10", None, None));
+        assert_eq!(
+            bytes,
+            vec![0xF0, 0x7F, 0x01, 0x02, 0x01, 0x01, b'1', b'0', 0xF7]
+        );
+    }
+
+    #[test]
+    fn test_build_msc_round_trip() {
+        let cases = [
+            msc(0x7F, 0x02, "", None, None),
+            msc(0x01, 0x01, "10", None, None),
+            msc(0x05, 0x01, "1.5", Some("3"), None),
+            msc(0x10, 0x03, "42.1", Some("2"), Some("7")),
+            msc(0x01, 0x07, "12", None, None),
+            msc(0x01, 0x08, "", None, None),
+            msc(0x01, 0x10, "5", Some("1"), None),
+            msc(0x01, 0x15, "", Some("4"), None),
+            msc(0x01, 0x1B, "", Some("2"), None),
+            msc(0x01, 0x1E, "", None, Some("9")),
+            ParsedMsc {
+                time: Some(time(1, 2, 3, 4, 50, 1)),
+                ..msc(0x01, 0x04, "7", Some("1"), None)
+            },
+            ParsedMsc {
+                time: Some(time(0, 0, 10, 0, 0, 3)),
+                ..msc(0x01, 0x18, "", Some("3"), None)
+            },
+            ParsedMsc {
+                set_control: Some(300),
+                set_value: Some(16383),
+                ..msc(0x01, 0x06, "", None, None)
+            },
+            ParsedMsc {
+                set_control: Some(1),
+                set_value: Some(64),
+                time: Some(time(0, 1, 0, 12, 0, 2)),
+                ..msc(0x01, 0x06, "", None, None)
+            },
+        ];
+        for case in &cases {
+            assert_eq!(parse_msc_sysex(&build_msc_sysex(case)).as_ref(), Some(case));
+        }
+    }
+
+    #[test]
+    fn test_build_msc_list_requires_cue_number() {
+        let bytes = build_msc_sysex(&msc(0x01, 0x01, "", Some("3"), None));
+        assert_eq!(parse_msc_sysex(&bytes).unwrap().cue_list, None);
+    }
+
+    #[test]
+    fn test_parse_msc_timed_go() {
+        // TIMED_GO at 01:02:03:04.50 (25 fps), cue 7
+        let bytes = [
+            0xF0, 0x7F, 0x01, 0x02, 0x01, 0x04, 0x21, 0x02, 0x03, 0x04, 50, b'7', 0xF7,
+        ];
+        let msc = parse_msc_sysex(&bytes).unwrap();
+        let t = msc.time.unwrap();
+        assert_eq!(t, time(1, 2, 3, 4, 50, 1));
+        assert_eq!(t.to_string(), "01:02:03:04.50");
+        assert!((t.as_seconds() - (3723.0 + 4.5 / 25.0)).abs() < 1e-9);
+        assert_eq!(msc.cue_number, "7");
+    }
+
+    #[test]
+    fn test_parse_msc_set() {
+        // SET control 300 (0x2C 0x02) to value 8192 (0x00 0x40)
+        let bytes = [
+            0xF0, 0x7F, 0x01, 0x02, 0x01, 0x06, 0x2C, 0x02, 0x00, 0x40, 0xF7,
+        ];
+        let msc = parse_msc_sysex(&bytes).unwrap();
+        assert_eq!(msc.set_control, Some(300));
+        assert_eq!(msc.set_value, Some(8192));
+        assert_eq!(msc.time, None);
+        // SET without its control/value bytes is malformed
+        let short = [0xF0, 0x7F, 0x01, 0x02, 0x01, 0x06, 0x2C, 0xF7];
+        assert!(parse_msc_sysex(&short).is_none());
+    }
+
+    #[test]
+    fn test_parse_msc_cue_list_commands() {
+        // OPEN_CUE_LIST 5: the only field is the cue list
+        let bytes = [0xF0, 0x7F, 0x01, 0x02, 0x01, 0x1B, b'5', 0xF7];
+        let msc = parse_msc_sysex(&bytes).unwrap();
+        assert_eq!(msc.cue_number, "");
+        assert_eq!(msc.cue_list.as_deref(), Some("5"));
+    }
+
+    /// The eight quarter frames describing `tc`.
+    fn quarter_frames(tc: &Timecode) -> Vec<[u8; 2]> {
+        (0..8)
+            .map(|piece| encode_quarter_frame(tc, piece))
+            .collect()
+    }
+
+    #[test]
+    fn test_mtc_quarter_frames() {
+        let start = Timecode {
+            hours: 10,
+            minutes: 59,
+            seconds: 59,
+            frames: 23,
+            rate: MtcRate::Fps24,
+        };
+        let mut decoder = MtcDecoder::default();
+        let frames = quarter_frames(&start);
+        for qf in &frames[..7] {
+            assert_eq!(decoder.push(qf), None);
+        }
+        // Complete two frames after piece 0 was sent
+        let (tc, running) = decoder.push(&frames[7]).unwrap();
+        assert!(running);
+        assert_eq!(tc.to_string(), "11:00:00:01");
+
+        // A gap in the sequence starts the assembly over
+        let mut decoder = MtcDecoder::default();
+        decoder.push(&frames[0]);
+        for qf in &frames[2..] {
+            assert_eq!(decoder.push(qf), None);
+        }
+    }
+
+    #[test]
+    fn test_mtc_full_frame() {
+        // 29.97 drop-frame (rate code 2), 01:02:03;04
+        let bytes = [0xF0, 0x7F, 0x7F, 0x01, 0x01, 0x41, 0x02, 0x03, 0x04, 0xF7];
+        let (tc, running) = MtcDecoder::default().push(&bytes).unwrap();
+        assert!(!running);
+        assert_eq!(tc.rate, MtcRate::Fps2997Drop);
+        assert_eq!(tc.to_string(), "01:02:03;04");
+        assert_eq!(encode_full_frame(&tc), bytes);
+    }
+
+    #[test]
+    fn test_drop_frame_skips_numbers() {
+        let tc = |minutes, seconds, frames| Timecode {
+            hours: 0,
+            minutes,
+            seconds,
+            frames,
+            rate: MtcRate::Fps2997Drop,
+        };
+        // Frames 0 and 1 don't exist at the start of minute 1
+        assert_eq!(tc(0, 59, 29).add_frames(1), tc(1, 0, 2));
+        // but do every tenth minute
+        assert_eq!(tc(9, 59, 29).add_frames(1), tc(10, 0, 0));
+        assert_eq!(tc(10, 0, 0).frame_count(), 17982);
+        // One hour of drop-frame is 3600 seconds of 29.97 fps
+        let hour = Timecode {
+            hours: 1,
+            ..tc(0, 0, 0)
+        };
+        assert!((hour.as_seconds() - 3600.0).abs() < 0.01);
+
+        let round_trip =
+            Timecode::from_frame_count(tc(23, 17, 5).frame_count(), MtcRate::Fps2997Drop);
+        assert_eq!(round_trip, tc(23, 17, 5));
+        // Wraps at midnight
+        let last = Timecode {
+            hours: 23,
+            ..tc(59, 59, 29)
+        };
+        assert_eq!(last.add_frames(1), tc(0, 0, 0));
+    }
+
+    /// Feed `count` clocks `interval_us` apart, starting at `*at`.
+    fn clocks(
+        follower: &mut ClockFollower,
+        at: &mut u64,
+        interval_us: u64,
+        count: usize,
+    ) -> Vec<ClockEvent> {
+        let mut events = Vec::new();
+        for _ in 0..count {
+            events.extend(follower.push(&[0xF8], *at));
+            *at += interval_us;
+        }
+        events
+    }
+
+    #[test]
+    fn test_clock_beats_and_tempo() {
+        let mut follower = ClockFollower::default();
+        let mut at = 0;
+        assert_eq!(
+            follower.push(&[0xFA], at),
+            vec![ClockEvent::Transport(TransportState::Start)]
+        );
+        // 120 BPM: 24 clocks every 500 ms
+        let events = clocks(&mut follower, &mut at, 20_833, 25);
+        assert_eq!(
+            events,
+            vec![
+                ClockEvent::Beat { beat: 1, bar: 1 },
+                ClockEvent::Tempo(120.0),
+                ClockEvent::Beat { beat: 2, bar: 1 },
+            ]
+        );
+        // Unchanged tempo isn't reported again
+        let events = clocks(&mut follower, &mut at, 20_833, 72);
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[2], ClockEvent::Beat { beat: 1, bar: 2 });
+
+        assert_eq!(
+            follower.push(&[0xFC], at),
+            vec![ClockEvent::Transport(TransportState::Stop)]
+        );
+        assert!(clocks(&mut follower, &mut at, 20_833, 23).is_empty());
+    }
+
+    #[test]
+    fn test_clock_tempo_is_smoothed() {
+        let mut follower = ClockFollower::default();
+        let mut at = 0;
+        clocks(&mut follower, &mut at, 20_833, 48);
+        // 125 BPM
+        clocks(&mut follower, &mut at, 20_000, 24);
+        let bpm = follower.bpm().unwrap();
+        assert!(120.0 < bpm && bpm < 125.0, "{}", bpm);
+        clocks(&mut follower, &mut at, 20_000, 240);
+        assert_eq!(follower.bpm(), Some(125.0));
+        // A pause isn't a tempo
+        at += 5_000_000;
+        clocks(&mut follower, &mut at, 20_000, 1);
+        assert_eq!(follower.bpm(), Some(125.0));
+    }
+
+    #[test]
+    fn test_song_position_and_continue() {
+        let mut follower = ClockFollower::default();
+        // Sixteen sixteenths in: the start of bar 2
+        assert!(follower.push(&[0xF2, 16, 0], 0).is_empty());
+        assert_eq!(
+            follower.push(&[0xFB], 0),
+            vec![ClockEvent::Transport(TransportState::Continue)]
+        );
+        assert_eq!(
+            follower.push(&[0xF8], 0),
+            vec![ClockEvent::Beat { beat: 1, bar: 2 }]
+        );
+    }
 }
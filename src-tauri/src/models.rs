@@ -8,11 +8,112 @@ pub struct Settings {
     pub osc_send_port: u16,
     pub osc_send_protocol: OscSendProtocol,
     pub osc_tcp_send_timeout_ms: u64,
+    #[serde(default)]
+    pub osc_tcp_send_framing: OscTcpFraming,
+    #[serde(default)]
+    pub osc_tcp_listen_framing: OscTcpListenFraming,
+    /// Named OSC targets mappings can send to, in addition to the default
+    /// `osc_send_host`/`osc_send_port` target
+    #[serde(default)]
+    pub osc_destinations: Vec<OscDestination>,
     pub midi_input_port_name: Option<String>,
     pub midi_output_port_name: Option<String>,
+    /// Further MIDI ports opened alongside `midi_input_port_name` /
+    /// `midi_output_port_name`
+    #[serde(default)]
+    pub midi_input_port_names: Vec<String>,
+    #[serde(default)]
+    pub midi_output_port_names: Vec<String>,
     pub engine_auto_start: bool,
     #[serde(default)]
     pub launch_on_startup: bool,
+    /// Messages per second on one input address that count as a feedback
+    /// loop; 0 turns loop detection off
+    #[serde(default = "default_loop_max_rate_hz")]
+    pub loop_max_rate_hz: u32,
+    /// Publishing of incoming MIDI timecode to OSC
+    #[serde(default)]
+    pub mtc_output: MtcOutput,
+    /// MIDI timecode sent on a MIDI output, driven by OSC transport messages
+    #[serde(default)]
+    pub mtc_generator: MtcGenerator,
+}
+
+/// Sends the timecode decoded from MIDI inputs as OSC.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MtcOutput {
+    pub enabled: bool,
+    pub address: String,
+    #[serde(default)]
+    pub format: MtcArgFormat,
+    /// Destination names; empty sends to the default target
+    #[serde(default)]
+    pub destinations: Vec<String>,
+}
+
+impl Default for MtcOutput {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            address: "/timecode".to_string(),
+            format: MtcArgFormat::default(),
+            destinations: Vec::new(),
+        }
+    }
+}
+
+/// Conduit as an MTC master: `<address>/play`, `<address>/stop` and
+/// `<address>/locate` control quarter frames sent on `port`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MtcGenerator {
+    pub enabled: bool,
+    /// Prefix of the transport addresses
+    pub address: String,
+    #[serde(default)]
+    pub rate: MtcRate,
+    /// MIDI output port; `None` sends to the default output
+    #[serde(default)]
+    pub port: Option<String>,
+}
+
+impl Default for MtcGenerator {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            address: "/mtc".to_string(),
+            rate: MtcRate::default(),
+            port: None,
+        }
+    }
+}
+
+/// Arguments of a timecode message, before the trailing state string.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum MtcArgFormat {
+    /// `"hh:mm:ss:ff"` (`;` before the frames at 29.97 drop-frame)
+    String,
+    /// Hours, minutes, seconds and frames as ints, then the fps as a float
+    Numeric,
+    /// The string, then the numeric arguments
+    #[default]
+    Both,
+}
+
+/// MIDI timecode frame rates, in the order of their 2-bit rate code.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum MtcRate {
+    Fps24,
+    Fps25,
+    /// 29.97 drop-frame
+    Fps2997Drop,
+    #[default]
+    Fps30,
+}
+
+fn default_loop_max_rate_hz() -> u32 {
+    1000
 }
 
 impl Default for Settings {
@@ -24,14 +125,87 @@ impl Default for Settings {
             osc_send_port: 9000,
             osc_send_protocol: OscSendProtocol::Udp,
             osc_tcp_send_timeout_ms: 3000,
+            osc_tcp_send_framing: OscTcpFraming::Slip,
+            osc_tcp_listen_framing: OscTcpListenFraming::Slip,
+            osc_destinations: Vec::new(),
             midi_input_port_name: None,
             midi_output_port_name: None,
+            midi_input_port_names: Vec::new(),
+            midi_output_port_names: Vec::new(),
             engine_auto_start: false,
             launch_on_startup: false,
+            loop_max_rate_hz: default_loop_max_rate_hz(),
+            mtc_output: MtcOutput::default(),
+            mtc_generator: MtcGenerator::default(),
         }
     }
 }
 
+fn merge_port_names(primary: &Option<String>, others: &[String]) -> Vec<String> {
+    let mut names: Vec<String> = primary.iter().cloned().collect();
+    for name in others {
+        if !names.contains(name) {
+            names.push(name.clone());
+        }
+    }
+    names
+}
+
+/// Name that always refers to the `osc_send_host`/`osc_send_port` target.
+pub const DEFAULT_OSC_DESTINATION: &str = "default";
+
+impl Settings {
+    /// Every MIDI input port to open, without duplicates.
+    pub fn midi_input_ports(&self) -> Vec<String> {
+        merge_port_names(&self.midi_input_port_name, &self.midi_input_port_names)
+    }
+
+    /// Every MIDI output port to open. The first is the default output for
+    /// mappings that don't name one.
+    pub fn midi_output_ports(&self) -> Vec<String> {
+        merge_port_names(&self.midi_output_port_name, &self.midi_output_port_names)
+    }
+
+    /// The single send target from before named destinations existed.
+    pub fn default_osc_destination(&self) -> OscDestination {
+        OscDestination {
+            name: DEFAULT_OSC_DESTINATION.to_string(),
+            host: self.osc_send_host.clone(),
+            port: self.osc_send_port,
+            protocol: self.osc_send_protocol.clone(),
+            timeout_ms: self.osc_tcp_send_timeout_ms,
+            framing: self.osc_tcp_send_framing.clone(),
+        }
+    }
+
+    /// Look up a destination by name. `"default"` resolves to the default
+    /// target unless a named destination overrides it.
+    pub fn osc_destination(&self, name: &str) -> Option<OscDestination> {
+        self.osc_destinations
+            .iter()
+            .find(|d| d.name == name)
+            .cloned()
+            .or_else(|| (name == DEFAULT_OSC_DESTINATION).then(|| self.default_osc_destination()))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct OscDestination {
+    pub name: String,
+    pub host: String,
+    pub port: u16,
+    pub protocol: OscSendProtocol,
+    #[serde(default = "default_tcp_send_timeout_ms")]
+    pub timeout_ms: u64,
+    /// Only used when `protocol` is TCP
+    #[serde(default)]
+    pub framing: OscTcpFraming,
+}
+
+fn default_tcp_send_timeout_ms() -> u64 {
+    3000
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "snake_case")]
 pub enum OscListenProtocol {
@@ -47,6 +221,27 @@ pub enum OscSendProtocol {
     Tcp,
 }
 
+/// How OSC packets are delimited on a TCP stream.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum OscTcpFraming {
+    /// SLIP (OSC 1.1)
+    #[default]
+    Slip,
+    /// 4-byte big-endian length prefix (OSC 1.0)
+    LengthPrefix,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum OscTcpListenFraming {
+    #[default]
+    Slip,
+    LengthPrefix,
+    /// Decide per connection from the first byte received
+    Auto,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Mapping {
     pub id: String,
@@ -54,23 +249,245 @@ pub struct Mapping {
     pub direction: Direction,
     pub osc_address: String,
     pub osc_arg_types: Vec<OscArgType>,
+    #[serde(default)]
+    pub osc_arg_match: OscArgMatch,
     pub midi_message_type: MidiMessageType,
-    pub midi_channel: u8,
-    pub midi_note_or_cc: u8,
+    pub midi_channel: MidiField,
+    pub midi_note_or_cc: MidiField,
     pub midi_velocity_or_value: ValueSource,
+    /// Only accept this exact value (0–16383 for pitch bend)
     #[serde(default)]
-    pub midi_input_velocity: Option<u8>,
+    pub midi_input_velocity: Option<u16>,
+    /// Only accept MIDI from this input port; `None` accepts every port
+    #[serde(default)]
+    pub midi_input_port: Option<String>,
+    /// Send MIDI to this output port; `None` uses the default output
+    #[serde(default)]
+    pub midi_output_port: Option<String>,
     pub osc_args: Vec<OscArgDef>,
     #[serde(default)]
     pub osc_output_address: String,
     #[serde(default)]
     pub osc_transform: Option<OscTransform>,
+    /// Destination names for OSC output; empty sends to the default target
+    #[serde(default)]
+    pub osc_destinations: Vec<String>,
     #[serde(default)]
     pub msc_device_id: Option<u8>,
     #[serde(default)]
     pub msc_command_format: Option<MscCommandFormat>,
     #[serde(default)]
     pub msc_command: Option<MscCommand>,
+    /// Cue fields for MSC output (`OscToMidi` with `midi_message_type: msc`)
+    #[serde(default)]
+    pub msc_cue_number: Option<MscCueSource>,
+    #[serde(default)]
+    pub msc_cue_list: Option<MscCueSource>,
+    #[serde(default)]
+    pub msc_cue_path: Option<MscCueSource>,
+    /// Resolution of CC mappings; 14-bit values span 0–16383
+    #[serde(default)]
+    pub cc_mode: CcMode,
+    /// NRPN/RPN parameter number (0–16383) when `cc_mode` is `nrpn` or `rpn`
+    #[serde(default)]
+    pub midi_parameter: u16,
+    /// MIDI clock input a `MidiToOsc` mapping reacts to instead of a MIDI
+    /// message; `midi_message_type` is ignored when set
+    #[serde(default)]
+    pub clock_input: Option<ClockInput>,
+    /// Transport message a `transport` clock mapping reacts to; `None` reacts
+    /// to Start, Continue and Stop
+    #[serde(default)]
+    pub transport_state: Option<TransportState>,
+    /// Output side of a `MidiToMidi` mapping
+    #[serde(default)]
+    pub midi_remap: MidiRemap,
+    /// Every condition must pass for the mapping to fire
+    #[serde(default)]
+    pub conditions: Vec<Condition>,
+    #[serde(default)]
+    pub mode: MappingMode,
+    /// Mappings in the same group share one on/off state; `None` keeps the
+    /// state per mapping
+    #[serde(default)]
+    pub state_group: Option<String>,
+    /// Keep toggle/latch state across engine and app restarts
+    #[serde(default)]
+    pub persist_state: bool,
+    /// Ramp output values instead of jumping to them
+    #[serde(default)]
+    pub slew: Option<Slew>,
+    /// Rate limiting and change-only filtering of the output
+    #[serde(default)]
+    pub output_filter: OutputFilter,
+    /// Further outputs sent in order after the mapping's own output
+    #[serde(default)]
+    pub steps: Vec<MacroStep>,
+    /// What a new trigger does while the steps are still running
+    #[serde(default)]
+    pub retrigger: Retrigger,
+}
+
+/// One timed output of a macro mapping.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MacroStep {
+    /// Wait this long after the previous output before sending
+    #[serde(default)]
+    pub delay_ms: u64,
+    pub output: MacroOutput,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum MacroOutput {
+    Osc {
+        address: String,
+        /// `midi_value` and `midi_note` sources read the triggering message
+        #[serde(default)]
+        args: Vec<OscArgDef>,
+        #[serde(default)]
+        destinations: Vec<String>,
+    },
+    Midi {
+        message_type: MidiMessageType,
+        channel: u8,
+        #[serde(default)]
+        note_or_cc: u8,
+        #[serde(default)]
+        value: u16,
+        #[serde(default)]
+        port: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Retrigger {
+    /// Stop the running sequence and start over
+    #[default]
+    Cancel,
+    /// Run the new sequence once the running ones finish
+    Queue,
+}
+
+/// Output smoothing. Values are in output units: OSC argument values, or
+/// MIDI values (0–127, 0–16383 for 14-bit).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Slew {
+    pub mode: SlewMode,
+    /// Time constant in ms, or the largest change per second
+    pub amount: f64,
+    /// How often intermediate values are sent
+    #[serde(default = "default_slew_rate_hz")]
+    pub rate_hz: f64,
+}
+
+fn default_slew_rate_hz() -> f64 {
+    60.0
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SlewMode {
+    /// Exponential approach: about 63% of the remaining distance per `amount` ms
+    TimeConstant,
+    /// Linear ramp of at most `amount` per second
+    MaxRate,
+}
+
+/// How a mapping turns presses into output.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum MappingMode {
+    /// Output follows the input
+    #[default]
+    Momentary,
+    /// Each press flips the state; releases are ignored
+    Toggle,
+    /// A press turns the state on; sends only when it changes
+    LatchOn,
+    /// A press turns the state off; sends only when it changes
+    LatchOff,
+}
+
+/// A predicate on the incoming message, checked after the address or MIDI
+/// filters have matched.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum Condition {
+    /// Numeric value within `min..=max`
+    Range {
+        value: ConditionValue,
+        min: f64,
+        max: f64,
+    },
+    Compare {
+        left: ConditionValue,
+        cmp: Comparison,
+        right: ConditionValue,
+    },
+    /// String value matching a regular expression
+    Regex {
+        value: ConditionValue,
+        pattern: String,
+    },
+}
+
+/// A value a condition reads from the incoming message, or a constant.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ConditionValue {
+    OscArg { index: usize },
+    /// MIDI velocity or value
+    MidiValue,
+    /// MIDI note, CC or parameter number
+    MidiNote,
+    Static { value: serde_json::Value },
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Comparison {
+    Eq,
+    Neq,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+/// Drops or delays a mapping's output before it is sent. All off by default.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct OutputFilter {
+    /// Most messages per second per output; the latest value is sent once
+    /// the limit allows
+    #[serde(default)]
+    pub max_rate_hz: Option<f64>,
+    /// Drop outputs identical to the last one sent
+    #[serde(default)]
+    pub dedupe: bool,
+    /// Drop outputs whose numeric values all moved less than this
+    #[serde(default)]
+    pub deadband: f64,
+}
+
+impl OutputFilter {
+    pub fn is_active(&self) -> bool {
+        self.max_rate_hz.is_some() || self.dedupe || self.deadband > 0.0
+    }
+}
+
+/// Rewrites applied by a `MidiToMidi` mapping; `None` keeps the incoming
+/// value. The value itself goes through `osc_transform` when one is set.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct MidiRemap {
+    #[serde(default)]
+    pub message_type: Option<MidiMessageType>,
+    #[serde(default)]
+    pub channel: Option<u8>,
+    /// Note, CC, program or NRPN/RPN parameter number
+    #[serde(default)]
+    pub number: Option<u16>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -79,6 +496,7 @@ pub enum Direction {
     OscToMidi,
     MidiToOsc,
     OscToOsc,
+    MidiToMidi,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -89,29 +507,209 @@ pub enum MidiMessageType {
     Cc,
     ProgramChange,
     Msc,
+    /// 14-bit value, 8192 = center
+    PitchBend,
+    ChannelPressure,
+    PolyAftertouch,
+}
+
+/// What a mapping follows from incoming MIDI clock and transport messages.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ClockInput {
+    /// Smoothed tempo from MIDI clock
+    Tempo,
+    /// Each quarter note of MIDI clock while the transport runs
+    Beat,
+    /// MIDI Start, Continue and Stop
+    Transport,
+}
+
+/// MIDI transport messages: Start (`FA`), Continue (`FB`) and Stop (`FC`).
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum TransportState {
+    Start,
+    Continue,
+    Stop,
+}
+
+impl TransportState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TransportState::Start => "start",
+            TransportState::Continue => "continue",
+            TransportState::Stop => "stop",
+        }
+    }
+}
+
+/// How a CC mapping reads and writes its value.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum CcMode {
+    /// Plain 7-bit controller
+    #[default]
+    SevenBit,
+    /// MSB on CC 0–31 paired with LSB on CC 32–63
+    FourteenBit,
+    /// CC 99/98 parameter select, CC 6/38 data entry
+    Nrpn,
+    /// CC 101/100 parameter select, CC 6/38 data entry
+    Rpn,
+}
+
+impl MidiMessageType {
+    /// Largest value the message type carries.
+    pub fn max_value(&self) -> u16 {
+        match self {
+            MidiMessageType::PitchBend => 16383,
+            _ => 127,
+        }
+    }
+
+    /// Whether the message carries a note or controller number. Pitch bend
+    /// and channel pressure apply to the whole channel.
+    pub fn has_note(&self) -> bool {
+        !matches!(
+            self,
+            MidiMessageType::PitchBend | MidiMessageType::ChannelPressure
+        )
+    }
 }
 
+/// MSC 1.0 commands.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "snake_case")]
 pub enum MscCommand {
     Go,
     Stop,
     Resume,
+    TimedGo,
+    Load,
+    Set,
+    Fire,
+    AllOff,
+    Restore,
+    Reset,
+    GoOff,
+    GoJamClock,
+    StandbyPlus,
+    StandbyMinus,
+    SequencePlus,
+    SequenceMinus,
+    StartClock,
+    StopClock,
+    ZeroClock,
+    SetClock,
+    MtcChaseOn,
+    MtcChaseOff,
+    OpenCueList,
+    CloseCueList,
+    OpenCuePath,
+    CloseCuePath,
 }
 
+/// MSC 1.0 command formats. Each group's first entry is its "general" format.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "snake_case")]
 pub enum MscCommandFormat {
     All,
     Lighting,
+    MovingLights,
+    ColorChangers,
+    Strobes,
+    Lasers,
+    Chasers,
     Sound,
+    Music,
+    CdPlayers,
+    EpromPlayback,
+    AudioTapeMachines,
+    Intercoms,
+    Amplifiers,
+    AudioEffects,
+    Equalizers,
+    Machinery,
+    Rigging,
+    Flys,
+    Lifts,
+    Turntables,
+    Trusses,
+    Robots,
+    Animation,
+    Floats,
+    Breakaways,
+    Barges,
+    Video,
+    VideoTapeMachines,
+    VideoCassetteMachines,
+    VideoDiscPlayers,
+    VideoSwitchers,
+    VideoEffects,
+    VideoCharacterGenerators,
+    VideoStillStores,
+    VideoMonitors,
+    Projection,
+    FilmProjectors,
+    SlideProjectors,
+    VideoProjectors,
+    Dissolvers,
+    ShutterControls,
+    ProcessControl,
+    HydraulicOil,
+    H2o,
+    Co2,
+    CompressedAir,
+    NaturalGas,
+    Fog,
+    Smoke,
+    CrackedHaze,
+    Pyro,
+    Fireworks,
+    Explosions,
+    Flame,
+    SmokePots,
+}
+
+/// Where an outgoing MSC cue number, list or path comes from.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum MscCueSource {
+    Static { value: String },
+    OscArg { index: usize },
+    Capture { index: usize },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum ValueSource {
-    Static { value: u8 },
+    /// 0–127, or 0–16383 for pitch bend
+    Static { value: u16 },
     OscArg { index: usize },
+    /// A wildcard segment of the matched `osc_address` (0 = first wildcard segment)
+    Capture { index: usize },
+}
+
+/// A MIDI channel or note/CC number. Fixed values serialize as a bare number,
+/// so mappings saved before dynamic fields existed load unchanged.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum MidiField {
+    Fixed(u8),
+    Dynamic(ValueSource),
+}
+
+impl MidiField {
+    /// Whether an incoming MIDI number satisfies this field. Fixed values must
+    /// match exactly; dynamic fields act as "any" on the input side.
+    pub fn accepts(&self, number: u8) -> bool {
+        match self {
+            MidiField::Fixed(n) => *n == number,
+            MidiField::Dynamic(ValueSource::Static { value }) => *value == u16::from(number),
+            MidiField::Dynamic(_) => true,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -120,6 +718,29 @@ pub enum OscArgType {
     Int,
     Float,
     String,
+    Blob,
+    Bool,
+    Nil,
+    Impulse,
+    Double,
+    Int64,
+    Timetag,
+    Char,
+    Color,
+    Midi,
+}
+
+/// How `osc_arg_types` is enforced on incoming OSC.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum OscArgMatch {
+    /// Argument types are not checked
+    #[default]
+    Any,
+    /// Argument count and types must equal `osc_arg_types`
+    Exact,
+    /// The leading arguments must match `osc_arg_types`; extra ones are allowed
+    Prefix,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -147,6 +768,20 @@ pub enum OscArgSource {
         #[serde(default, skip_serializing_if = "Option::is_none")]
         template: Option<String>,
     },
+    /// TIMED_GO / SET / SET_CLOCK time: `hh:mm:ss:ff.sf` as a string, seconds as a number
+    MscTime,
+    /// SET control number (0–16383)
+    MscSetControl,
+    /// SET value: raw 0–16383 as int, normalized 0.0–1.0 as float
+    MscSetValue,
+    /// Tempo input: beats per minute
+    Bpm,
+    /// Beat input: beat within the bar, from 1
+    Beat,
+    /// Beat input: bar number, from 1
+    Bar,
+    /// Transport input: `"start"`, `"continue"` or `"stop"`
+    Transport,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -205,6 +840,25 @@ pub struct EngineStatus {
     pub error: Option<String>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum OscConnectionState {
+    Connecting,
+    Connected,
+    Disconnected,
+}
+
+/// State of the persistent TCP connection to one OSC destination.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OscConnectionStatus {
+    pub destination: String,
+    pub host: String,
+    pub port: u16,
+    pub state: OscConnectionState,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MappingActivity {
     pub timestamp: String,
@@ -220,6 +874,9 @@ pub struct UnmatchedMessage {
     pub timestamp: String,
     pub protocol: String,
     pub display: String,
+    /// Set when a mapping's address matched but a check rejected the message
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
 }
 
 #[cfg(test)]
@@ -253,6 +910,55 @@ mod tests {
         }"#;
         let s: Settings = serde_json::from_str(json).unwrap();
         assert!(!s.launch_on_startup);
+        assert!(s.osc_destinations.is_empty());
+        assert_eq!(s.osc_tcp_send_framing, OscTcpFraming::Slip);
+        assert_eq!(s.osc_tcp_listen_framing, OscTcpListenFraming::Slip);
+    }
+
+    #[test]
+    fn test_settings_default_destination() {
+        let s = Settings::default();
+        let d = s.osc_destination(DEFAULT_OSC_DESTINATION).unwrap();
+        assert_eq!(d.host, "127.0.0.1");
+        assert_eq!(d.port, 9000);
+        assert_eq!(d.protocol, OscSendProtocol::Udp);
+        assert_eq!(d.timeout_ms, 3000);
+        assert!(s.osc_destination("qlab").is_none());
+    }
+
+    #[test]
+    fn test_settings_midi_port_lists() {
+        let json = r#"{
+            "osc_listen_port": 8000, "osc_listen_protocol": "udp",
+            "osc_send_host": "127.0.0.1", "osc_send_port": 9000, "osc_send_protocol": "udp",
+            "osc_tcp_send_timeout_ms": 3000,
+            "midi_input_port_name": "Keys", "midi_output_port_name": null,
+            "engine_auto_start": false
+        }"#;
+        let mut s: Settings = serde_json::from_str(json).unwrap();
+        assert_eq!(s.midi_input_ports(), vec!["Keys"]);
+        assert!(s.midi_output_ports().is_empty());
+        s.midi_input_port_names = vec!["Faders".to_string(), "Keys".to_string()];
+        s.midi_output_port_names = vec!["Show Control".to_string()];
+        assert_eq!(s.midi_input_ports(), vec!["Keys", "Faders"]);
+        assert_eq!(s.midi_output_ports(), vec!["Show Control"]);
+    }
+
+    #[test]
+    fn test_settings_named_destinations() {
+        let json = r#"{"name": "qlab", "host": "10.0.0.5", "port": 53000, "protocol": "tcp"}"#;
+        let qlab: OscDestination = serde_json::from_str(json).unwrap();
+        assert_eq!(qlab.timeout_ms, 3000);
+        assert_eq!(qlab.framing, OscTcpFraming::Slip);
+        let s = Settings {
+            osc_destinations: vec![qlab.clone()],
+            ..Default::default()
+        };
+        assert_eq!(s.osc_destination("qlab"), Some(qlab));
+        assert_eq!(
+            s.osc_destination(DEFAULT_OSC_DESTINATION),
+            Some(s.default_osc_destination())
+        );
     }
 
     #[test]
@@ -279,17 +985,37 @@ mod tests {
             direction: Direction::OscToMidi,
             osc_address: "/cue/go".to_string(),
             osc_arg_types: vec![],
+            osc_arg_match: OscArgMatch::Any,
             midi_message_type: MidiMessageType::NoteOn,
-            midi_channel: 1,
-            midi_note_or_cc: 60,
+            midi_channel: MidiField::Fixed(1),
+            midi_note_or_cc: MidiField::Fixed(60),
             midi_velocity_or_value: ValueSource::Static { value: 127 },
             midi_input_velocity: None,
+            midi_input_port: None,
+            midi_output_port: None,
             osc_args: vec![],
             osc_output_address: String::new(),
             osc_transform: None,
+            osc_destinations: vec![],
             msc_device_id: None,
             msc_command_format: None,
             msc_command: None,
+            msc_cue_number: None,
+            msc_cue_list: None,
+            msc_cue_path: None,
+            cc_mode: CcMode::SevenBit,
+            midi_parameter: 0,
+            clock_input: None,
+            transport_state: None,
+            midi_remap: MidiRemap::default(),
+            conditions: vec![],
+            mode: MappingMode::Momentary,
+            state_group: None,
+            persist_state: false,
+            slew: None,
+            output_filter: OutputFilter::default(),
+            steps: vec![],
+            retrigger: Retrigger::default(),
         };
         let json = serde_json::to_string_pretty(&m).unwrap();
         let m2: Mapping = serde_json::from_str(&json).unwrap();
@@ -304,17 +1030,37 @@ mod tests {
             direction: Direction::OscToMidi,
             osc_address: "/cue/go".to_string(),
             osc_arg_types: vec![],
+            osc_arg_match: OscArgMatch::Any,
             midi_message_type: MidiMessageType::NoteOn,
-            midi_channel: 1,
-            midi_note_or_cc: 60,
+            midi_channel: MidiField::Fixed(1),
+            midi_note_or_cc: MidiField::Fixed(60),
             midi_velocity_or_value: ValueSource::Static { value: 127 },
             midi_input_velocity: None,
+            midi_input_port: None,
+            midi_output_port: None,
             osc_args: vec![],
             osc_output_address: String::new(),
             osc_transform: None,
+            osc_destinations: vec![],
             msc_device_id: None,
             msc_command_format: None,
             msc_command: None,
+            msc_cue_number: None,
+            msc_cue_list: None,
+            msc_cue_path: None,
+            cc_mode: CcMode::SevenBit,
+            midi_parameter: 0,
+            clock_input: None,
+            transport_state: None,
+            midi_remap: MidiRemap::default(),
+            conditions: vec![],
+            mode: MappingMode::Momentary,
+            state_group: None,
+            persist_state: false,
+            slew: None,
+            output_filter: OutputFilter::default(),
+            steps: vec![],
+            retrigger: Retrigger::default(),
         };
         let v: serde_json::Value = serde_json::to_value(&m).unwrap();
         assert_eq!(v["direction"], "osc_to_midi");
@@ -331,6 +1077,32 @@ mod tests {
         assert_eq!(v["index"], 0);
     }
 
+    #[test]
+    fn test_midi_field_fixed_serializes_as_number() {
+        let v = serde_json::to_value(MidiField::Fixed(10)).unwrap();
+        assert_eq!(v, 10);
+        let f: MidiField = serde_json::from_str("10").unwrap();
+        assert_eq!(f, MidiField::Fixed(10));
+    }
+
+    #[test]
+    fn test_midi_field_capture_roundtrip() {
+        let f = MidiField::Dynamic(ValueSource::Capture { index: 0 });
+        let v = serde_json::to_value(&f).unwrap();
+        assert_eq!(v["type"], "capture");
+        assert_eq!(v["index"], 0);
+        let f2: MidiField = serde_json::from_value(v).unwrap();
+        assert_eq!(f, f2);
+    }
+
+    #[test]
+    fn test_midi_field_accepts() {
+        assert!(MidiField::Fixed(7).accepts(7));
+        assert!(!MidiField::Fixed(7).accepts(8));
+        assert!(MidiField::Dynamic(ValueSource::Static { value: 7 }).accepts(7));
+        assert!(MidiField::Dynamic(ValueSource::Capture { index: 0 }).accepts(99));
+    }
+
     #[test]
     fn test_osc_arg_def_serialization() {
         let arg = OscArgDef {
@@ -350,20 +1122,40 @@ mod tests {
             direction: Direction::MidiToOsc,
             osc_address: "/mix/volume".to_string(),
             osc_arg_types: vec![OscArgType::Float],
+            osc_arg_match: OscArgMatch::Any,
             midi_message_type: MidiMessageType::Cc,
-            midi_channel: 1,
-            midi_note_or_cc: 7,
+            midi_channel: MidiField::Fixed(1),
+            midi_note_or_cc: MidiField::Fixed(7),
             midi_velocity_or_value: ValueSource::Static { value: 0 },
             midi_input_velocity: None,
+            midi_input_port: None,
+            midi_output_port: None,
             osc_args: vec![OscArgDef {
                 arg_type: OscArgType::Float,
                 source: OscArgSource::MidiValue,
             }],
             osc_output_address: String::new(),
             osc_transform: None,
+            osc_destinations: vec![],
             msc_device_id: None,
             msc_command_format: None,
             msc_command: None,
+            msc_cue_number: None,
+            msc_cue_list: None,
+            msc_cue_path: None,
+            cc_mode: CcMode::SevenBit,
+            midi_parameter: 0,
+            clock_input: None,
+            transport_state: None,
+            midi_remap: MidiRemap::default(),
+            conditions: vec![],
+            mode: MappingMode::Momentary,
+            state_group: None,
+            persist_state: false,
+            slew: None,
+            output_filter: OutputFilter::default(),
+            steps: vec![],
+            retrigger: Retrigger::default(),
         };
         let json = serde_json::to_string_pretty(&m).unwrap();
         let m2: Mapping = serde_json::from_str(&json).unwrap();
@@ -387,8 +1179,10 @@ mod tests {
         }"#;
         let m: Mapping = serde_json::from_str(json).unwrap();
         assert_eq!(m.midi_input_velocity, None);
+        assert_eq!(m.osc_arg_match, OscArgMatch::Any);
         assert_eq!(m.osc_output_address, "");
         assert_eq!(m.osc_transform, None);
+        assert!(m.osc_destinations.is_empty());
     }
 
     #[test]
@@ -399,11 +1193,14 @@ mod tests {
             direction: Direction::OscToOsc,
             osc_address: "/meters/1".to_string(),
             osc_arg_types: vec![],
+            osc_arg_match: OscArgMatch::Any,
             midi_message_type: MidiMessageType::NoteOn,
-            midi_channel: 1,
-            midi_note_or_cc: 60,
+            midi_channel: MidiField::Fixed(1),
+            midi_note_or_cc: MidiField::Fixed(60),
             midi_velocity_or_value: ValueSource::Static { value: 0 },
             midi_input_velocity: None,
+            midi_input_port: None,
+            midi_output_port: None,
             osc_args: vec![],
             osc_output_address: "/plugin/volume".to_string(),
             osc_transform: Some(OscTransform {
@@ -416,9 +1213,26 @@ mod tests {
                 output_type: OscOutputType::Float,
                 smoothing: 1.0,
             }),
+            osc_destinations: vec![],
             msc_device_id: None,
             msc_command_format: None,
             msc_command: None,
+            msc_cue_number: None,
+            msc_cue_list: None,
+            msc_cue_path: None,
+            cc_mode: CcMode::SevenBit,
+            midi_parameter: 0,
+            clock_input: None,
+            transport_state: None,
+            midi_remap: MidiRemap::default(),
+            conditions: vec![],
+            mode: MappingMode::Momentary,
+            state_group: None,
+            persist_state: false,
+            slew: None,
+            output_filter: OutputFilter::default(),
+            steps: vec![],
+            retrigger: Retrigger::default(),
         };
         let json = serde_json::to_string_pretty(&m).unwrap();
         let m2: Mapping = serde_json::from_str(&json).unwrap();
@@ -455,6 +1269,12 @@ mod tests {
         assert_eq!(v, "go");
         let v = serde_json::to_value(MscCommandFormat::Lighting).unwrap();
         assert_eq!(v, "lighting");
+        let v = serde_json::to_value(MscCommand::GoJamClock).unwrap();
+        assert_eq!(v, "go_jam_clock");
+        let v = serde_json::to_value(MscCommandFormat::VideoTapeMachines).unwrap();
+        assert_eq!(v, "video_tape_machines");
+        let source: OscArgSource = serde_json::from_str(r#"{"type":"msc_set_value"}"#).unwrap();
+        assert_eq!(source, OscArgSource::MscSetValue);
     }
 
     #[test]
@@ -0,0 +1,469 @@
+use crate::midi_engine::{encode_full_frame, encode_quarter_frame, MidiOutputs, Timecode};
+use crate::models::{MtcArgFormat, MtcGenerator, MtcOutput, MtcRate};
+use crate::router::{IncomingMessage, OscArgValue, OutputAction};
+use log::{info, warn};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// How long without quarter frames before running timecode counts as
+/// stopped. Quarter frames arrive every 8–10 ms while a source plays.
+const DROPOUT: Duration = Duration::from_millis(250);
+
+/// Turns decoded MIDI timecode into OSC messages and notices when the
+/// source stops sending.
+pub struct MtcPublisher {
+    output: MtcOutput,
+    last: Option<Timecode>,
+    /// When running timecode counts as dropped out if nothing arrives
+    deadline: Option<Instant>,
+}
+
+impl MtcPublisher {
+    pub fn new(output: MtcOutput) -> Self {
+        Self {
+            output,
+            last: None,
+            deadline: None,
+        }
+    }
+
+    /// The message to send for a decoded position, if publishing is on.
+    pub fn update(&mut self, time: Timecode, running: bool, now: Instant) -> Option<OutputAction> {
+        if !self.output.enabled {
+            return None;
+        }
+        self.last = Some(time);
+        self.deadline = running.then(|| now + DROPOUT);
+        Some(self.message(&time, running))
+    }
+
+    pub fn dropout_deadline(&self) -> Option<Instant> {
+        self.deadline
+    }
+
+    /// Once the dropout deadline has passed, the last position marked as
+    /// stopped.
+    pub fn check_dropout(&mut self, now: Instant) -> Option<OutputAction> {
+        if self.deadline.is_none_or(|deadline| now < deadline) {
+            return None;
+        }
+        self.deadline = None;
+        let last = self.last?;
+        Some(self.message(&last, false))
+    }
+
+    fn message(&self, time: &Timecode, running: bool) -> OutputAction {
+        OutputAction::Osc {
+            address: self.output.address.clone(),
+            args: timecode_args(time, self.output.format, running),
+            destinations: self.output.destinations.clone(),
+        }
+    }
+}
+
+/// OSC arguments for a timecode position, ending with `"running"` or
+/// `"stopped"`.
+pub fn timecode_args(time: &Timecode, format: MtcArgFormat, running: bool) -> Vec<OscArgValue> {
+    let mut args = Vec::new();
+    if matches!(format, MtcArgFormat::String | MtcArgFormat::Both) {
+        args.push(OscArgValue::String(time.to_string()));
+    }
+    if matches!(format, MtcArgFormat::Numeric | MtcArgFormat::Both) {
+        args.extend([
+            OscArgValue::Int(i32::from(time.hours)),
+            OscArgValue::Int(i32::from(time.minutes)),
+            OscArgValue::Int(i32::from(time.seconds)),
+            OscArgValue::Int(i32::from(time.frames)),
+            OscArgValue::Float(Timecode::fps(time.rate) as f32),
+        ]);
+    }
+    let state = if running { "running" } else { "stopped" };
+    args.push(OscArgValue::String(state.to_string()));
+    args
+}
+
+/// A transport command for the MTC generator.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Transport {
+    Play,
+    Stop,
+    Locate(Timecode),
+}
+
+/// Reads `<prefix>/play`, `<prefix>/stop` and `<prefix>/locate`. `None`
+/// means the address is not a transport command.
+pub fn parse_transport(
+    prefix: &str,
+    address: &str,
+    args: &[OscArgValue],
+    rate: MtcRate,
+) -> Option<Result<Transport, String>> {
+    match address.strip_prefix(prefix)? {
+        "/play" => Some(Ok(Transport::Play)),
+        "/stop" => Some(Ok(Transport::Stop)),
+        "/locate" => Some(parse_locate(args, rate).map(Transport::Locate)),
+        _ => None,
+    }
+}
+
+/// Locate takes `"hh:mm:ss:ff"` or hours, minutes, seconds and frames as
+/// four numbers.
+fn parse_locate(args: &[OscArgValue], rate: MtcRate) -> Result<Timecode, String> {
+    let fields: Vec<i64> = match args {
+        [OscArgValue::String(text)] => text
+            .split([':', ';'])
+            .map(|field| field.trim().parse::<i64>())
+            .collect::<Result<_, _>>()
+            .map_err(|_| format!("Invalid timecode: {}", text))?,
+        _ => args
+            .iter()
+            .map(|arg| match arg {
+                OscArgValue::Int(i) => Some(i64::from(*i)),
+                OscArgValue::Float(f) => Some(*f as i64),
+                OscArgValue::Double(d) => Some(*d as i64),
+                _ => None,
+            })
+            .collect::<Option<_>>()
+            .ok_or("Locate expects a timecode string or four numbers")?,
+    };
+    let [hours, minutes, seconds, mut frames] = fields[..] else {
+        return Err(format!("Locate expects hh:mm:ss:ff, got {:?}", args));
+    };
+    if !(0..24).contains(&hours)
+        || !(0..60).contains(&minutes)
+        || !(0..60).contains(&seconds)
+        || !(0..Timecode::nominal_fps(rate)).contains(&frames)
+    {
+        return Err(format!("Timecode out of range: {:?}", args));
+    }
+    // Drop-frame skips frames 0 and 1 of most minutes; use the next one
+    if rate == MtcRate::Fps2997Drop && seconds == 0 && minutes % 10 != 0 {
+        frames = frames.max(2);
+    }
+    Ok(Timecode {
+        hours: hours as u8,
+        minutes: minutes as u8,
+        seconds: seconds as u8,
+        frames: frames as u8,
+        rate,
+    })
+}
+
+/// Quarter-frame schedule of the MTC generator. Quarter frame `n` after
+/// play is due `n / (4 × fps)` seconds after it, so late sends don't add
+/// up to drift.
+pub struct MtcClock {
+    rate: MtcRate,
+    /// Position described by the first quarter-frame cycle
+    origin: Timecode,
+    /// When play started and quarter frames sent since, while playing
+    playing: Option<(Instant, u64)>,
+}
+
+impl MtcClock {
+    pub fn new(rate: MtcRate) -> Self {
+        Self {
+            rate,
+            origin: Timecode::from_frame_count(0, rate),
+            playing: None,
+        }
+    }
+
+    fn quarter_frames_per_second(&self) -> f64 {
+        4.0 * Timecode::fps(self.rate)
+    }
+
+    fn due(&self, started: Instant, n: u64) -> Instant {
+        started + Duration::from_secs_f64(n as f64 / self.quarter_frames_per_second())
+    }
+
+    /// When the next quarter frame is due, while playing.
+    pub fn next_due(&self) -> Option<Instant> {
+        self.playing.map(|(started, sent)| self.due(started, sent))
+    }
+
+    pub fn position(&self, now: Instant) -> Timecode {
+        match self.playing {
+            Some((started, _)) => {
+                let elapsed = now.saturating_duration_since(started).as_secs_f64();
+                self.origin
+                    .add_frames((elapsed * Timecode::fps(self.rate)) as i64)
+            }
+            None => self.origin,
+        }
+    }
+
+    pub fn play(&mut self, now: Instant) {
+        if self.playing.is_none() {
+            self.playing = Some((now, 0));
+        }
+    }
+
+    pub fn stop(&mut self, now: Instant) {
+        self.origin = self.position(now);
+        self.playing = None;
+    }
+
+    /// Jump to `tc`, carrying on playing from there if playing. Returns the
+    /// full frame that tells receivers.
+    pub fn locate(&mut self, tc: Timecode, now: Instant) -> [u8; 10] {
+        self.origin = tc;
+        if self.playing.is_some() {
+            self.playing = Some((now, 0));
+        }
+        encode_full_frame(&tc)
+    }
+
+    /// The quarter frames due by `now`. After a stall longer than a
+    /// quarter-frame cycle the missed ones are skipped and sending resumes
+    /// at the start of the next cycle.
+    pub fn poll(&mut self, now: Instant) -> Vec<[u8; 2]> {
+        let Some((started, mut sent)) = self.playing else {
+            return Vec::new();
+        };
+        let elapsed = now.saturating_duration_since(started).as_secs_f64();
+        let current = (elapsed * self.quarter_frames_per_second()) as u64;
+        if current >= sent + 8 {
+            sent = current.div_ceil(8) * 8;
+        }
+        let mut frames = Vec::new();
+        while self.due(started, sent) <= now {
+            let tc = self.origin.add_frames(2 * (sent / 8) as i64);
+            frames.push(encode_quarter_frame(&tc, (sent % 8) as u8));
+            sent += 1;
+        }
+        self.playing = Some((started, sent));
+        frames
+    }
+}
+
+/// Runs the MTC generator on its own thread, sending straight to the MIDI
+/// output so quarter frames keep their spacing however busy the async
+/// runtime is. The thread ends when this is dropped.
+pub struct MtcMaster {
+    address: String,
+    rate: MtcRate,
+    tx: Sender<Transport>,
+}
+
+impl MtcMaster {
+    pub fn start(config: &MtcGenerator, outputs: Arc<MidiOutputs>) -> Result<Self, String> {
+        let (tx, rx) = mpsc::channel();
+        let clock = MtcClock::new(config.rate);
+        let port = config.port.clone();
+        std::thread::Builder::new()
+            .name("mtc-generator".to_string())
+            .spawn(move || run_generator(rx, clock, port, outputs))
+            .map_err(|e| format!("Failed to start MTC generator: {}", e))?;
+        info!("MTC generator on {} at {:?}", config.address, config.rate);
+        Ok(Self {
+            address: config.address.clone(),
+            rate: config.rate,
+            tx,
+        })
+    }
+
+    /// Pass a transport command on to the generator. Returns false when
+    /// `msg` isn't one, so it can be routed as usual.
+    pub fn handle(&self, msg: &IncomingMessage) -> bool {
+        let IncomingMessage::Osc { address, args } = msg else {
+            return false;
+        };
+        match parse_transport(&self.address, address, args, self.rate) {
+            Some(Ok(transport)) => {
+                let _ = self.tx.send(transport);
+                true
+            }
+            Some(Err(e)) => {
+                warn!("MTC {}: {}", address, e);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+fn run_generator(
+    rx: Receiver<Transport>,
+    mut clock: MtcClock,
+    port: Option<String>,
+    outputs: Arc<MidiOutputs>,
+) {
+    // Warn once per run of failed sends rather than for every quarter frame
+    let mut failing = false;
+    let mut send = |bytes: &[u8]| match outputs.send(port.as_deref(), bytes) {
+        Ok(()) => failing = false,
+        Err(e) => {
+            if !failing {
+                warn!("MTC generator: {}", e);
+            }
+            failing = true;
+        }
+    };
+    loop {
+        let command = match clock.next_due() {
+            Some(due) => match rx.recv_timeout(due.saturating_duration_since(Instant::now())) {
+                Ok(command) => Some(command),
+                Err(RecvTimeoutError::Timeout) => None,
+                Err(RecvTimeoutError::Disconnected) => return,
+            },
+            None => match rx.recv() {
+                Ok(command) => Some(command),
+                Err(_) => return,
+            },
+        };
+        let now = Instant::now();
+        match command {
+            Some(Transport::Play) => clock.play(now),
+            Some(Transport::Stop) => clock.stop(now),
+            Some(Transport::Locate(tc)) => send(&clock.locate(tc, now)),
+            None => {}
+        }
+        for quarter_frame in clock.poll(Instant::now()) {
+            send(&quarter_frame);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::midi_engine::MtcDecoder;
+
+    fn tc() -> Timecode {
+        Timecode {
+            hours: 1,
+            minutes: 2,
+            seconds: 3,
+            frames: 4,
+            rate: MtcRate::Fps25,
+        }
+    }
+
+    #[test]
+    fn test_timecode_args() {
+        let args = timecode_args(&tc(), MtcArgFormat::Both, true);
+        assert_eq!(
+            args,
+            vec![
+                OscArgValue::String("01:02:03:04".into()),
+                OscArgValue::Int(1),
+                OscArgValue::Int(2),
+                OscArgValue::Int(3),
+                OscArgValue::Int(4),
+                OscArgValue::Float(25.0),
+                OscArgValue::String("running".into()),
+            ]
+        );
+        assert_eq!(
+            timecode_args(&tc(), MtcArgFormat::String, false),
+            vec![
+                OscArgValue::String("01:02:03:04".into()),
+                OscArgValue::String("stopped".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_dropout_marks_stopped() {
+        let mut publisher = MtcPublisher::new(MtcOutput {
+            enabled: true,
+            format: MtcArgFormat::String,
+            ..Default::default()
+        });
+        let t0 = Instant::now();
+        assert!(publisher.update(tc(), true, t0).is_some());
+        assert_eq!(publisher.dropout_deadline(), Some(t0 + DROPOUT));
+        assert!(publisher.check_dropout(t0 + DROPOUT / 2).is_none());
+
+        match publisher.check_dropout(t0 + DROPOUT) {
+            Some(OutputAction::Osc { address, args, .. }) => {
+                assert_eq!(address, "/timecode");
+                assert_eq!(args.last(), Some(&OscArgValue::String("stopped".into())));
+            }
+            other => panic!("Expected Osc, got {:?}", other),
+        }
+        // Only reported once
+        assert_eq!(publisher.dropout_deadline(), None);
+        assert!(publisher.check_dropout(t0 + DROPOUT * 2).is_none());
+    }
+
+    #[test]
+    fn test_disabled_publishes_nothing() {
+        let mut publisher = MtcPublisher::new(MtcOutput::default());
+        assert!(publisher.update(tc(), true, Instant::now()).is_none());
+        assert_eq!(publisher.dropout_deadline(), None);
+    }
+
+    #[test]
+    fn test_parse_transport() {
+        let rate = MtcRate::Fps25;
+        assert_eq!(
+            parse_transport("/mtc", "/mtc/play", &[], rate),
+            Some(Ok(Transport::Play))
+        );
+        assert_eq!(parse_transport("/mtc", "/other/play", &[], rate), None);
+        let locate = [OscArgValue::String("01:02:03:04".into())];
+        assert_eq!(
+            parse_transport("/mtc", "/mtc/locate", &locate, rate),
+            Some(Ok(Transport::Locate(tc())))
+        );
+        let numbers = [1, 2, 3, 4].map(OscArgValue::Int);
+        assert_eq!(
+            parse_transport("/mtc", "/mtc/locate", &numbers, rate),
+            Some(Ok(Transport::Locate(tc())))
+        );
+        let bad = [OscArgValue::String("01:02:03:25".into())];
+        assert!(matches!(
+            parse_transport("/mtc", "/mtc/locate", &bad, rate),
+            Some(Err(_))
+        ));
+    }
+
+    #[test]
+    fn test_clock_schedule() {
+        let mut clock = MtcClock::new(MtcRate::Fps25);
+        let t0 = Instant::now();
+        assert_eq!(clock.locate(tc(), t0)[..5], [0xF0, 0x7F, 0x7F, 0x01, 0x01]);
+        assert_eq!(clock.next_due(), None);
+
+        clock.play(t0);
+        // 100 quarter frames a second at 25 fps
+        let frames = clock.poll(t0 + Duration::from_millis(75));
+        assert_eq!(frames.len(), 8);
+        assert_eq!(clock.next_due(), Some(t0 + Duration::from_millis(80)));
+
+        // The decoder reads the first cycle as two frames on
+        let mut decoder = MtcDecoder::default();
+        let decoded = frames.iter().filter_map(|qf| decoder.push(qf)).last();
+        assert_eq!(decoded, Some((tc().add_frames(2), true)));
+
+        clock.stop(t0 + Duration::from_millis(85));
+        assert_eq!(
+            clock.position(t0 + Duration::from_secs(9)),
+            tc().add_frames(2)
+        );
+        assert!(clock.poll(t0 + Duration::from_secs(9)).is_empty());
+    }
+
+    #[test]
+    fn test_clock_skips_missed_cycles() {
+        let mut clock = MtcClock::new(MtcRate::Fps25);
+        let t0 = Instant::now();
+        clock.locate(tc(), t0);
+        clock.play(t0);
+        // Stalled for a second: wait for the next cycle instead of bursting
+        // out a hundred quarter frames
+        assert!(clock.poll(t0 + Duration::from_millis(1000)).is_empty());
+        assert_eq!(clock.next_due(), Some(t0 + Duration::from_millis(1040)));
+        let mut decoder = MtcDecoder::default();
+        let decoded = clock
+            .poll(t0 + Duration::from_millis(1115))
+            .iter()
+            .filter_map(|qf| decoder.push(qf))
+            .last();
+        // The cycle at 26 frames, read two frames on
+        assert_eq!(decoded, Some((tc().add_frames(28), true)));
+    }
+}
@@ -1,11 +1,14 @@
+use crate::models::{OscTcpFraming, OscTcpListenFraming};
+use crate::osc_tcp::TcpSenderPool;
 use crate::router::{IncomingMessage, OscArgValue, OutputAction};
 use log::{error, info, warn};
-use rosc::{OscMessage, OscPacket, OscType};
+use rosc::{OscArray, OscColor, OscMessage, OscMidiMessage, OscPacket, OscTime, OscType};
 use std::net::SocketAddr;
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use std::time::SystemTime;
+use tokio::io::AsyncReadExt;
 use tokio::net::{TcpListener, TcpStream, UdpSocket};
 use tokio::sync::mpsc;
-use tokio::time::{timeout, Duration};
+use tokio::time::Duration;
 use tokio_util::sync::CancellationToken;
 
 pub async fn start_udp_listener(
@@ -28,9 +31,7 @@ pub async fn start_udp_listener(
                 result = socket.recv_from(&mut buf) => {
                     match result {
                         Ok((size, _src)) => {
-                            if let Some(msg) = decode_osc_udp(&buf[..size]) {
-                                let _ = tx.send(msg);
-                            }
+                            deliver(decode_osc_udp(&buf[..size]), &tx, &token);
                         }
                         Err(e) => {
                             error!("UDP recv error: {}", e);
@@ -46,6 +47,7 @@ pub async fn start_udp_listener(
 
 pub async fn start_tcp_listener(
     port: u16,
+    framing: OscTcpListenFraming,
     tx: mpsc::UnboundedSender<IncomingMessage>,
     token: CancellationToken,
 ) -> Result<(), String> {
@@ -66,7 +68,7 @@ pub async fn start_tcp_listener(
                             info!("OSC TCP client connected: {}", addr);
                             let tx = tx.clone();
                             let token = token.clone();
-                            tokio::spawn(handle_tcp_client(stream, addr, tx, token));
+                            tokio::spawn(handle_tcp_client(stream, addr, framing.clone(), tx, token));
                         }
                         Err(e) => {
                             error!("TCP accept error: {}", e);
@@ -88,15 +90,131 @@ const SLIP_ESC_END: u8 = 0xDC;
 const SLIP_ESC_ESC: u8 = 0xDD;
 const SLIP_MAX_PACKET: usize = 65536;
 
+/// Splits a TCP byte stream into OSC packets, keeping partial frames
+/// between reads.
+struct TcpFrameDecoder {
+    mode: FrameMode,
+    buf: Vec<u8>,
+    in_escape: bool,
+}
+
+enum FrameMode {
+    Slip,
+    LengthPrefix,
+    Detect,
+}
+
+impl TcpFrameDecoder {
+    fn new(framing: &OscTcpListenFraming) -> Self {
+        let mode = match framing {
+            OscTcpListenFraming::Slip => FrameMode::Slip,
+            OscTcpListenFraming::LengthPrefix => FrameMode::LengthPrefix,
+            OscTcpListenFraming::Auto => FrameMode::Detect,
+        };
+        Self {
+            mode,
+            buf: Vec::new(),
+            in_escape: false,
+        }
+    }
+
+    /// Feed newly read bytes and return every completed packet. An error
+    /// means the stream can no longer be framed and should be closed.
+    fn push(&mut self, data: &[u8], peer: SocketAddr) -> Result<Vec<Vec<u8>>, String> {
+        if let (FrameMode::Detect, Some(&first)) = (&self.mode, data.first()) {
+            // A length prefix for any sane packet size starts with 0x00; a SLIP
+            // stream starts with END or directly with '/' or '#bundle'
+            self.mode = if first == 0x00 {
+                FrameMode::LengthPrefix
+            } else {
+                FrameMode::Slip
+            };
+        }
+        match self.mode {
+            FrameMode::Slip => Ok(self.push_slip(data, peer)),
+            FrameMode::LengthPrefix => self.push_length_prefixed(data),
+            FrameMode::Detect => Ok(Vec::new()),
+        }
+    }
+
+    fn push_slip(&mut self, data: &[u8], peer: SocketAddr) -> Vec<Vec<u8>> {
+        let mut packets = Vec::new();
+        for &byte in data {
+            if self.in_escape {
+                self.in_escape = false;
+                match byte {
+                    SLIP_ESC_END => self.buf.push(SLIP_END),
+                    SLIP_ESC_ESC => self.buf.push(SLIP_ESC),
+                    _ => {
+                        warn!("OSC TCP SLIP: invalid escape byte 0x{:02X} from {}", byte, peer);
+                        self.buf.clear();
+                    }
+                }
+                continue;
+            }
+            match byte {
+                SLIP_END => {
+                    if !self.buf.is_empty() {
+                        packets.push(std::mem::take(&mut self.buf));
+                    }
+                }
+                SLIP_ESC => self.in_escape = true,
+                _ => {
+                    self.buf.push(byte);
+                    if self.buf.len() > SLIP_MAX_PACKET {
+                        warn!("OSC TCP SLIP: oversized packet (>{} bytes) from {}, dropping", SLIP_MAX_PACKET, peer);
+                        self.buf.clear();
+                    }
+                }
+            }
+        }
+        packets
+    }
+
+    fn push_length_prefixed(&mut self, data: &[u8]) -> Result<Vec<Vec<u8>>, String> {
+        self.buf.extend_from_slice(data);
+        let mut packets = Vec::new();
+        let mut offset = 0;
+        while let Some(prefix) = self.buf.get(offset..offset + 4) {
+            let len = u32::from_be_bytes([prefix[0], prefix[1], prefix[2], prefix[3]]) as usize;
+            if len > SLIP_MAX_PACKET {
+                return Err(format!("oversized packet ({} bytes)", len));
+            }
+            let Some(packet) = self.buf.get(offset + 4..offset + 4 + len) else {
+                break;
+            };
+            if !packet.is_empty() {
+                packets.push(packet.to_vec());
+            }
+            offset += 4 + len;
+        }
+        self.buf.drain(..offset);
+        Ok(packets)
+    }
+}
+
+/// Frame an encoded OSC packet for a TCP stream.
+pub(crate) fn frame_tcp_packet(framing: &OscTcpFraming, data: &[u8]) -> Vec<u8> {
+    match framing {
+        OscTcpFraming::Slip => slip_encode(data),
+        OscTcpFraming::LengthPrefix => {
+            let mut framed = Vec::with_capacity(data.len() + 4);
+            framed.extend_from_slice(&(data.len() as u32).to_be_bytes());
+            framed.extend_from_slice(data);
+            framed
+        }
+    }
+}
+
 async fn handle_tcp_client(
     mut stream: TcpStream,
     peer: SocketAddr,
+    framing: OscTcpListenFraming,
     tx: mpsc::UnboundedSender<IncomingMessage>,
     token: CancellationToken,
 ) {
     let mut read_buf = [0u8; 4096];
-    let mut packet_buf: Vec<u8> = Vec::new();
-    let mut in_escape = false;
+    let mut decoder = TcpFrameDecoder::new(&framing);
 
     loop {
         tokio::select! {
@@ -104,43 +222,17 @@ async fn handle_tcp_client(
             result = stream.read(&mut read_buf) => {
                 match result {
                     Ok(0) => break, // connection closed
-                    Ok(n) => {
-                        for &byte in &read_buf[..n] {
-                            if in_escape {
-                                in_escape = false;
-                                match byte {
-                                    SLIP_ESC_END => packet_buf.push(SLIP_END),
-                                    SLIP_ESC_ESC => packet_buf.push(SLIP_ESC),
-                                    _ => {
-                                        warn!("OSC TCP SLIP: invalid escape byte 0x{:02X} from {}", byte, peer);
-                                        packet_buf.clear();
-                                    }
-                                }
-                            } else {
-                                match byte {
-                                    SLIP_END => {
-                                        if !packet_buf.is_empty() {
-                                            if let Some(msg) = decode_osc_udp(&packet_buf) {
-                                                let _ = tx.send(msg);
-                                            }
-                                            packet_buf.clear();
-                                        }
-                                    }
-                                    SLIP_ESC => {
-                                        in_escape = true;
-                                    }
-                                    _ => {
-                                        packet_buf.push(byte);
-                                        if packet_buf.len() > SLIP_MAX_PACKET {
-                                            warn!("OSC TCP SLIP: oversized packet (>{} bytes) from {}, dropping", SLIP_MAX_PACKET, peer);
-                                            packet_buf.clear();
-                                            in_escape = false;
-                                        }
-                                    }
-                                }
+                    Ok(n) => match decoder.push(&read_buf[..n], peer) {
+                        Ok(packets) => {
+                            for packet in packets {
+                                deliver(decode_osc_udp(&packet), &tx, &token);
                             }
                         }
-                    }
+                        Err(e) => {
+                            warn!("OSC TCP framing error from {}: {}, closing connection", peer, e);
+                            break;
+                        }
+                    },
                     Err(e) => {
                         error!("OSC TCP read error from {}: {}", peer, e);
                         break;
@@ -152,35 +244,199 @@ async fn handle_tcp_client(
     info!("OSC TCP client disconnected: {}", peer);
 }
 
-fn decode_osc_udp(data: &[u8]) -> Option<IncomingMessage> {
+/// A decoded OSC message and when it should reach the router (`None` = now).
+type Scheduled = (Option<SystemTime>, IncomingMessage);
+
+// Seconds between the OSC/NTP epoch (1900) and the Unix epoch (RFC 5905)
+const OSC_UNIX_OFFSET: u32 = 2_208_988_800;
+/// Bundles dated further ahead than this are assumed to come from a sender
+/// whose clock runs ahead, and are delivered at once rather than held
+const MAX_BUNDLE_DELAY: Duration = Duration::from_secs(60);
+
+fn decode_osc_udp(data: &[u8]) -> Vec<Scheduled> {
     match rosc::decoder::decode_udp(data) {
         Ok((_rest, packet)) => decode_osc_packet(packet),
         Err(e) => {
             warn!("OSC decode error: {:?}", e);
-            None
+            Vec::new()
         }
     }
 }
 
-fn decode_osc_packet(packet: OscPacket) -> Option<IncomingMessage> {
+fn decode_osc_packet(packet: OscPacket) -> Vec<Scheduled> {
+    let mut out = Vec::new();
+    flatten_osc_packet(packet, None, &mut out);
+    out
+}
+
+/// Unpack a packet into its messages, depth first. A nested bundle is never
+/// delivered earlier than the bundle that contains it.
+fn flatten_osc_packet(
+    packet: OscPacket,
+    parent_time: Option<SystemTime>,
+    out: &mut Vec<Scheduled>,
+) {
     match packet {
         OscPacket::Message(msg) => {
-            let args: Vec<OscArgValue> = msg
-                .args
-                .into_iter()
-                .filter_map(|a| match a {
-                    OscType::Int(i) => Some(OscArgValue::Int(i)),
-                    OscType::Float(f) => Some(OscArgValue::Float(f)),
-                    OscType::String(s) => Some(OscArgValue::String(s)),
-                    _ => None,
-                })
-                .collect();
-            Some(IncomingMessage::Osc {
-                address: msg.addr,
-                args,
-            })
+            let args: Vec<OscArgValue> = msg.args.into_iter().map(osc_type_to_arg).collect();
+            out.push((
+                parent_time,
+                IncomingMessage::Osc {
+                    address: msg.addr,
+                    args,
+                },
+            ));
         }
-        OscPacket::Bundle(_) => None, // Post-MVP
+        OscPacket::Bundle(bundle) => {
+            let time = match (parent_time, osc_time_to_system_time(bundle.timetag)) {
+                (Some(parent), Some(own)) => Some(parent.max(own)),
+                (parent, own) => parent.or(own),
+            };
+            for inner in bundle.content {
+                flatten_osc_packet(inner, time, out);
+            }
+        }
+    }
+}
+
+fn osc_type_to_arg(arg: OscType) -> OscArgValue {
+    match arg {
+        OscType::Int(i) => OscArgValue::Int(i),
+        OscType::Float(f) => OscArgValue::Float(f),
+        OscType::String(s) => OscArgValue::String(s),
+        OscType::Blob(b) => OscArgValue::Blob(b),
+        OscType::Bool(b) => OscArgValue::Bool(b),
+        OscType::Nil => OscArgValue::Nil,
+        OscType::Inf => OscArgValue::Impulse,
+        OscType::Double(d) => OscArgValue::Double(d),
+        OscType::Long(l) => OscArgValue::Int64(l),
+        OscType::Time(t) => OscArgValue::TimeTag {
+            seconds: t.seconds,
+            fractional: t.fractional,
+        },
+        OscType::Char(c) => OscArgValue::Char(c),
+        OscType::Color(c) => OscArgValue::Color {
+            red: c.red,
+            green: c.green,
+            blue: c.blue,
+            alpha: c.alpha,
+        },
+        OscType::Midi(m) => OscArgValue::Midi {
+            port: m.port,
+            status: m.status,
+            data1: m.data1,
+            data2: m.data2,
+        },
+        OscType::Array(a) => {
+            OscArgValue::Array(a.content.into_iter().map(osc_type_to_arg).collect())
+        }
+    }
+}
+
+fn arg_to_osc_type(arg: &OscArgValue) -> OscType {
+    match arg {
+        OscArgValue::Int(i) => OscType::Int(*i),
+        OscArgValue::Float(f) => OscType::Float(*f),
+        OscArgValue::String(s) => OscType::String(s.clone()),
+        OscArgValue::Blob(b) => OscType::Blob(b.clone()),
+        OscArgValue::Bool(b) => OscType::Bool(*b),
+        OscArgValue::Nil => OscType::Nil,
+        OscArgValue::Impulse => OscType::Inf,
+        OscArgValue::Double(d) => OscType::Double(*d),
+        OscArgValue::Int64(l) => OscType::Long(*l),
+        OscArgValue::TimeTag {
+            seconds,
+            fractional,
+        } => OscType::Time(OscTime {
+            seconds: *seconds,
+            fractional: *fractional,
+        }),
+        OscArgValue::Char(c) => OscType::Char(*c),
+        OscArgValue::Color {
+            red,
+            green,
+            blue,
+            alpha,
+        } => OscType::Color(OscColor {
+            red: *red,
+            green: *green,
+            blue: *blue,
+            alpha: *alpha,
+        }),
+        OscArgValue::Midi {
+            port,
+            status,
+            data1,
+            data2,
+        } => OscType::Midi(OscMidiMessage {
+            port: *port,
+            status: *status,
+            data1: *data1,
+            data2: *data2,
+        }),
+        OscArgValue::Array(items) => OscType::Array(OscArray {
+            content: items.iter().map(arg_to_osc_type).collect(),
+        }),
+    }
+}
+
+/// Convert a bundle timetag to wall-clock time. The special "immediately"
+/// timetag (0, 1) and anything before 1970 map to `None`.
+fn osc_time_to_system_time(time: OscTime) -> Option<SystemTime> {
+    if time.seconds < OSC_UNIX_OFFSET {
+        return None;
+    }
+    Some(SystemTime::from(time))
+}
+
+/// Forward decoded messages to the router. Messages due now are sent inline;
+/// future-dated ones are held by a timer task, one per run of messages that
+/// share a timetag, so bundle order is preserved. Messages dated more than
+/// `MAX_BUNDLE_DELAY` ahead are sent inline too, with a warning.
+fn deliver(
+    messages: Vec<Scheduled>,
+    tx: &mpsc::UnboundedSender<IncomingMessage>,
+    token: &CancellationToken,
+) {
+    let now = SystemTime::now();
+    let mut pending: Vec<(Duration, Vec<IncomingMessage>)> = Vec::new();
+    let mut skewed = 0;
+    for (time, msg) in messages {
+        let delay = time.and_then(|t| t.duration_since(now).ok());
+        match delay {
+            Some(delay) if delay > MAX_BUNDLE_DELAY => {
+                skewed += 1;
+                let _ = tx.send(msg);
+            }
+            Some(delay) if !delay.is_zero() => match pending.last_mut() {
+                Some((d, batch)) if *d == delay => batch.push(msg),
+                _ => pending.push((delay, vec![msg])),
+            },
+            _ => {
+                let _ = tx.send(msg);
+            }
+        }
+    }
+    if skewed > 0 {
+        warn!(
+            "Delivered {} OSC bundle messages at once: scheduled more than {}s ahead, is the sender's clock ahead?",
+            skewed,
+            MAX_BUNDLE_DELAY.as_secs()
+        );
+    }
+    for (delay, batch) in pending {
+        let tx = tx.clone();
+        let token = token.clone();
+        tokio::spawn(async move {
+            tokio::select! {
+                _ = token.cancelled() => {}
+                _ = tokio::time::sleep(delay) => {
+                    for msg in batch {
+                        let _ = tx.send(msg);
+                    }
+                }
+            }
+        });
     }
 }
 
@@ -197,9 +453,7 @@ pub async fn send_osc_udp(
         .await
         .map_err(|e| format!("Failed to create UDP socket: {}", e))?;
 
-    let msg = build_osc_message(address, args);
-    let data = rosc::encoder::encode(&OscPacket::Message(msg))
-        .map_err(|e| format!("OSC encode error: {:?}", e))?;
+    let data = encode_osc_message(address, args)?;
 
     socket
         .send_to(&data, addr)
@@ -228,46 +482,13 @@ fn slip_encode(data: &[u8]) -> Vec<u8> {
     encoded
 }
 
-pub async fn send_osc_tcp(
-    host: &str,
-    port: u16,
-    address: &str,
-    args: &[OscArgValue],
-    timeout_ms: u64,
-) -> Result<(), String> {
-    let addr: SocketAddr = format!("{}:{}", host, port)
-        .parse()
-        .map_err(|e| format!("Invalid send address: {}", e))?;
-
+pub fn encode_osc_message(address: &str, args: &[OscArgValue]) -> Result<Vec<u8>, String> {
     let msg = build_osc_message(address, args);
-    let data = rosc::encoder::encode(&OscPacket::Message(msg))
-        .map_err(|e| format!("OSC encode error: {:?}", e))?;
-
-    let slip_data = slip_encode(&data);
-
-    let result = timeout(Duration::from_millis(timeout_ms), async {
-        let mut stream = TcpStream::connect(addr).await?;
-        stream.write_all(&slip_data).await?;
-        Ok::<(), std::io::Error>(())
-    })
-    .await;
-
-    match result {
-        Ok(Ok(())) => Ok(()),
-        Ok(Err(e)) => Err(format!("TCP send error: {}", e)),
-        Err(_) => Err(format!("TCP send timeout ({}ms) to {}:{}", timeout_ms, host, port)),
-    }
+    rosc::encoder::encode(&OscPacket::Message(msg)).map_err(|e| format!("OSC encode error: {:?}", e))
 }
 
 fn build_osc_message(address: &str, args: &[OscArgValue]) -> OscMessage {
-    let osc_args: Vec<OscType> = args
-        .iter()
-        .map(|a| match a {
-            OscArgValue::Int(i) => OscType::Int(*i),
-            OscArgValue::Float(f) => OscType::Float(*f),
-            OscArgValue::String(s) => OscType::String(s.clone()),
-        })
-        .collect();
+    let osc_args: Vec<OscType> = args.iter().map(arg_to_osc_type).collect();
     OscMessage {
         addr: address.to_string(),
         args: osc_args,
@@ -277,35 +498,65 @@ fn build_osc_message(address: &str, args: &[OscArgValue]) -> OscMessage {
 pub fn dispatch_output(
     action: &OutputAction,
     settings: &std::sync::Arc<std::sync::Mutex<crate::models::Settings>>,
-    midi_out: &Option<std::sync::Arc<std::sync::Mutex<midir::MidiOutputConnection>>>,
+    midi_out: &crate::midi_engine::MidiOutputs,
+    tcp: &TcpSenderPool,
     rt: &tokio::runtime::Handle,
 ) {
     match action {
         OutputAction::Midi {
+            port,
             message_type,
             channel,
             note_or_cc,
             value,
         } => {
-            if let Some(conn) = midi_out {
-                let status = match message_type {
-                    crate::models::MidiMessageType::NoteOn => 0x90 + (channel - 1),
-                    crate::models::MidiMessageType::NoteOff => 0x80 + (channel - 1),
-                    crate::models::MidiMessageType::Cc => 0xB0 + (channel - 1),
-                    crate::models::MidiMessageType::ProgramChange => 0xC0 + (channel - 1),
-                    crate::models::MidiMessageType::Msc => return, // MSC output not supported
-                };
-                if let Ok(mut conn) = conn.lock() {
-                    if matches!(message_type, crate::models::MidiMessageType::ProgramChange) {
-                        let _ = conn.send(&[status, *note_or_cc]);
-                    } else {
-                        let _ = conn.send(&[status, *note_or_cc, *value]);
-                    }
+            // MSC is sent as OutputAction::Msc
+            let Some(bytes) = crate::midi_engine::encode_midi_message(
+                message_type,
+                *channel,
+                *note_or_cc,
+                *value,
+            ) else {
+                return;
+            };
+            if let Err(e) = midi_out.send(port.as_deref(), &bytes) {
+                warn!("{}", e);
+            }
+        }
+        OutputAction::Cc14 {
+            port,
+            mode,
+            channel,
+            parameter,
+            value,
+        } => {
+            let encoded = crate::midi_engine::encode_cc14(mode, *channel, *parameter, *value);
+            let messages = match encoded {
+                Ok(messages) => messages,
+                Err(e) => {
+                    warn!("{}", e);
+                    return;
+                }
+            };
+            for msg in messages {
+                if let Err(e) = midi_out.send(port.as_deref(), &msg) {
+                    warn!("{}", e);
+                    break;
                 }
             }
         }
-        OutputAction::Osc { address, args } => {
-            let (host, port, protocol, timeout_ms) = {
+        OutputAction::Msc { msc, port } => {
+            let sysex = crate::midi_engine::build_msc_sysex(msc);
+            if let Err(e) = midi_out.send(port.as_deref(), &sysex) {
+                error!("MSC send error: {}", e);
+            }
+        }
+        OutputAction::Osc {
+            address,
+            args,
+            destinations,
+        } => {
+            let targets = {
                 let s = match settings.lock() {
                     Ok(guard) => guard,
                     Err(e) => {
@@ -313,28 +564,375 @@ pub fn dispatch_output(
                         return;
                     }
                 };
-                (
-                    s.osc_send_host.clone(),
-                    s.osc_send_port,
-                    s.osc_send_protocol.clone(),
-                    s.osc_tcp_send_timeout_ms,
-                )
+                resolve_destinations(&s, destinations)
             };
-            let address = address.clone();
-            let args = args.clone();
-            rt.spawn(async move {
-                let result = match protocol {
-                    crate::models::OscSendProtocol::Udp => {
-                        send_osc_udp(&host, port, &address, &args).await
+            for target in targets {
+                if target.protocol == crate::models::OscSendProtocol::Tcp {
+                    match encode_osc_message(address, args) {
+                        Ok(packet) => tcp.send(&target, &packet, rt),
+                        Err(e) => error!("OSC send error ({}): {}", target.name, e),
                     }
-                    crate::models::OscSendProtocol::Tcp => {
-                        send_osc_tcp(&host, port, &address, &args, timeout_ms).await
-                    }
-                };
-                if let Err(e) = result {
-                    error!("OSC send error: {}", e);
+                    continue;
                 }
-            });
+                let address = address.clone();
+                let args = args.clone();
+                rt.spawn(async move {
+                    if let Err(e) = send_osc_udp(&target.host, target.port, &address, &args).await {
+                        error!("OSC send error ({}): {}", target.name, e);
+                    }
+                });
+            }
+        }
+    }
+}
+
+/// Resolve a mapping's destination names. An empty list means the default
+/// target; unknown names are logged and skipped.
+fn resolve_destinations(
+    settings: &crate::models::Settings,
+    names: &[String],
+) -> Vec<crate::models::OscDestination> {
+    if names.is_empty() {
+        return vec![settings.default_osc_destination()];
+    }
+    names
+        .iter()
+        .filter_map(|name| {
+            let dest = settings.osc_destination(name);
+            if dest.is_none() {
+                warn!("Unknown OSC destination '{}'", name);
+            }
+            dest
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rosc::OscBundle;
+
+    const IMMEDIATE: OscTime = OscTime {
+        seconds: 0,
+        fractional: 1,
+    };
+
+    fn message(addr: &str, value: i32) -> OscPacket {
+        OscPacket::Message(OscMessage {
+            addr: addr.to_string(),
+            args: vec![OscType::Int(value)],
+        })
+    }
+
+    fn destination(name: &str, port: u16) -> crate::models::OscDestination {
+        crate::models::OscDestination {
+            name: name.to_string(),
+            host: "127.0.0.1".to_string(),
+            port,
+            protocol: crate::models::OscSendProtocol::Udp,
+            timeout_ms: 3000,
+            framing: crate::models::OscTcpFraming::Slip,
+        }
+    }
+
+    #[test]
+    fn test_resolve_destinations_empty_uses_default() {
+        let settings = crate::models::Settings::default();
+        let targets = resolve_destinations(&settings, &[]);
+        assert_eq!(targets, vec![settings.default_osc_destination()]);
+    }
+
+    #[test]
+    fn test_resolve_destinations_named_and_unknown() {
+        let settings = crate::models::Settings {
+            osc_destinations: vec![destination("lights", 7000), destination("sound", 53000)],
+            ..Default::default()
+        };
+        let names = vec![
+            "sound".to_string(),
+            "missing".to_string(),
+            "default".to_string(),
+        ];
+        let ports: Vec<u16> = resolve_destinations(&settings, &names)
+            .iter()
+            .map(|d| d.port)
+            .collect();
+        assert_eq!(ports, vec![53000, 9000]);
+    }
+
+    fn peer() -> SocketAddr {
+        "127.0.0.1:9".parse().unwrap()
+    }
+
+    fn encoded(addr: &str, value: i32) -> Vec<u8> {
+        rosc::encoder::encode(&message(addr, value)).unwrap()
+    }
+
+    fn decode_chunks(
+        framing: OscTcpListenFraming,
+        stream: &[u8],
+        chunk: usize,
+    ) -> Result<Vec<Vec<u8>>, String> {
+        let mut decoder = TcpFrameDecoder::new(&framing);
+        let mut packets = Vec::new();
+        for part in stream.chunks(chunk) {
+            packets.extend(decoder.push(part, peer())?);
+        }
+        Ok(packets)
+    }
+
+    fn framed_stream(framing: &OscTcpFraming, packets: &[Vec<u8>]) -> Vec<u8> {
+        packets
+            .iter()
+            .flat_map(|p| frame_tcp_packet(framing, p))
+            .collect()
+    }
+
+    #[test]
+    fn test_tcp_framing_split_and_coalesced_reads() {
+        // 0xC0/0xDB inside the payload exercise SLIP escaping
+        let packets = vec![encoded("/a", 1), encoded("/b", 0xC0DB), encoded("/c", 3)];
+        let cases = [
+            (OscTcpFraming::Slip, OscTcpListenFraming::Slip),
+            (OscTcpFraming::LengthPrefix, OscTcpListenFraming::LengthPrefix),
+            (OscTcpFraming::Slip, OscTcpListenFraming::Auto),
+            (OscTcpFraming::LengthPrefix, OscTcpListenFraming::Auto),
+        ];
+        for (send, listen) in cases {
+            let stream = framed_stream(&send, &packets);
+            // One byte per read, a few bytes per read, and everything in one read
+            for chunk in [1, 5, stream.len()] {
+                let decoded = decode_chunks(listen.clone(), &stream, chunk).unwrap();
+                assert_eq!(decoded, packets, "{:?}/{:?} chunk {}", send, listen, chunk);
+            }
+        }
+    }
+
+    #[test]
+    fn test_auto_framing_detects_slip_without_leading_end() {
+        let packet = encoded("/a", 1);
+        let mut stream = packet.clone();
+        stream.push(SLIP_END);
+        let decoded = decode_chunks(OscTcpListenFraming::Auto, &stream, 3).unwrap();
+        assert_eq!(decoded, vec![packet]);
+    }
+
+    #[test]
+    fn test_length_prefix_oversized_packet_is_error() {
+        let stream = (SLIP_MAX_PACKET as u32 + 1).to_be_bytes();
+        assert!(decode_chunks(OscTcpListenFraming::LengthPrefix, &stream, 4).is_err());
+    }
+
+    #[test]
+    fn test_length_prefix_frame() {
+        assert_eq!(
+            frame_tcp_packet(&OscTcpFraming::LengthPrefix, b"abcd"),
+            b"\0\0\0\x04abcd".to_vec()
+        );
+    }
+
+    fn bundle(timetag: OscTime, content: Vec<OscPacket>) -> OscPacket {
+        OscPacket::Bundle(OscBundle { timetag, content })
+    }
+
+    fn address_of(msg: &IncomingMessage) -> &str {
+        match msg {
+            IncomingMessage::Osc { address, .. } => address,
+            other => panic!("Expected OSC message, got {:?}", other),
         }
     }
+
+    fn timetag_in(delay: Duration) -> OscTime {
+        OscTime::try_from(SystemTime::now() + delay).unwrap()
+    }
+
+    #[test]
+    fn test_decode_plain_message() {
+        let decoded = decode_osc_packet(message("/cue/go", 1));
+        assert_eq!(decoded.len(), 1);
+        assert_eq!(decoded[0].0, None);
+        assert_eq!(address_of(&decoded[0].1), "/cue/go");
+    }
+
+    #[test]
+    fn test_decode_immediate_bundle() {
+        let packet = bundle(IMMEDIATE, vec![message("/a", 1), message("/b", 2)]);
+        let decoded = decode_osc_packet(packet);
+        let addrs: Vec<&str> = decoded.iter().map(|(_, m)| address_of(m)).collect();
+        assert_eq!(addrs, vec!["/a", "/b"]);
+        assert!(decoded.iter().all(|(t, _)| t.is_none()));
+    }
+
+    #[test]
+    fn test_decode_nested_bundles_in_order() {
+        let packet = bundle(
+            IMMEDIATE,
+            vec![
+                message("/a", 1),
+                bundle(
+                    IMMEDIATE,
+                    vec![message("/b", 2), bundle(IMMEDIATE, vec![message("/c", 3)])],
+                ),
+                message("/d", 4),
+            ],
+        );
+        let decoded = decode_osc_packet(packet);
+        let addrs: Vec<&str> = decoded.iter().map(|(_, m)| address_of(m)).collect();
+        assert_eq!(addrs, vec!["/a", "/b", "/c", "/d"]);
+    }
+
+    #[test]
+    fn test_decode_nested_bundle_not_earlier_than_parent() {
+        let outer = timetag_in(Duration::from_secs(10));
+        let packet = bundle(outer, vec![bundle(IMMEDIATE, vec![message("/inner", 1)])]);
+        let decoded = decode_osc_packet(packet);
+        assert_eq!(decoded[0].0, Some(SystemTime::from(outer)));
+    }
+
+    #[test]
+    fn test_decode_bundle_survives_udp_roundtrip() {
+        let packet = bundle(
+            IMMEDIATE,
+            vec![message("/a", 1), bundle(IMMEDIATE, vec![message("/b", 2)])],
+        );
+        let data = rosc::encoder::encode(&packet).unwrap();
+        let decoded = decode_osc_udp(&data);
+        assert_eq!(decoded.len(), 2);
+    }
+
+    #[test]
+    fn test_decode_keeps_every_argument_type() {
+        let packet = OscPacket::Message(OscMessage {
+            addr: "/all".to_string(),
+            args: vec![
+                OscType::Blob(vec![1, 2]),
+                OscType::Bool(true),
+                OscType::Nil,
+                OscType::Inf,
+                OscType::Double(0.25),
+                OscType::Long(1 << 40),
+                OscType::Time(IMMEDIATE),
+                OscType::Char('x'),
+                OscType::Color(OscColor {
+                    red: 1,
+                    green: 2,
+                    blue: 3,
+                    alpha: 4,
+                }),
+                OscType::Midi(OscMidiMessage {
+                    port: 0,
+                    status: 0x90,
+                    data1: 60,
+                    data2: 100,
+                }),
+                OscType::Int(7),
+            ],
+        });
+        let decoded = decode_osc_packet(packet);
+        match &decoded[0].1 {
+            IncomingMessage::Osc { args, .. } => {
+                assert_eq!(args.len(), 11);
+                // The trailing int keeps its index instead of shifting left
+                assert!(matches!(args[10], OscArgValue::Int(7)));
+                assert!(matches!(args[5], OscArgValue::Int64(v) if v == 1 << 40));
+                assert!(matches!(args[7], OscArgValue::Char('x')));
+            }
+            other => panic!("Expected OSC message, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_build_osc_message_roundtrips_every_type() {
+        let args = vec![
+            OscArgValue::Blob(vec![9]),
+            OscArgValue::Bool(false),
+            OscArgValue::Nil,
+            OscArgValue::Impulse,
+            OscArgValue::Double(1.5),
+            OscArgValue::Int64(-3),
+            OscArgValue::TimeTag {
+                seconds: 1,
+                fractional: 2,
+            },
+            OscArgValue::Char('c'),
+            OscArgValue::Color {
+                red: 255,
+                green: 0,
+                blue: 0,
+                alpha: 255,
+            },
+            OscArgValue::Midi {
+                port: 1,
+                status: 0xB0,
+                data1: 7,
+                data2: 64,
+            },
+            OscArgValue::Array(vec![OscArgValue::Int(1), OscArgValue::Float(2.0)]),
+        ];
+        let msg = build_osc_message("/out", &args);
+        let data = rosc::encoder::encode(&OscPacket::Message(msg.clone())).unwrap();
+        let (_, packet) = rosc::decoder::decode_udp(&data).unwrap();
+        assert_eq!(packet, OscPacket::Message(msg));
+    }
+
+    #[test]
+    fn test_osc_time_before_unix_epoch_is_immediate() {
+        assert_eq!(osc_time_to_system_time(IMMEDIATE), None);
+        let t = OscTime {
+            seconds: 1000,
+            fractional: 0,
+        };
+        assert_eq!(osc_time_to_system_time(t), None);
+    }
+
+    #[tokio::test]
+    async fn test_deliver_future_bundle_waits_for_timetag() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let token = CancellationToken::new();
+        let packet = bundle(
+            timetag_in(Duration::from_millis(150)),
+            vec![message("/later/1", 1), message("/later/2", 2)],
+        );
+        let start = std::time::Instant::now();
+        deliver(decode_osc_packet(packet), &tx, &token);
+        deliver(decode_osc_packet(message("/now", 0)), &tx, &token);
+
+        let first = rx.recv().await.unwrap();
+        assert_eq!(address_of(&first), "/now");
+        let second = rx.recv().await.unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(100));
+        assert_eq!(address_of(&second), "/later/1");
+        let third = rx.recv().await.unwrap();
+        assert_eq!(address_of(&third), "/later/2");
+    }
+
+    #[tokio::test]
+    async fn test_deliver_skewed_bundle_immediately() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let token = CancellationToken::new();
+        // A sender whose clock runs a few minutes ahead
+        let packet = bundle(
+            timetag_in(MAX_BUNDLE_DELAY + Duration::from_secs(180)),
+            vec![message("/skewed", 1)],
+        );
+        deliver(decode_osc_packet(packet), &tx, &token);
+        match rx.try_recv() {
+            Ok(IncomingMessage::Osc { address, .. }) => assert_eq!(address, "/skewed"),
+            other => panic!("expected immediate delivery, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_deliver_future_bundle_dropped_on_cancel() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let token = CancellationToken::new();
+        let packet = bundle(
+            timetag_in(Duration::from_millis(100)),
+            vec![message("/later", 1)],
+        );
+        deliver(decode_osc_packet(packet), &tx, &token);
+        token.cancel();
+        drop(tx);
+        assert!(rx.recv().await.is_none());
+    }
 }
@@ -0,0 +1,279 @@
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Literal(char),
+    AnyChar,
+    AnySequence,
+    Class {
+        negated: bool,
+        ranges: Vec<(char, char)>,
+    },
+    Alternatives(Vec<Vec<char>>),
+}
+
+/// A compiled OSC 1.0 address pattern.
+///
+/// Supports `?` (any single character), `*` (any run of characters),
+/// `[a-z]` / `[!0-9]` character classes and `{foo,bar}` alternatives.
+/// None of the wildcards match across a `/` — patterns are matched
+/// segment by segment, so `/ch/*/fader` never matches `/ch/1/2/fader`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OscPattern {
+    source: String,
+    /// `None` when the pattern has no special characters — matched by plain equality.
+    segments: Option<Vec<Vec<Token>>>,
+}
+
+impl OscPattern {
+    /// Compile an address pattern. Fails on unterminated `[` or `{` groups.
+    pub fn compile(pattern: &str) -> Result<Self, String> {
+        if !pattern.contains(['*', '?', '[', '{']) {
+            return Ok(Self::literal(pattern));
+        }
+        let segments = pattern
+            .split('/')
+            .map(|seg| {
+                compile_segment(seg).map_err(|e| format!("Invalid OSC pattern {}: {}", pattern, e))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self {
+            source: pattern.to_string(),
+            segments: Some(segments),
+        })
+    }
+
+    /// A pattern that only matches `address` exactly.
+    pub fn literal(address: &str) -> Self {
+        Self {
+            source: address.to_string(),
+            segments: None,
+        }
+    }
+
+    /// Match `address` and return one capture per segment that contains a
+    /// wildcard, in order: the text matched from its first wildcard to its
+    /// last, without the literal prefix and suffix. `/ch/*/mix/{a,b}` against
+    /// `/ch/12/mix/b` yields `["12", "b"]`, and `/ch/ch*/fader` against
+    /// `/ch/ch12/fader` yields `["12"]`. Literal patterns capture nothing.
+    pub fn captures(&self, address: &str) -> Option<Vec<String>> {
+        let segments = match &self.segments {
+            Some(s) => s,
+            None => return (address == self.source).then(Vec::new),
+        };
+        let parts: Vec<&str> = address.split('/').collect();
+        if parts.len() != segments.len() {
+            return None;
+        }
+        let mut captures = Vec::new();
+        for (tokens, part) in segments.iter().zip(parts) {
+            let chars: Vec<char> = part.chars().collect();
+            if !match_tokens(tokens, &chars) {
+                return None;
+            }
+            let is_wildcard = |t: &Token| !matches!(t, Token::Literal(_));
+            if let (Some(first), Some(last)) = (
+                tokens.iter().position(is_wildcard),
+                tokens.iter().rposition(is_wildcard),
+            ) {
+                // Literal tokens are one character each
+                let suffix = tokens.len() - 1 - last;
+                captures.push(chars[first..chars.len() - suffix].iter().collect());
+            }
+        }
+        Some(captures)
+    }
+}
+
+fn compile_segment(seg: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = seg.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '?' => tokens.push(Token::AnyChar),
+            '*' => {
+                // Consecutive stars are equivalent to one
+                if tokens.last() != Some(&Token::AnySequence) {
+                    tokens.push(Token::AnySequence);
+                }
+            }
+            '[' => {
+                let mut body = Vec::new();
+                loop {
+                    match chars.next() {
+                        Some(']') => break,
+                        Some(ch) => body.push(ch),
+                        None => return Err("unterminated '['".to_string()),
+                    }
+                }
+                tokens.push(compile_class(&body));
+            }
+            '{' => {
+                let mut body = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(ch) => body.push(ch),
+                        None => return Err("unterminated '{'".to_string()),
+                    }
+                }
+                let alternatives = body.split(',').map(|a| a.chars().collect()).collect();
+                tokens.push(Token::Alternatives(alternatives));
+            }
+            ']' | '}' => return Err(format!("unmatched '{}'", c)),
+            _ => tokens.push(Token::Literal(c)),
+        }
+    }
+    Ok(tokens)
+}
+
+fn compile_class(body: &[char]) -> Token {
+    let (negated, body) = match body.first() {
+        Some('!') => (true, &body[1..]),
+        _ => (false, body),
+    };
+    let mut ranges = Vec::new();
+    let mut i = 0;
+    while i < body.len() {
+        // A '-' between two characters is a range; at either end it is literal
+        if i + 2 < body.len() && body[i + 1] == '-' {
+            let (lo, hi) = (body[i], body[i + 2]);
+            ranges.push((lo.min(hi), lo.max(hi)));
+            i += 3;
+        } else {
+            ranges.push((body[i], body[i]));
+            i += 1;
+        }
+    }
+    Token::Class { negated, ranges }
+}
+
+fn match_tokens(tokens: &[Token], input: &[char]) -> bool {
+    let Some((token, rest)) = tokens.split_first() else {
+        return input.is_empty();
+    };
+    match token {
+        Token::Literal(c) => input.first() == Some(c) && match_tokens(rest, &input[1..]),
+        Token::AnyChar => !input.is_empty() && match_tokens(rest, &input[1..]),
+        Token::AnySequence => (0..=input.len()).any(|n| match_tokens(rest, &input[n..])),
+        Token::Class { negated, ranges } => match input.first() {
+            Some(c) => {
+                let in_class = ranges.iter().any(|(lo, hi)| (*lo..=*hi).contains(c));
+                in_class != *negated && match_tokens(rest, &input[1..])
+            }
+            None => false,
+        },
+        Token::Alternatives(alternatives) => alternatives
+            .iter()
+            .any(|alt| input.starts_with(alt) && match_tokens(rest, &input[alt.len()..])),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(pattern: &str, address: &str) -> bool {
+        OscPattern::compile(pattern)
+            .unwrap()
+            .captures(address)
+            .is_some()
+    }
+
+    #[test]
+    fn test_literal_pattern() {
+        assert!(matches("/cue/go", "/cue/go"));
+        assert!(!matches("/cue/go", "/cue/go/1"));
+        assert!(!matches("/cue/go", "/cue/stop"));
+    }
+
+    #[test]
+    fn test_star_matches_within_segment() {
+        assert!(matches("/ch/*/fader", "/ch/1/fader"));
+        assert!(matches("/ch/*/fader", "/ch/32/fader"));
+        assert!(matches("/ch/*/fader", "/ch//fader"));
+        assert!(!matches("/ch/*/fader", "/ch/1/2/fader"));
+        assert!(!matches("/ch/*/fader", "/ch/1/mute"));
+    }
+
+    #[test]
+    fn test_star_partial_segment() {
+        assert!(matches("/ch/0*", "/ch/01"));
+        assert!(matches("/ch/*mute", "/ch/mute"));
+        assert!(matches("/ch/a*b*c", "/ch/aXXbYc"));
+        assert!(!matches("/ch/a*b*c", "/ch/aXXbY"));
+    }
+
+    #[test]
+    fn test_question_mark() {
+        assert!(matches("/ch/?/fader", "/ch/5/fader"));
+        assert!(!matches("/ch/?/fader", "/ch/15/fader"));
+        assert!(!matches("/ch/?/fader", "/ch//fader"));
+    }
+
+    #[test]
+    fn test_character_class() {
+        assert!(matches("/ch/[1-4]", "/ch/3"));
+        assert!(!matches("/ch/[1-4]", "/ch/5"));
+        assert!(matches("/ch/[abc]", "/ch/b"));
+        assert!(matches("/ch/[a-c-]", "/ch/-"));
+    }
+
+    #[test]
+    fn test_negated_character_class() {
+        assert!(matches("/ch/[!0-9]", "/ch/x"));
+        assert!(!matches("/ch/[!0-9]", "/ch/7"));
+    }
+
+    #[test]
+    fn test_alternatives() {
+        assert!(matches("/mix/{bus,aux}/1", "/mix/bus/1"));
+        assert!(matches("/mix/{bus,aux}/1", "/mix/aux/1"));
+        assert!(!matches("/mix/{bus,aux}/1", "/mix/main/1"));
+        assert!(matches("/{a,ab}c", "/abc"));
+    }
+
+    #[test]
+    fn test_combined_wildcards() {
+        assert!(matches("/*/[0-9]?/{fader,mute}", "/ch/12/mute"));
+        assert!(!matches("/*/[0-9]?/{fader,mute}", "/ch/x2/mute"));
+    }
+
+    #[test]
+    fn test_captures_wildcard_segments() {
+        let p = OscPattern::compile("/ch/*/mix/{a,b}").unwrap();
+        assert_eq!(
+            p.captures("/ch/12/mix/b"),
+            Some(vec!["12".to_string(), "b".to_string()])
+        );
+        assert_eq!(p.captures("/ch/12/mix/c"), None);
+    }
+
+    #[test]
+    fn test_captures_skip_literal_prefix_and_suffix() {
+        let p = OscPattern::compile("/ch/0?/fader").unwrap();
+        assert_eq!(p.captures("/ch/07/fader"), Some(vec!["7".to_string()]));
+        let p = OscPattern::compile("/ch/ch*/fader").unwrap();
+        assert_eq!(p.captures("/ch/ch12/fader"), Some(vec!["12".to_string()]));
+        let p = OscPattern::compile("/bus/[0-9]*_mix").unwrap();
+        assert_eq!(p.captures("/bus/12_mix"), Some(vec!["12".to_string()]));
+    }
+
+    #[test]
+    fn test_captures_literal_pattern_is_empty() {
+        let p = OscPattern::compile("/cue/go").unwrap();
+        assert_eq!(p.captures("/cue/go"), Some(vec![]));
+        assert_eq!(p.captures("/cue/stop"), None);
+    }
+
+    #[test]
+    fn test_unterminated_groups_rejected() {
+        assert!(OscPattern::compile("/ch/[1-4").is_err());
+        assert!(OscPattern::compile("/mix/{bus,aux").is_err());
+    }
+
+    #[test]
+    fn test_literal_fallback() {
+        let p = OscPattern::literal("/ch/[1");
+        assert!(p.captures("/ch/[1").is_some());
+        assert!(p.captures("/ch/1").is_none());
+    }
+}
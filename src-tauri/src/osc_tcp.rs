@@ -0,0 +1,454 @@
+use crate::models::{
+    OscConnectionState, OscConnectionStatus, OscDestination, OscSendProtocol, OscTcpFraming,
+    Settings,
+};
+use crate::osc_engine::frame_tcp_packet;
+use log::{error, info, warn};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::sync::{mpsc, Notify};
+use tokio::time::{timeout, Duration};
+use tokio_util::sync::CancellationToken;
+
+/// Packets held per destination while it is disconnected; the oldest are
+/// dropped first so the receiver gets the most recent values on reconnect.
+const QUEUE_CAPACITY: usize = 1024;
+const INITIAL_BACKOFF: Duration = Duration::from_millis(250);
+const MAX_BACKOFF: Duration = Duration::from_secs(10);
+
+/// Long-lived TCP connections to OSC destinations, one per destination name.
+///
+/// Connections are opened on first send and reconnect with exponential
+/// backoff. State changes are sent on the status channel.
+pub struct TcpSenderPool {
+    connections: Mutex<HashMap<String, Connection>>,
+    statuses: Arc<Mutex<HashMap<String, OscConnectionStatus>>>,
+    status_tx: mpsc::UnboundedSender<OscConnectionStatus>,
+    token: CancellationToken,
+}
+
+struct Connection {
+    host: String,
+    port: u16,
+    timeout_ms: u64,
+    framing: OscTcpFraming,
+    queue: Arc<PacketQueue>,
+    token: CancellationToken,
+}
+
+#[derive(Default)]
+struct PacketQueue {
+    packets: Mutex<VecDeque<Vec<u8>>>,
+    notify: Notify,
+    dropped: Mutex<u64>,
+}
+
+impl PacketQueue {
+    fn push(&self, packet: Vec<u8>, name: &str) {
+        let Ok(mut packets) = self.packets.lock() else {
+            return;
+        };
+        if packets.len() >= QUEUE_CAPACITY {
+            packets.pop_front();
+            if let Ok(mut dropped) = self.dropped.lock() {
+                if *dropped == 0 {
+                    warn!(
+                        "OSC TCP queue for '{}' is full, dropping oldest messages",
+                        name
+                    );
+                }
+                *dropped += 1;
+            }
+        }
+        packets.push_back(packet);
+        drop(packets);
+        self.notify.notify_one();
+    }
+
+    fn pop(&self) -> Option<Vec<u8>> {
+        self.packets.lock().ok()?.pop_front()
+    }
+
+    /// Put back a packet whose write failed so it is resent after reconnecting.
+    fn requeue(&self, packet: Vec<u8>) {
+        if let Ok(mut packets) = self.packets.lock() {
+            if packets.len() < QUEUE_CAPACITY {
+                packets.push_front(packet);
+            }
+        }
+    }
+
+    fn take_dropped(&self) -> u64 {
+        self.dropped
+            .lock()
+            .map(|mut d| std::mem::take(&mut *d))
+            .unwrap_or(0)
+    }
+}
+
+impl TcpSenderPool {
+    pub fn new(
+        token: CancellationToken,
+        status_tx: mpsc::UnboundedSender<OscConnectionStatus>,
+    ) -> Self {
+        Self {
+            connections: Mutex::new(HashMap::new()),
+            statuses: Arc::new(Mutex::new(HashMap::new())),
+            status_tx,
+            token,
+        }
+    }
+
+    /// Queue an encoded OSC packet for `dest`, connecting on first use.
+    /// A destination whose host, port, timeout or framing changed gets a fresh connection.
+    pub fn send(&self, dest: &OscDestination, packet: &[u8], rt: &tokio::runtime::Handle) {
+        let mut connections = match self.connections.lock() {
+            Ok(guard) => guard,
+            Err(e) => {
+                error!("TCP connections mutex poisoned in send(): {}", e);
+                return;
+            }
+        };
+        let stale = connections.get(&dest.name).is_some_and(|c| {
+            c.host != dest.host
+                || c.port != dest.port
+                || c.timeout_ms != dest.timeout_ms
+                || c.framing != dest.framing
+        });
+        if stale {
+            if let Some(old) = connections.remove(&dest.name) {
+                old.token.cancel();
+            }
+        }
+        let connection = connections
+            .entry(dest.name.clone())
+            .or_insert_with(|| self.open(dest, rt));
+        connection
+            .queue
+            .push(frame_tcp_packet(&dest.framing, packet), &dest.name);
+    }
+
+    /// Close connections to destinations that are no longer configured for
+    /// TCP, e.g. after one was renamed or deleted in the settings.
+    pub fn retain_configured(&self, settings: &Settings) {
+        let configured = |name: &str| {
+            settings
+                .osc_destination(name)
+                .is_some_and(|d| d.protocol == OscSendProtocol::Tcp)
+        };
+        match self.connections.lock() {
+            Ok(mut connections) => connections.retain(|name, connection| {
+                let keep = configured(name);
+                if !keep {
+                    info!(
+                        "Closing OSC TCP connection to removed destination '{}'",
+                        name
+                    );
+                    connection.token.cancel();
+                }
+                keep
+            }),
+            Err(e) => error!(
+                "TCP connections mutex poisoned in retain_configured(): {}",
+                e
+            ),
+        }
+        if let Ok(mut statuses) = self.statuses.lock() {
+            statuses.retain(|name, _| configured(name));
+        }
+    }
+
+    /// Last reported state of every destination, sorted by name.
+    pub fn statuses(&self) -> Vec<OscConnectionStatus> {
+        let mut statuses: Vec<OscConnectionStatus> = match self.statuses.lock() {
+            Ok(guard) => guard.values().cloned().collect(),
+            Err(_) => Vec::new(),
+        };
+        statuses.sort_by(|a, b| a.destination.cmp(&b.destination));
+        statuses
+    }
+
+    fn open(&self, dest: &OscDestination, rt: &tokio::runtime::Handle) -> Connection {
+        let queue = Arc::new(PacketQueue::default());
+        let token = self.token.child_token();
+        let reporter = StatusReporter {
+            dest: dest.clone(),
+            statuses: self.statuses.clone(),
+            status_tx: self.status_tx.clone(),
+            token: token.clone(),
+        };
+        rt.spawn(run_connection(reporter, queue.clone(), token.clone()));
+        Connection {
+            host: dest.host.clone(),
+            port: dest.port,
+            timeout_ms: dest.timeout_ms,
+            framing: dest.framing.clone(),
+            queue,
+            token,
+        }
+    }
+}
+
+struct StatusReporter {
+    dest: OscDestination,
+    statuses: Arc<Mutex<HashMap<String, OscConnectionStatus>>>,
+    status_tx: mpsc::UnboundedSender<OscConnectionStatus>,
+    token: CancellationToken,
+}
+
+impl StatusReporter {
+    fn report(&self, state: OscConnectionState, error: Option<String>) {
+        // A closed connection must not bring back its pruned status
+        if self.token.is_cancelled() {
+            return;
+        }
+        let status = OscConnectionStatus {
+            destination: self.dest.name.clone(),
+            host: self.dest.host.clone(),
+            port: self.dest.port,
+            state,
+            error,
+        };
+        if let Ok(mut statuses) = self.statuses.lock() {
+            statuses.insert(status.destination.clone(), status.clone());
+        }
+        let _ = self.status_tx.send(status);
+    }
+}
+
+async fn run_connection(
+    reporter: StatusReporter,
+    queue: Arc<PacketQueue>,
+    token: CancellationToken,
+) {
+    let dest = &reporter.dest;
+    let addr = format!("{}:{}", dest.host, dest.port);
+    let connect_timeout = Duration::from_millis(dest.timeout_ms);
+    let mut backoff = INITIAL_BACKOFF;
+
+    loop {
+        reporter.report(OscConnectionState::Connecting, None);
+        let connected = tokio::select! {
+            _ = token.cancelled() => return,
+            result = timeout(connect_timeout, TcpStream::connect(&addr)) => result,
+        };
+        let err = match connected {
+            Ok(Ok(stream)) => {
+                info!("OSC TCP connected to '{}' ({})", dest.name, addr);
+                backoff = INITIAL_BACKOFF;
+                reporter.report(OscConnectionState::Connected, None);
+                match pump(stream, &queue, connect_timeout, &token).await {
+                    Some(err) => err,
+                    None => return,
+                }
+            }
+            Ok(Err(e)) => format!("connect failed: {}", e),
+            Err(_) => format!("connect timeout ({}ms)", dest.timeout_ms),
+        };
+        warn!("OSC TCP destination '{}' ({}): {}", dest.name, addr, err);
+        reporter.report(OscConnectionState::Disconnected, Some(err));
+        tokio::select! {
+            _ = token.cancelled() => return,
+            _ = tokio::time::sleep(backoff) => {}
+        }
+        backoff = (backoff * 2).min(MAX_BACKOFF);
+    }
+}
+
+/// Write queued packets until the connection fails (`Some(error)`) or the
+/// sender is cancelled (`None`).
+async fn pump(
+    stream: TcpStream,
+    queue: &PacketQueue,
+    write_timeout: Duration,
+    token: &CancellationToken,
+) -> Option<String> {
+    let (mut reader, mut writer) = stream.into_split();
+    let mut scratch = [0u8; 512];
+    loop {
+        while let Some(packet) = queue.pop() {
+            let err = match timeout(write_timeout, writer.write_all(&packet)).await {
+                Ok(Ok(())) => continue,
+                Ok(Err(e)) => format!("write failed: {}", e),
+                Err(_) => format!("write timeout ({}ms)", write_timeout.as_millis()),
+            };
+            queue.requeue(packet);
+            return Some(err);
+        }
+        let dropped = queue.take_dropped();
+        if dropped > 0 {
+            warn!(
+                "OSC TCP dropped {} queued messages while disconnected",
+                dropped
+            );
+        }
+        tokio::select! {
+            _ = token.cancelled() => return None,
+            _ = queue.notify.notified() => {}
+            // Anything the receiver sends back is ignored; reads only detect a closed socket
+            read = reader.read(&mut scratch) => match read {
+                Ok(0) => return Some("connection closed by peer".to_string()),
+                Ok(_) => {}
+                Err(e) => return Some(format!("read failed: {}", e)),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+
+    fn destination(port: u16) -> OscDestination {
+        OscDestination {
+            name: "test".to_string(),
+            host: "127.0.0.1".to_string(),
+            port,
+            protocol: OscSendProtocol::Tcp,
+            timeout_ms: 1000,
+            framing: OscTcpFraming::Slip,
+        }
+    }
+
+    fn pool() -> (TcpSenderPool, mpsc::UnboundedReceiver<OscConnectionStatus>) {
+        let (tx, rx) = mpsc::unbounded_channel();
+        (TcpSenderPool::new(CancellationToken::new(), tx), rx)
+    }
+
+    async fn read_frames(stream: &mut TcpStream, count: usize) -> Vec<u8> {
+        let expected = count * frame_tcp_packet(&OscTcpFraming::Slip, b"x").len();
+        let mut data = vec![0u8; expected];
+        timeout(Duration::from_secs(5), stream.read_exact(&mut data))
+            .await
+            .unwrap()
+            .unwrap();
+        data
+    }
+
+    async fn wait_for(
+        rx: &mut mpsc::UnboundedReceiver<OscConnectionStatus>,
+        state: OscConnectionState,
+    ) {
+        timeout(Duration::from_secs(5), async {
+            while rx.recv().await.unwrap().state != state {}
+        })
+        .await
+        .unwrap();
+    }
+
+    #[test]
+    fn test_queue_drops_oldest_when_full() {
+        let queue = PacketQueue::default();
+        for i in 0..QUEUE_CAPACITY + 2 {
+            queue.push((i as u32).to_be_bytes().to_vec(), "test");
+        }
+        assert_eq!(queue.take_dropped(), 2);
+        assert_eq!(queue.pop(), Some(2u32.to_be_bytes().to_vec()));
+    }
+
+    #[tokio::test]
+    async fn test_messages_share_one_connection() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let dest = destination(listener.local_addr().unwrap().port());
+        let (pool, mut rx) = pool();
+        let rt = tokio::runtime::Handle::current();
+        for _ in 0..3 {
+            pool.send(&dest, b"x", &rt);
+        }
+        let (mut stream, _) = listener.accept().await.unwrap();
+        assert_eq!(
+            read_frames(&mut stream, 3).await,
+            frame_tcp_packet(&OscTcpFraming::Slip, b"x").repeat(3)
+        );
+        wait_for(&mut rx, OscConnectionState::Connected).await;
+
+        pool.send(&dest, b"x", &rt);
+        read_frames(&mut stream, 1).await;
+        // No second connection was attempted
+        assert!(timeout(Duration::from_millis(100), listener.accept())
+            .await
+            .is_err());
+        assert_eq!(pool.statuses()[0].state, OscConnectionState::Connected);
+    }
+
+    #[tokio::test]
+    async fn test_removed_destination_is_closed() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let dest = destination(listener.local_addr().unwrap().port());
+        let (pool, mut rx) = pool();
+        pool.send(&dest, b"x", &tokio::runtime::Handle::current());
+        let (mut stream, _) = listener.accept().await.unwrap();
+        read_frames(&mut stream, 1).await;
+        wait_for(&mut rx, OscConnectionState::Connected).await;
+
+        let mut settings = Settings {
+            osc_destinations: vec![dest.clone()],
+            ..Default::default()
+        };
+        pool.retain_configured(&settings);
+        assert_eq!(pool.statuses().len(), 1);
+
+        settings.osc_destinations[0].name = "renamed".to_string();
+        pool.retain_configured(&settings);
+        assert!(pool.statuses().is_empty());
+        // The connection task hung up instead of redialing
+        let mut buf = [0u8; 1];
+        let read = timeout(Duration::from_secs(5), stream.read(&mut buf)).await;
+        assert!(matches!(read, Ok(Ok(0))));
+    }
+
+    #[tokio::test]
+    async fn test_length_prefix_destination() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let dest = OscDestination {
+            framing: OscTcpFraming::LengthPrefix,
+            ..destination(listener.local_addr().unwrap().port())
+        };
+        let (pool, _rx) = pool();
+        pool.send(&dest, b"abcd", &tokio::runtime::Handle::current());
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut data = [0u8; 8];
+        timeout(Duration::from_secs(5), stream.read_exact(&mut data))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(&data, b"\0\0\0\x04abcd");
+    }
+
+    #[tokio::test]
+    async fn test_reconnects_and_flushes_queue() {
+        let port = {
+            let probe = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            probe.local_addr().unwrap().port()
+        };
+        let dest = destination(port);
+        let (pool, mut rx) = pool();
+        let rt = tokio::runtime::Handle::current();
+        pool.send(&dest, b"x", &rt);
+        wait_for(&mut rx, OscConnectionState::Disconnected).await;
+        pool.send(&dest, b"x", &rt);
+
+        let listener = TcpListener::bind(("127.0.0.1", port)).await.unwrap();
+        let (mut stream, _) = timeout(Duration::from_secs(5), listener.accept())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            read_frames(&mut stream, 2).await,
+            frame_tcp_packet(&OscTcpFraming::Slip, b"x").repeat(2)
+        );
+
+        // Peer closing the socket is noticed and reconnected
+        drop(stream);
+        wait_for(&mut rx, OscConnectionState::Disconnected).await;
+        let (mut stream, _) = timeout(Duration::from_secs(5), listener.accept())
+            .await
+            .unwrap()
+            .unwrap();
+        pool.send(&dest, b"x", &rt);
+        read_frames(&mut stream, 1).await;
+    }
+}
@@ -0,0 +1,346 @@
+use crate::models::OutputFilter;
+use crate::router::{IncomingMessage, OutputAction, Routed};
+use crate::slew::{output_key, values_of, with_values};
+use log::error;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Once this many outputs are tracked, those with nothing held and nothing
+/// sent for `OUTPUT_IDLE` are forgotten.
+const MAX_OUTPUTS: usize = 1024;
+const OUTPUT_IDLE: Duration = Duration::from_secs(60);
+
+/// How many outputs a mapping's filter has dropped, by reason.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct DropCount {
+    /// Values replaced by a newer one while waiting out the rate limit
+    pub throttled: u64,
+    pub duplicate: u64,
+    pub deadband: u64,
+}
+
+/// Drop counts of every mapping, keyed by mapping ID. Shared with the
+/// `get_drop_counts` command and reset when the engine starts.
+#[derive(Default)]
+pub struct DropCounts {
+    counts: Mutex<HashMap<String, DropCount>>,
+}
+
+impl DropCounts {
+    fn add(&self, mapping_id: &str, count: impl FnOnce(&mut DropCount)) {
+        match self.counts.lock() {
+            Ok(mut guard) => count(guard.entry(mapping_id.to_string()).or_default()),
+            Err(e) => error!("Drop counts mutex poisoned in add(): {}", e),
+        }
+    }
+
+    pub fn snapshot(&self) -> HashMap<String, DropCount> {
+        match self.counts.lock() {
+            Ok(guard) => guard.clone(),
+            Err(e) => {
+                error!("Drop counts mutex poisoned in snapshot(): {}", e);
+                HashMap::new()
+            }
+        }
+    }
+
+    pub fn reset(&self) {
+        match self.counts.lock() {
+            Ok(mut guard) => guard.clear(),
+            Err(e) => error!("Drop counts mutex poisoned in reset(): {}", e),
+        }
+    }
+}
+
+/// An output held back by the rate limit, with the input that produced it
+/// for the activity event.
+pub struct Pending {
+    pub routed: Routed,
+    pub input: IncomingMessage,
+    due: Instant,
+}
+
+#[derive(Default)]
+struct OutputState {
+    last_sent: Option<OutputAction>,
+    sent_at: Option<Instant>,
+    pending: Option<Pending>,
+}
+
+/// Per-output bookkeeping for mappings with an `output_filter`.
+pub struct OutputFilters {
+    outputs: HashMap<String, OutputState>,
+    counts: Arc<DropCounts>,
+}
+
+impl OutputFilters {
+    pub fn new(counts: Arc<DropCounts>) -> Self {
+        Self {
+            outputs: HashMap::new(),
+            counts,
+        }
+    }
+
+    /// Run an output through its mapping's filter. Returns it when it should
+    /// be sent now; otherwise it was dropped or held for `flush_due`.
+    pub fn offer(
+        &mut self,
+        filter: &OutputFilter,
+        routed: Routed,
+        input: &IncomingMessage,
+        now: Instant,
+    ) -> Option<Routed> {
+        if !filter.is_active() {
+            return Some(routed);
+        }
+        let key = format!("{}:{}", routed.mapping_id, output_key(&routed.action));
+        if self.outputs.len() >= MAX_OUTPUTS && !self.outputs.contains_key(&key) {
+            self.outputs.retain(|_, s| {
+                s.pending.is_some()
+                    || s.sent_at
+                        .is_some_and(|t| now.duration_since(t) < OUTPUT_IDLE)
+            });
+        }
+        let state = self.outputs.entry(key).or_default();
+
+        if let Some(last) = &state.last_sent {
+            let unchanged = if filter.dedupe && *last == routed.action {
+                Some(Reason::Duplicate)
+            } else if within_deadband(last, &routed.action, filter.deadband) {
+                Some(Reason::Deadband)
+            } else {
+                None
+            };
+            if let Some(reason) = unchanged {
+                // The latest value is the one already sent, so a held one
+                // would now be stale
+                let superseded = state.pending.take().is_some();
+                self.counts.add(&routed.mapping_id, |c| {
+                    match reason {
+                        Reason::Duplicate => c.duplicate += 1,
+                        Reason::Deadband => c.deadband += 1,
+                    }
+                    if superseded {
+                        c.throttled += 1;
+                    }
+                });
+                return None;
+            }
+        }
+
+        let interval = filter
+            .max_rate_hz
+            .filter(|hz| *hz > 0.0)
+            .map(|hz| Duration::from_secs_f64(1.0 / hz));
+        let due = match (interval, state.sent_at) {
+            (Some(interval), Some(sent_at)) if now < sent_at + interval => sent_at + interval,
+            _ => {
+                state.last_sent = Some(routed.action.clone());
+                state.sent_at = Some(now);
+                state.pending = None;
+                return Some(routed);
+            }
+        };
+        if state.pending.is_some() {
+            self.counts.add(&routed.mapping_id, |c| c.throttled += 1);
+        }
+        state.pending = Some(Pending {
+            routed,
+            input: input.clone(),
+            due,
+        });
+        None
+    }
+
+    /// When the next held output is due.
+    pub fn next_due(&self) -> Option<Instant> {
+        self.outputs
+            .values()
+            .filter_map(|s| s.pending.as_ref().map(|p| p.due))
+            .min()
+    }
+
+    /// Take the held outputs whose rate limit has passed.
+    pub fn flush_due(&mut self, now: Instant) -> Vec<Pending> {
+        let mut due = Vec::new();
+        for state in self.outputs.values_mut() {
+            if state.pending.as_ref().is_some_and(|p| p.due <= now) {
+                if let Some(pending) = state.pending.take() {
+                    state.last_sent = Some(pending.routed.action.clone());
+                    state.sent_at = Some(now);
+                    due.push(pending);
+                }
+            }
+        }
+        due
+    }
+}
+
+enum Reason {
+    Duplicate,
+    Deadband,
+}
+
+/// Whether every numeric value of `next` is less than `deadband` away from
+/// `last` and nothing else changed.
+fn within_deadband(last: &OutputAction, next: &OutputAction, deadband: f64) -> bool {
+    if deadband <= 0.0 {
+        return false;
+    }
+    let (Some(old), Some(new)) = (values_of(last), values_of(next)) else {
+        return false;
+    };
+    old.len() == new.len()
+        && old.iter().zip(&new).all(|(a, b)| (a - b).abs() < deadband)
+        && with_values(last, &new) == *next
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::router::OscArgValue;
+
+    fn routed(value: f32) -> Routed {
+        Routed {
+            mapping_id: "m".to_string(),
+            slew: None,
+            action: OutputAction::Osc {
+                address: "/meter".to_string(),
+                args: vec![OscArgValue::Float(value)],
+                destinations: vec![],
+            },
+            steps: vec![],
+            retrigger: Default::default(),
+        }
+    }
+
+    fn input() -> IncomingMessage {
+        IncomingMessage::Osc {
+            address: "/in".to_string(),
+            args: vec![],
+        }
+    }
+
+    fn value(routed: &Routed) -> f32 {
+        match &routed.action {
+            OutputAction::Osc { args, .. } => match args[0] {
+                OscArgValue::Float(f) => f,
+                _ => panic!("Expected Float"),
+            },
+            other => panic!("Expected Osc, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_rate_limit_keeps_latest() {
+        let counts = Arc::new(DropCounts::default());
+        let mut filters = OutputFilters::new(counts.clone());
+        let filter = OutputFilter {
+            max_rate_hz: Some(10.0),
+            ..Default::default()
+        };
+        let t0 = Instant::now();
+
+        assert!(filters.offer(&filter, routed(0.1), &input(), t0).is_some());
+        let t1 = t0 + Duration::from_millis(20);
+        assert!(filters.offer(&filter, routed(0.2), &input(), t1).is_none());
+        assert!(filters.offer(&filter, routed(0.3), &input(), t1).is_none());
+        assert_eq!(filters.next_due(), Some(t0 + Duration::from_millis(100)));
+
+        assert!(filters.flush_due(t1).is_empty());
+        let flushed = filters.flush_due(t0 + Duration::from_millis(100));
+        assert_eq!(flushed.len(), 1);
+        assert_eq!(value(&flushed[0].routed), 0.3);
+        assert_eq!(filters.next_due(), None);
+        assert_eq!(counts.snapshot()["m"].throttled, 1);
+    }
+
+    #[test]
+    fn test_idle_outputs_are_forgotten() {
+        let mut filters = OutputFilters::new(Arc::default());
+        let filter = OutputFilter {
+            max_rate_hz: Some(10.0),
+            ..Default::default()
+        };
+        let fader = |n: usize| Routed {
+            action: OutputAction::Osc {
+                address: format!("/ch/{}/fader", n),
+                args: vec![OscArgValue::Float(0.5)],
+                destinations: vec![],
+            },
+            ..routed(0.5)
+        };
+        let t0 = Instant::now();
+        for n in 0..MAX_OUTPUTS {
+            filters.offer(&filter, fader(n), &input(), t0);
+        }
+        // Output 0 stays busy and keeps a value held back
+        let t1 = t0 + OUTPUT_IDLE - Duration::from_millis(50);
+        filters.offer(&filter, fader(0), &input(), t1);
+        filters.offer(&filter, fader(0), &input(), t1);
+
+        filters.offer(&filter, fader(MAX_OUTPUTS), &input(), t0 + OUTPUT_IDLE);
+        assert_eq!(filters.outputs.len(), 2);
+        assert!(filters.next_due().is_some());
+    }
+
+    #[test]
+    fn test_dedupe_and_deadband() {
+        let counts = Arc::new(DropCounts::default());
+        let mut filters = OutputFilters::new(counts.clone());
+        let filter = OutputFilter {
+            dedupe: true,
+            deadband: 0.05,
+            ..Default::default()
+        };
+        let now = Instant::now();
+
+        assert!(filters.offer(&filter, routed(0.5), &input(), now).is_some());
+        assert!(filters.offer(&filter, routed(0.5), &input(), now).is_none());
+        assert!(filters
+            .offer(&filter, routed(0.52), &input(), now)
+            .is_none());
+        // Measured from the last value sent, so slow drift still gets through
+        assert!(filters
+            .offer(&filter, routed(0.56), &input(), now)
+            .is_some());
+        assert_eq!(
+            counts.snapshot()["m"],
+            DropCount {
+                throttled: 0,
+                duplicate: 1,
+                deadband: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn test_return_to_sent_value_cancels_held_output() {
+        let counts = Arc::new(DropCounts::default());
+        let mut filters = OutputFilters::new(counts.clone());
+        let filter = OutputFilter {
+            max_rate_hz: Some(10.0),
+            dedupe: true,
+            ..Default::default()
+        };
+        let t0 = Instant::now();
+
+        assert!(filters.offer(&filter, routed(0.0), &input(), t0).is_some());
+        assert!(filters.offer(&filter, routed(1.0), &input(), t0).is_none());
+        assert!(filters.offer(&filter, routed(0.0), &input(), t0).is_none());
+        assert!(filters.flush_due(t0 + Duration::from_secs(1)).is_empty());
+    }
+
+    #[test]
+    fn test_inactive_filter_passes_everything() {
+        let mut filters = OutputFilters::new(Arc::default());
+        let now = Instant::now();
+        for _ in 0..3 {
+            assert!(filters
+                .offer(&OutputFilter::default(), routed(0.5), &input(), now)
+                .is_some());
+        }
+    }
+}
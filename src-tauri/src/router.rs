@@ -1,9 +1,17 @@
+use crate::condition::{ConditionChecker, ConditionInput};
+use crate::loop_guard::LoopGuard;
+use crate::macros::TimedOutput;
+use crate::mapping_state::{MappingStates, Transition};
+use crate::midi_engine::{MscTime, ParsedMsc, Timecode};
 use crate::models::*;
+use crate::osc_pattern::OscPattern;
+use crate::output_filter::{DropCounts, OutputFilters};
 use log::{error, warn};
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, Mutex};
-use std::time::Instant;
-use tauri::{AppHandle, Emitter};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Runtime, Wry};
 
 #[derive(Debug, Clone)]
 pub enum IncomingMessage {
@@ -12,46 +20,132 @@ pub enum IncomingMessage {
         args: Vec<OscArgValue>,
     },
     Midi {
+        /// Input port the message arrived on
+        port: String,
         message_type: MidiMessageType,
         channel: u8,
         note_or_cc: u8,
-        value: u8,
+        /// 0–127, or 0–16383 for pitch bend
+        value: u16,
+    },
+    /// A 14-bit CC pair or NRPN/RPN value assembled by the MIDI input
+    Cc14 {
+        port: String,
+        mode: CcMode,
+        channel: u8,
+        parameter: u16,
+        value: u16,
     },
     Msc {
+        port: String,
         device_id: u8,
         command_format: u8,
         command: u8,
         cue_number: String,
         cue_list: Option<String>,
         cue_path: Option<String>,
+        time: Option<MscTime>,
+        set_control: Option<u16>,
+        set_value: Option<u16>,
+    },
+    /// MIDI timecode; `running` is false for a full-frame locate
+    Mtc {
+        port: String,
+        time: Timecode,
+        running: bool,
+    },
+    /// Smoothed MIDI clock tempo, sent at most once a beat when it changes
+    Tempo {
+        port: String,
+        bpm: f64,
+    },
+    /// A quarter note of MIDI clock while the transport runs
+    Beat {
+        port: String,
+        /// Beat within the bar, from 1
+        beat: u32,
+        /// Bar number, from 1
+        bar: u32,
+    },
+    Transport {
+        port: String,
+        state: TransportState,
     },
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum OscArgValue {
     Int(i32),
     Float(f32),
     String(String),
+    Blob(Vec<u8>),
+    Bool(bool),
+    Nil,
+    Impulse,
+    Double(f64),
+    Int64(i64),
+    TimeTag { seconds: u32, fractional: u32 },
+    Char(char),
+    Color { red: u8, green: u8, blue: u8, alpha: u8 },
+    Midi { port: u8, status: u8, data1: u8, data2: u8 },
+    Array(Vec<OscArgValue>),
 }
 
-pub struct Router {
+/// Compiled `osc_address` patterns, keyed by the pattern string.
+#[derive(Default)]
+struct PatternCache {
+    compiled: HashMap<String, OscPattern>,
+    /// A pattern was compiled since the cache was last pruned
+    grown: bool,
+}
+
+pub struct Router<R: Runtime = Wry> {
     mappings: Arc<Mutex<Vec<Mapping>>>,
-    app_handle: AppHandle,
+    app_handle: AppHandle<R>,
+    patterns: Mutex<PatternCache>,
+    conditions: Mutex<ConditionChecker>,
+    states: Arc<MappingStates>,
+    filters: Mutex<OutputFilters>,
+    loop_guard: Arc<LoopGuard>,
     last_emit: Mutex<Instant>,
     emit_count: Mutex<u32>,
 }
 
-impl Router {
-    pub fn new(mappings: Arc<Mutex<Vec<Mapping>>>, app_handle: AppHandle) -> Self {
+impl<R: Runtime> Router<R> {
+    pub fn new(
+        mappings: Arc<Mutex<Vec<Mapping>>>,
+        states: Arc<MappingStates>,
+        drop_counts: Arc<DropCounts>,
+        loop_guard: Arc<LoopGuard>,
+        app_handle: AppHandle<R>,
+    ) -> Self {
+        // Compile patterns for the loaded mappings up front; mappings added
+        // while the engine runs are compiled on first use and cached.
+        let mut patterns = PatternCache::default();
+        if let Ok(guard) = mappings.lock() {
+            for mapping in guard.iter() {
+                if matches!(mapping.direction, Direction::OscToMidi | Direction::OscToOsc) {
+                    patterns
+                        .compiled
+                        .entry(mapping.osc_address.clone())
+                        .or_insert_with(|| compile_pattern(&mapping.osc_address));
+                }
+            }
+        }
         Self {
             mappings,
             app_handle,
+            patterns: Mutex::new(patterns),
+            conditions: Mutex::new(ConditionChecker::default()),
+            states,
+            filters: Mutex::new(OutputFilters::new(drop_counts)),
+            loop_guard,
             last_emit: Mutex::new(Instant::now()),
             emit_count: Mutex::new(0),
         }
     }
 
-    pub fn route(&self, msg: &IncomingMessage) -> Vec<OutputAction> {
+    pub fn route(&self, msg: &IncomingMessage) -> Vec<Routed> {
         let mappings = match self.mappings.lock() {
             Ok(guard) => guard,
             Err(e) => {
@@ -59,101 +153,339 @@ impl Router {
                 return Vec::new();
             }
         };
+        let now = Instant::now();
+        if self.loop_guard.is_echo(msg, now) {
+            self.emit_unmatched(msg, Some("Echo of our own output".to_string()));
+            return Vec::new();
+        }
+        let runaway = self.loop_guard.is_runaway(msg, now);
         let mut actions = Vec::new();
         let mut matched = false;
+        let mut rejection: Option<String> = None;
 
         for mapping in mappings.iter() {
             if !mapping.enabled {
                 continue;
             }
 
-            if let Some(action) = self.try_match(mapping, msg) {
-                matched = true;
-                self.emit_activity(mapping, msg, &action);
-                actions.push(action);
+            // A tripped mapping, or any mapping while the input is running
+            // away, is only matched to report it; its state must not move.
+            let blocked = runaway || self.loop_guard.is_tripped(&mapping.id);
+            match self.try_match(mapping, msg, !blocked) {
+                Ok(_) if blocked => {
+                    if runaway {
+                        self.trip_breaker(mapping, msg);
+                    }
+                    rejection
+                        .get_or_insert(format!("Loop breaker tripped on mapping {}", mapping.id));
+                }
+                Ok(action) => {
+                    matched = true;
+                    let routed = Routed {
+                        mapping_id: mapping.id.clone(),
+                        slew: mapping.slew.clone(),
+                        action,
+                        steps: macro_steps(mapping, msg),
+                        retrigger: mapping.retrigger,
+                    };
+                    if let Some(routed) = self.filter_output(mapping, routed, msg) {
+                        self.emit_activity(&routed.mapping_id, msg, &routed.action);
+                        actions.push(routed);
+                    }
+                }
+                Err(NoMatch::Rejected(reason)) => {
+                    rejection.get_or_insert(reason);
+                }
+                Err(NoMatch::Skip) => {}
             }
         }
+        self.forget_unused_patterns(&mappings);
 
         if !matched {
-            self.emit_unmatched(msg);
+            self.emit_unmatched(msg, rejection);
         }
 
         actions
     }
 
-    fn try_match(&self, mapping: &Mapping, msg: &IncomingMessage) -> Option<OutputAction> {
+    /// Disable a mapping caught in a feedback loop and warn about it once.
+    fn trip_breaker(&self, mapping: &Mapping, msg: &IncomingMessage) {
+        let (_, input) = format_incoming(msg);
+        let reason = format!(
+            "Feedback loop detected on {}: mapping {} disabled until its loop breaker is reset",
+            input, mapping.id
+        );
+        if self.loop_guard.trip(&mapping.id, reason.clone()) {
+            warn!("{}", reason);
+            let _ = self.app_handle.emit(
+                "engine-status",
+                EngineStatus {
+                    running: true,
+                    error: Some(reason),
+                },
+            );
+        }
+    }
+
+    /// When the next output held back by a rate limit is due.
+    pub fn next_flush(&self) -> Option<Instant> {
+        match self.filters.lock() {
+            Ok(guard) => guard.next_due(),
+            Err(e) => {
+                error!("Output filters mutex poisoned in next_flush(): {}", e);
+                None
+            }
+        }
+    }
+
+    /// Release the held outputs that are due.
+    pub fn flush_held(&self) -> Vec<Routed> {
+        let due = match self.filters.lock() {
+            Ok(mut guard) => guard.flush_due(Instant::now()),
+            Err(e) => {
+                error!("Output filters mutex poisoned in flush_held(): {}", e);
+                return Vec::new();
+            }
+        };
+        due.into_iter()
+            .map(|pending| {
+                self.emit_activity(
+                    &pending.routed.mapping_id,
+                    &pending.input,
+                    &pending.routed.action,
+                );
+                pending.routed
+            })
+            .collect()
+    }
+
+    /// Apply the mapping's rate limit, dedupe and deadband. `None` when the
+    /// output was dropped or held back.
+    fn filter_output(
+        &self,
+        mapping: &Mapping,
+        routed: Routed,
+        msg: &IncomingMessage,
+    ) -> Option<Routed> {
+        if !mapping.output_filter.is_active() {
+            return Some(routed);
+        }
+        match self.filters.lock() {
+            Ok(mut guard) => guard.offer(&mapping.output_filter, routed, msg, Instant::now()),
+            Err(e) => {
+                error!("Output filters mutex poisoned in filter_output(): {}", e);
+                Some(routed)
+            }
+        }
+    }
+
+    /// Match `msg` against one mapping. With `commit` false the mapping's
+    /// on/off state is left untouched.
+    fn try_match(
+        &self,
+        mapping: &Mapping,
+        msg: &IncomingMessage,
+        commit: bool,
+    ) -> Result<OutputAction, NoMatch> {
+        // Clock mappings only match their clock input, other mappings never do
+        if clock_input_of(msg) != mapping.clock_input {
+            return Err(NoMatch::Skip);
+        }
         match (msg, &mapping.direction) {
             (
                 IncomingMessage::Osc { address, args },
                 Direction::OscToMidi,
             ) => {
-                if address != &mapping.osc_address {
-                    return None;
+                let captures = self
+                    .osc_address_captures(&mapping.osc_address, address)
+                    .ok_or(NoMatch::Skip)?;
+                check_arg_types(mapping, args)?;
+                let input = ConditionInput {
+                    args,
+                    ..Default::default()
+                };
+                self.check_conditions(mapping, &input)?;
+                if mapping.midi_message_type == MidiMessageType::Msc {
+                    return msc_output(mapping, args, &captures);
                 }
+                let max = if is_cc14(mapping) {
+                    16383
+                } else {
+                    mapping.midi_message_type.max_value()
+                };
                 let value = match &mapping.midi_velocity_or_value {
-                    ValueSource::Static { value } => *value,
-                    ValueSource::OscArg { index } => {
-                        match args.get(*index) {
-                            Some(a) => osc_arg_to_midi_value(a),
-                            None => {
-                                warn!(
-                                    "OSC arg index {} out of range (message has {} args), defaulting to 0",
-                                    index,
-                                    args.len()
-                                );
-                                0
-                            }
+                    ValueSource::Static { value } => (*value).min(max),
+                    ValueSource::OscArg { index } => match args.get(*index) {
+                        Some(a) => osc_arg_to_midi_value(a, max),
+                        None => {
+                            return Err(NoMatch::Rejected(format!(
+                                "OSC arg {} is missing (message has {} args)",
+                                index,
+                                args.len()
+                            )))
                         }
+                    },
+                    ValueSource::Capture { index } => {
+                        capture_to_value(&captures, *index, max).map_err(NoMatch::Rejected)?
                     }
                 };
-                Some(OutputAction::Midi {
+                let channel = resolve_midi_field(&mapping.midi_channel, args, &captures)
+                    .map_err(NoMatch::Rejected)?
+                    .clamp(1, 16);
+                let note_or_cc = resolve_midi_field(&mapping.midi_note_or_cc, args, &captures)
+                    .map_err(NoMatch::Rejected)?;
+                let cc14_parameter = match mapping.cc_mode {
+                    CcMode::FourteenBit => u16::from(note_or_cc),
+                    _ => mapping.midi_parameter,
+                };
+                let cc14_parameter = is_cc14(mapping)
+                    .then(|| cc14_number(mapping.cc_mode, cc14_parameter))
+                    .transpose()?;
+                let latch = self.update_state(mapping, osc_is_press(args), commit)?;
+                let value = latched_value(value, max, latch);
+                if let Some(parameter) = cc14_parameter {
+                    return Ok(OutputAction::Cc14 {
+                        port: mapping.midi_output_port.clone(),
+                        mode: mapping.cc_mode,
+                        channel,
+                        parameter,
+                        value,
+                    });
+                }
+                Ok(OutputAction::Midi {
+                    port: mapping.midi_output_port.clone(),
                     message_type: mapping.midi_message_type.clone(),
-                    channel: mapping.midi_channel,
-                    note_or_cc: mapping.midi_note_or_cc,
+                    channel,
+                    note_or_cc,
                     value,
                 })
             }
             (
                 IncomingMessage::Midi {
+                    port,
                     message_type,
                     channel,
                     note_or_cc,
                     value,
                 },
-                Direction::MidiToOsc,
+                Direction::MidiToOsc | Direction::MidiToMidi,
             ) => {
-                if message_type != &mapping.midi_message_type
-                    || channel != &mapping.midi_channel
-                    || note_or_cc != &mapping.midi_note_or_cc
+                if !accepts_port(mapping, port)
+                    || message_type != &mapping.midi_message_type
+                    || is_cc14(mapping)
+                    || !mapping.midi_channel.accepts(*channel)
+                    || (message_type.has_note() && !mapping.midi_note_or_cc.accepts(*note_or_cc))
                 {
-                    return None;
+                    return Err(NoMatch::Skip);
                 }
                 if let Some(vel) = mapping.midi_input_velocity {
-                    if value != &vel {
-                        return None;
+                    if *value != vel {
+                        return Err(NoMatch::Skip);
                     }
                 }
+                let input = ConditionInput {
+                    midi_value: Some(*value),
+                    midi_note: Some(u16::from(*note_or_cc)),
+                    ..Default::default()
+                };
+                self.check_conditions(mapping, &input)?;
+                let latch =
+                    self.update_state(mapping, midi_is_press(message_type, *value), commit)?;
+                let value = latched_value(*value, message_type.max_value(), latch);
+                if mapping.direction == Direction::MidiToMidi {
+                    return remap_midi(mapping, message_type, *channel, *note_or_cc, value);
+                }
+                let note_or_cc = u16::from(*note_or_cc);
                 let osc_args: Vec<OscArgValue> = mapping
                     .osc_args
                     .iter()
-                    .map(|def| build_osc_arg(def, *value, *note_or_cc))
+                    .map(|def| build_osc_arg(def, value, message_type.max_value(), note_or_cc))
                     .collect();
-                Some(OutputAction::Osc {
-                    address: mapping.osc_address.clone(),
+                Ok(OutputAction::Osc {
+                    address: fill_address(
+                        &mapping.osc_address,
+                        &[],
+                        &midi_fields(*channel, note_or_cc),
+                    ),
                     args: osc_args,
+                    destinations: mapping.osc_destinations.clone(),
+                })
+            }
+            (
+                IncomingMessage::Cc14 {
+                    port,
+                    mode,
+                    channel,
+                    parameter,
+                    value,
+                },
+                Direction::MidiToOsc | Direction::MidiToMidi,
+            ) => {
+                if !accepts_port(mapping, port)
+                    || !is_cc14(mapping)
+                    || *mode != mapping.cc_mode
+                    || !mapping.midi_channel.accepts(*channel)
+                {
+                    return Err(NoMatch::Skip);
+                }
+                let parameter_matches = match mode {
+                    CcMode::FourteenBit => u8::try_from(*parameter)
+                        .is_ok_and(|cc| mapping.midi_note_or_cc.accepts(cc)),
+                    _ => *parameter == mapping.midi_parameter,
+                };
+                if !parameter_matches {
+                    return Err(NoMatch::Skip);
+                }
+                if mapping.midi_input_velocity.is_some_and(|vel| *value != vel) {
+                    return Err(NoMatch::Skip);
+                }
+                let input = ConditionInput {
+                    midi_value: Some(*value),
+                    midi_note: Some(*parameter),
+                    ..Default::default()
+                };
+                self.check_conditions(mapping, &input)?;
+                let latch = self.update_state(mapping, *value > 0, commit)?;
+                let value = latched_value(*value, 16383, latch);
+                if mapping.direction == Direction::MidiToMidi {
+                    return remap_cc14(mapping, *channel, *parameter, value);
+                }
+                let osc_args: Vec<OscArgValue> = mapping
+                    .osc_args
+                    .iter()
+                    .map(|def| build_osc_arg(def, value, 16383, *parameter))
+                    .collect();
+                Ok(OutputAction::Osc {
+                    address: fill_address(
+                        &mapping.osc_address,
+                        &[],
+                        &midi_fields(*channel, *parameter),
+                    ),
+                    args: osc_args,
+                    destinations: mapping.osc_destinations.clone(),
                 })
             }
             (
                 IncomingMessage::Osc { address, args },
                 Direction::OscToOsc,
             ) => {
-                if address != &mapping.osc_address {
-                    return None;
-                }
+                let captures = self
+                    .osc_address_captures(&mapping.osc_address, address)
+                    .ok_or(NoMatch::Skip)?;
+                check_arg_types(mapping, args)?;
+                let input = ConditionInput {
+                    args,
+                    ..Default::default()
+                };
+                self.check_conditions(mapping, &input)?;
+                let latch = self.update_state(mapping, osc_is_press(args), commit)?;
+                let latched = latch.map(|on| latched_args(args, on));
+                let args = latched.as_ref().unwrap_or(args);
+                // Pass the concrete incoming address through, not the pattern
                 let output_address = if mapping.osc_output_address.is_empty() {
-                    mapping.osc_address.clone()
+                    address.clone()
                 } else {
-                    mapping.osc_output_address.clone()
+                    fill_address(&mapping.osc_output_address, &captures, &[])
                 };
                 let output_args = match &mapping.osc_transform {
                     Some(transform) => args
@@ -162,29 +494,34 @@ impl Router {
                         .collect(),
                     None => args.clone(),
                 };
-                Some(OutputAction::Osc {
+                Ok(OutputAction::Osc {
                     address: output_address,
                     args: output_args,
+                    destinations: mapping.osc_destinations.clone(),
                 })
             }
             (
                 IncomingMessage::Msc {
+                    port,
                     device_id,
                     command_format,
                     command,
                     cue_number,
                     cue_list,
                     cue_path,
+                    time,
+                    set_control,
+                    set_value,
                 },
-                Direction::MidiToOsc,
+                Direction::MidiToOsc | Direction::MidiToMidi,
             ) => {
-                if mapping.midi_message_type != MidiMessageType::Msc {
-                    return None;
+                if mapping.midi_message_type != MidiMessageType::Msc || !accepts_port(mapping, port) {
+                    return Err(NoMatch::Skip);
                 }
                 // Filter by device ID (None = match all)
                 if let Some(expected_id) = mapping.msc_device_id {
                     if *device_id != expected_id && *device_id != 0x7F && expected_id != 0x7F {
-                        return None;
+                        return Err(NoMatch::Skip);
                     }
                 }
                 // Filter by command format
@@ -194,30 +531,175 @@ impl Router {
                         && *command_format != 0x7F
                         && expected_byte != 0x7F
                     {
-                        return None;
+                        return Err(NoMatch::Skip);
                     }
                 }
                 // Filter by command
                 if let Some(ref expected_cmd) = mapping.msc_command {
                     let expected_byte = msc_command_to_byte(expected_cmd);
                     if *command != expected_byte {
-                        return None;
+                        return Err(NoMatch::Skip);
+                    }
+                }
+                self.check_conditions(mapping, &ConditionInput::default())?;
+                if mapping.direction == Direction::MidiToMidi {
+                    // MSC passes through unchanged
+                    return Ok(OutputAction::Msc {
+                        msc: ParsedMsc {
+                            device_id: *device_id,
+                            command_format: *command_format,
+                            command: *command,
+                            cue_number: cue_number.clone(),
+                            cue_list: cue_list.clone(),
+                            cue_path: cue_path.clone(),
+                            time: time.clone(),
+                            set_control: *set_control,
+                            set_value: *set_value,
+                        },
+                        port: mapping.midi_output_port.clone(),
+                    });
+                }
+                let osc_args: Vec<OscArgValue> = mapping
+                    .osc_args
+                    .iter()
+                    .map(|def| {
+                        let set = set_control.zip(*set_value);
+                        build_osc_arg_msc(def, cue_number, cue_list, cue_path, time, set)
+                    })
+                    .collect();
+                Ok(OutputAction::Osc {
+                    address: mapping.osc_address.clone(),
+                    args: osc_args,
+                    destinations: mapping.osc_destinations.clone(),
+                })
+            }
+            (
+                IncomingMessage::Tempo { port, .. }
+                | IncomingMessage::Beat { port, .. }
+                | IncomingMessage::Transport { port, .. },
+                Direction::MidiToOsc,
+            ) => {
+                let (midi_value, midi_note) = match msg {
+                    IncomingMessage::Tempo { bpm, .. } => (Some(bpm.round() as u16), None),
+                    IncomingMessage::Beat { beat, bar, .. } => {
+                        (u16::try_from(*beat).ok(), u16::try_from(*bar).ok())
+                    }
+                    _ => (None, None),
+                };
+                if !accepts_port(mapping, port) {
+                    return Err(NoMatch::Skip);
+                }
+                if let (IncomingMessage::Transport { state, .. }, Some(expected)) =
+                    (msg, mapping.transport_state)
+                {
+                    if *state != expected {
+                        return Err(NoMatch::Skip);
                     }
                 }
+                let input = ConditionInput {
+                    midi_value,
+                    midi_note,
+                    ..Default::default()
+                };
+                self.check_conditions(mapping, &input)?;
                 let osc_args: Vec<OscArgValue> = mapping
                     .osc_args
                     .iter()
-                    .map(|def| build_osc_arg_msc(def, cue_number, cue_list, cue_path))
+                    .map(|def| build_osc_arg_clock(def, msg))
                     .collect();
-                Some(OutputAction::Osc {
+                Ok(OutputAction::Osc {
                     address: mapping.osc_address.clone(),
                     args: osc_args,
+                    destinations: mapping.osc_destinations.clone(),
                 })
             }
-            _ => None,
+            _ => Err(NoMatch::Skip),
+        }
+    }
+
+    /// Run the mapping's conditions, rejecting with the first that fails.
+    fn check_conditions(&self, mapping: &Mapping, input: &ConditionInput) -> Result<(), NoMatch> {
+        if mapping.conditions.is_empty() {
+            return Ok(());
+        }
+        let mut checker = match self.conditions.lock() {
+            Ok(guard) => guard,
+            Err(e) => {
+                error!("Conditions mutex poisoned in check_conditions(): {}", e);
+                return Err(NoMatch::Skip);
+            }
+        };
+        checker
+            .check(&mapping.conditions, input)
+            .map_err(NoMatch::Rejected)
+    }
+
+    /// Run a press or release through the mapping's mode. `Some(on)` means
+    /// the output carries the new on/off state instead of the input value.
+    fn update_state(
+        &self,
+        mapping: &Mapping,
+        press: bool,
+        commit: bool,
+    ) -> Result<Option<bool>, NoMatch> {
+        if !commit {
+            return Ok(None);
+        }
+        match self.states.update(mapping, press) {
+            Transition::Passthrough => Ok(None),
+            Transition::Set(on) => {
+                if mapping.persist_state {
+                    self.states.mark_unsaved();
+                }
+                Ok(Some(on))
+            }
+            Transition::Ignore => Err(NoMatch::Skip),
         }
     }
 
+    /// Match `address` against a mapping's pattern, returning its wildcard captures.
+    fn osc_address_captures(&self, pattern: &str, address: &str) -> Option<Vec<String>> {
+        let mut patterns = match self.patterns.lock() {
+            Ok(guard) => guard,
+            Err(e) => {
+                error!("Patterns mutex poisoned in osc_address_captures(): {}", e);
+                return (pattern == address).then(Vec::new);
+            }
+        };
+        if !patterns.compiled.contains_key(pattern) {
+            patterns
+                .compiled
+                .insert(pattern.to_string(), compile_pattern(pattern));
+            patterns.grown = true;
+        }
+        patterns.compiled[pattern].captures(address)
+    }
+
+    /// Once a new pattern has been compiled, e.g. after an `osc_address` was
+    /// edited, drop the ones no enabled mapping uses anymore.
+    fn forget_unused_patterns(&self, mappings: &[Mapping]) {
+        let mut patterns = match self.patterns.lock() {
+            Ok(guard) => guard,
+            Err(e) => {
+                error!("Patterns mutex poisoned in forget_unused_patterns(): {}", e);
+                return;
+            }
+        };
+        if !std::mem::take(&mut patterns.grown) {
+            return;
+        }
+        let used: HashSet<&str> = mappings
+            .iter()
+            .filter(|m| {
+                m.enabled && matches!(m.direction, Direction::OscToMidi | Direction::OscToOsc)
+            })
+            .map(|m| m.osc_address.as_str())
+            .collect();
+        patterns
+            .compiled
+            .retain(|pattern, _| used.contains(pattern.as_str()));
+    }
+
     fn can_emit(&self) -> bool {
         let mut last = match self.last_emit.lock() {
             Ok(guard) => guard,
@@ -245,7 +727,7 @@ impl Router {
         true
     }
 
-    fn emit_activity(&self, mapping: &Mapping, msg: &IncomingMessage, action: &OutputAction) {
+    fn emit_activity(&self, mapping_id: &str, msg: &IncomingMessage, action: &OutputAction) {
         if !self.can_emit() {
             return;
         }
@@ -260,12 +742,12 @@ impl Router {
                 input_display,
                 output_protocol,
                 output_display,
-                mapping_id: mapping.id.clone(),
+                mapping_id: mapping_id.to_string(),
             },
         );
     }
 
-    fn emit_unmatched(&self, msg: &IncomingMessage) {
+    fn emit_unmatched(&self, msg: &IncomingMessage, reason: Option<String>) {
         if !self.can_emit() {
             return;
         }
@@ -277,30 +759,460 @@ impl Router {
                 timestamp,
                 protocol,
                 display,
+                reason,
             },
         );
     }
 }
 
+/// Whether a MIDI message from `port` passes the mapping's input port filter.
+fn accepts_port(mapping: &Mapping, port: &str) -> bool {
+    mapping.midi_input_port.as_deref().is_none_or(|p| p == port)
+}
+
+/// Whether a mapping reads or writes 14-bit CC / NRPN / RPN values.
+fn is_cc14(mapping: &Mapping) -> bool {
+    mapping.midi_message_type == MidiMessageType::Cc && mapping.cc_mode != CcMode::SevenBit
+}
+
+/// Why a mapping produced no output for a message.
+#[derive(Debug)]
+enum NoMatch {
+    /// The message is not addressed to this mapping
+    Skip,
+    /// The message reached this mapping but a check rejected it
+    Rejected(String),
+}
+
+/// An output produced by one mapping.
 #[derive(Debug, Clone)]
+pub struct Routed {
+    pub mapping_id: String,
+    pub slew: Option<Slew>,
+    pub action: OutputAction,
+    /// Macro outputs to send after `action`
+    pub steps: Vec<TimedOutput>,
+    pub retrigger: Retrigger,
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub enum OutputAction {
     Midi {
+        /// Output port; `None` uses the default output
+        port: Option<String>,
         message_type: MidiMessageType,
         channel: u8,
         note_or_cc: u8,
-        value: u8,
+        /// 0–127, or 0–16383 for pitch bend
+        value: u16,
+    },
+    /// Sent as a CC pair or an NRPN/RPN sequence
+    Cc14 {
+        port: Option<String>,
+        mode: CcMode,
+        channel: u8,
+        parameter: u16,
+        value: u16,
     },
     Osc {
         address: String,
         args: Vec<OscArgValue>,
+        /// Destination names from the mapping; empty means the default target
+        destinations: Vec<String>,
+    },
+    Msc {
+        msc: ParsedMsc,
+        port: Option<String>,
     },
 }
 
+/// Build a macro mapping's further outputs. OSC argument sources read the
+/// value and note of a triggering MIDI message.
+fn macro_steps(mapping: &Mapping, msg: &IncomingMessage) -> Vec<TimedOutput> {
+    let (midi_value, value_max, midi_note) = match msg {
+        IncomingMessage::Midi {
+            message_type,
+            note_or_cc,
+            value,
+            ..
+        } => (*value, message_type.max_value(), u16::from(*note_or_cc)),
+        IncomingMessage::Cc14 {
+            parameter, value, ..
+        } => (*value, 16383, *parameter),
+        _ => (0, 127, 0),
+    };
+    mapping
+        .steps
+        .iter()
+        .filter_map(|step| {
+            let action = match &step.output {
+                MacroOutput::Osc {
+                    address,
+                    args,
+                    destinations,
+                } => OutputAction::Osc {
+                    address: address.clone(),
+                    args: args
+                        .iter()
+                        .map(|def| build_osc_arg(def, midi_value, value_max, midi_note))
+                        .collect(),
+                    destinations: destinations.clone(),
+                },
+                MacroOutput::Midi {
+                    message_type: MidiMessageType::Msc,
+                    ..
+                } => {
+                    warn!("Mapping {}: MSC macro steps are not supported", mapping.id);
+                    return None;
+                }
+                MacroOutput::Midi {
+                    message_type,
+                    channel,
+                    note_or_cc,
+                    value,
+                    port,
+                } => OutputAction::Midi {
+                    port: port.clone(),
+                    message_type: message_type.clone(),
+                    channel: *channel,
+                    note_or_cc: *note_or_cc,
+                    value: (*value).min(message_type.max_value()),
+                },
+            };
+            Some(TimedOutput {
+                delay: Duration::from_millis(step.delay_ms),
+                action,
+            })
+        })
+        .collect()
+}
+
+/// Build the MSC output of an `OscToMidi` mapping.
+fn msc_output(
+    mapping: &Mapping,
+    args: &[OscArgValue],
+    captures: &[String],
+) -> Result<OutputAction, NoMatch> {
+    let command = mapping
+        .msc_command
+        .as_ref()
+        .ok_or_else(|| NoMatch::Rejected("MSC output has no command".to_string()))?;
+    // These carry a time or SET control/value that mappings can't supply yet;
+    // sending zeros would be a wrong but valid command
+    let unsupported = match command {
+        MscCommand::TimedGo => Some("TIMED_GO"),
+        MscCommand::Set => Some("SET"),
+        MscCommand::SetClock => Some("SET_CLOCK"),
+        _ => None,
+    };
+    if let Some(name) = unsupported {
+        return Err(NoMatch::Rejected(format!(
+            "MSC {} output is not supported: mappings can't set its time or control fields",
+            name
+        )));
+    }
+    let command = msc_command_to_byte(command);
+    let cue_number = resolve_msc_cue(&mapping.msc_cue_number, args, captures)?;
+    let cue_list = resolve_msc_cue(&mapping.msc_cue_list, args, captures)?;
+    let cue_path = resolve_msc_cue(&mapping.msc_cue_path, args, captures)?;
+    // Clock and cue list/path commands carry a single list or path field
+    let standalone_field = (0x15..=0x1E).contains(&command);
+    if !standalone_field && cue_list.is_some() && cue_number.is_none() {
+        return Err(NoMatch::Rejected(
+            "MSC cue list needs a cue number".to_string(),
+        ));
+    }
+    if !standalone_field && cue_path.is_some() && cue_list.is_none() {
+        return Err(NoMatch::Rejected(
+            "MSC cue path needs a cue list".to_string(),
+        ));
+    }
+    if command == 0x07
+        && cue_number
+            .as_deref()
+            .is_some_and(|n| n.parse::<u8>().map_or(true, |n| n > 127))
+    {
+        return Err(NoMatch::Rejected(
+            "MSC FIRE macro number must be 0-127".to_string(),
+        ));
+    }
+    let msc = ParsedMsc {
+        device_id: mapping.msc_device_id.unwrap_or(0x7F) & 0x7F,
+        command_format: mapping
+            .msc_command_format
+            .as_ref()
+            .map(msc_command_format_to_byte)
+            .unwrap_or(0x7F),
+        command,
+        cue_number: cue_number.unwrap_or_default(),
+        cue_list,
+        cue_path,
+        time: None,
+        set_control: None,
+        set_value: None,
+    };
+    Ok(OutputAction::Msc {
+        msc,
+        port: mapping.midi_output_port.clone(),
+    })
+}
+
+/// Resolve one MSC cue field. Cue values may only contain digits and `.`.
+fn resolve_msc_cue(
+    source: &Option<MscCueSource>,
+    args: &[OscArgValue],
+    captures: &[String],
+) -> Result<Option<String>, NoMatch> {
+    let value = match source {
+        None => return Ok(None),
+        Some(MscCueSource::Static { value }) => value.clone(),
+        Some(MscCueSource::OscArg { index }) => match args.get(*index) {
+            Some(OscArgValue::Int(i)) => i.to_string(),
+            Some(OscArgValue::Int64(i)) => i.to_string(),
+            Some(OscArgValue::Float(f)) => f.to_string(),
+            Some(OscArgValue::Double(d)) => d.to_string(),
+            Some(OscArgValue::String(s)) => s.clone(),
+            Some(other) => {
+                return Err(NoMatch::Rejected(format!(
+                    "MSC cue arg {} is not a number or string: {}",
+                    index,
+                    format_osc_arg(other)
+                )))
+            }
+            None => {
+                return Err(NoMatch::Rejected(format!(
+                    "MSC cue arg index {} out of range",
+                    index
+                )))
+            }
+        },
+        Some(MscCueSource::Capture { index }) => captures
+            .get(*index)
+            .cloned()
+            .ok_or_else(|| NoMatch::Rejected(format!("MSC cue capture {} out of range", index)))?,
+    };
+    if value.is_empty() {
+        return Ok(None);
+    }
+    if !value.chars().all(|c| c.is_ascii_digit() || c == '.') {
+        return Err(NoMatch::Rejected(format!(
+            "invalid MSC cue value '{}'",
+            value
+        )));
+    }
+    Ok(Some(value))
+}
+
+/// Build the output of a `MidiToMidi` mapping from a matched MIDI message.
+fn remap_midi(
+    mapping: &Mapping,
+    message_type: &MidiMessageType,
+    channel: u8,
+    note_or_cc: u8,
+    value: u16,
+) -> Result<OutputAction, NoMatch> {
+    let remap = &mapping.midi_remap;
+    let output_type = remap.message_type.as_ref().unwrap_or(message_type);
+    if *output_type == MidiMessageType::Msc {
+        return Err(NoMatch::Rejected(
+            "MIDI can't be remapped to MSC; MSC only passes through".to_string(),
+        ));
+    }
+    let number = remap.number.map_or(note_or_cc, |n| n.min(127) as u8);
+    Ok(OutputAction::Midi {
+        port: mapping.midi_output_port.clone(),
+        message_type: output_type.clone(),
+        channel: remap.channel.unwrap_or(channel).clamp(1, 16),
+        note_or_cc: number,
+        value: remap_midi_value(
+            value,
+            message_type.max_value(),
+            output_type.max_value(),
+            mapping.osc_transform.as_ref(),
+        ),
+    })
+}
+
+/// Build the output of a `MidiToMidi` mapping from a matched 14-bit CC or
+/// NRPN/RPN value. The output keeps the mapping's `cc_mode`.
+fn remap_cc14(
+    mapping: &Mapping,
+    channel: u8,
+    parameter: u16,
+    value: u16,
+) -> Result<OutputAction, NoMatch> {
+    let remap = &mapping.midi_remap;
+    let parameter = match remap.number {
+        None => parameter,
+        Some(n) => cc14_number(mapping.cc_mode, n)?,
+    };
+    Ok(OutputAction::Cc14 {
+        port: mapping.midi_output_port.clone(),
+        mode: mapping.cc_mode,
+        channel: remap.channel.unwrap_or(channel).clamp(1, 16),
+        parameter,
+        value: remap_midi_value(value, 16383, 16383, mapping.osc_transform.as_ref()),
+    })
+}
+
+/// Check the controller or parameter number of a 14-bit output. A 14-bit
+/// CC pairs controller N with N + 32, so N must be 0–31.
+fn cc14_number(mode: CcMode, number: u16) -> Result<u16, NoMatch> {
+    match mode {
+        CcMode::FourteenBit if number > 31 => Err(NoMatch::Rejected(format!(
+            "14-bit CC needs a controller number 0–31, got {}",
+            number
+        ))),
+        _ => Ok(number.min(16383)),
+    }
+}
+
+/// Apply `transform` to a raw MIDI value, or rescale it between the input
+/// and output ranges when there is none.
+fn remap_midi_value(
+    value: u16,
+    in_max: u16,
+    out_max: u16,
+    transform: Option<&OscTransform>,
+) -> u16 {
+    let out = match transform {
+        Some(t) => transform_value(f64::from(value), t),
+        None if in_max == out_max => return value.min(out_max),
+        None => f64::from(value) * f64::from(out_max) / f64::from(in_max),
+    };
+    out.round().clamp(0.0, f64::from(out_max)) as u16
+}
+
+/// Whether an OSC message is a press: no arguments, or a first argument
+/// that is true or above zero.
+fn osc_is_press(args: &[OscArgValue]) -> bool {
+    match args.first() {
+        None | Some(OscArgValue::Impulse) => true,
+        Some(OscArgValue::Int(i)) => *i > 0,
+        Some(OscArgValue::Float(f)) => *f > 0.0,
+        Some(OscArgValue::Double(d)) => *d > 0.0,
+        Some(OscArgValue::Int64(i)) => *i > 0,
+        Some(OscArgValue::Bool(b)) => *b,
+        Some(_) => true,
+    }
+}
+
+/// Whether a MIDI message is a press. Note Off and zero values are
+/// releases; a program change is always a press.
+fn midi_is_press(message_type: &MidiMessageType, value: u16) -> bool {
+    match message_type {
+        MidiMessageType::NoteOff => false,
+        MidiMessageType::ProgramChange => true,
+        _ => value > 0,
+    }
+}
+
+/// The output value for a toggle/latch state: 0 when off, and the incoming
+/// value (or `max` if that is 0) when on.
+fn latched_value(value: u16, max: u16, latch: Option<bool>) -> u16 {
+    match latch {
+        None => value,
+        Some(false) => 0,
+        Some(true) if value == 0 => max,
+        Some(true) => value,
+    }
+}
+
+/// Replace the first argument with 1 or 0 of the same type, so the
+/// transform (if any) sees the on/off state.
+fn latched_args(args: &[OscArgValue], on: bool) -> Vec<OscArgValue> {
+    let n = if on { 1 } else { 0 };
+    let state = match args.first() {
+        Some(OscArgValue::Float(_)) => OscArgValue::Float(n as f32),
+        Some(OscArgValue::Double(_)) => OscArgValue::Double(n as f64),
+        Some(OscArgValue::Int64(_)) => OscArgValue::Int64(n as i64),
+        Some(OscArgValue::Bool(_)) => OscArgValue::Bool(on),
+        _ => OscArgValue::Int(n),
+    };
+    let rest = args.iter().skip(1).cloned();
+    std::iter::once(state).chain(rest).collect()
+}
+
+/// Enforce `osc_arg_types` according to the mapping's `osc_arg_match` mode.
+fn check_arg_types(mapping: &Mapping, args: &[OscArgValue]) -> Result<(), NoMatch> {
+    let expected = &mapping.osc_arg_types;
+    let ok = match mapping.osc_arg_match {
+        OscArgMatch::Any => return Ok(()),
+        OscArgMatch::Exact => args.len() == expected.len(),
+        OscArgMatch::Prefix => args.len() >= expected.len(),
+    } && expected
+        .iter()
+        .zip(args)
+        .all(|(t, a)| osc_arg_type_of(a).as_ref() == Some(t));
+    if ok {
+        return Ok(());
+    }
+    let expected_tags: String = expected.iter().map(osc_type_tag).collect();
+    let actual_tags: String = args
+        .iter()
+        .map(|a| osc_arg_type_of(a).as_ref().map(osc_type_tag).unwrap_or('['))
+        .collect();
+    Err(NoMatch::Rejected(format!(
+        "type mismatch: expected ,{}{} got ,{}",
+        expected_tags,
+        if mapping.osc_arg_match == OscArgMatch::Prefix { "…" } else { "" },
+        actual_tags
+    )))
+}
+
+/// The declared type matching an incoming argument. Arrays have none.
+pub(crate) fn osc_arg_type_of(arg: &OscArgValue) -> Option<OscArgType> {
+    Some(match arg {
+        OscArgValue::Int(_) => OscArgType::Int,
+        OscArgValue::Float(_) => OscArgType::Float,
+        OscArgValue::String(_) => OscArgType::String,
+        OscArgValue::Blob(_) => OscArgType::Blob,
+        OscArgValue::Bool(_) => OscArgType::Bool,
+        OscArgValue::Nil => OscArgType::Nil,
+        OscArgValue::Impulse => OscArgType::Impulse,
+        OscArgValue::Double(_) => OscArgType::Double,
+        OscArgValue::Int64(_) => OscArgType::Int64,
+        OscArgValue::TimeTag { .. } => OscArgType::Timetag,
+        OscArgValue::Char(_) => OscArgType::Char,
+        OscArgValue::Color { .. } => OscArgType::Color,
+        OscArgValue::Midi { .. } => OscArgType::Midi,
+        OscArgValue::Array(_) => return None,
+    })
+}
+
+fn osc_type_tag(t: &OscArgType) -> char {
+    match t {
+        OscArgType::Int => 'i',
+        OscArgType::Float => 'f',
+        OscArgType::String => 's',
+        OscArgType::Blob => 'b',
+        OscArgType::Bool => 'T',
+        OscArgType::Nil => 'N',
+        OscArgType::Impulse => 'I',
+        OscArgType::Double => 'd',
+        OscArgType::Int64 => 'h',
+        OscArgType::Timetag => 't',
+        OscArgType::Char => 'c',
+        OscArgType::Color => 'r',
+        OscArgType::Midi => 'm',
+    }
+}
+
+fn compile_pattern(pattern: &str) -> OscPattern {
+    OscPattern::compile(pattern).unwrap_or_else(|e| {
+        warn!("{}, matching it literally", e);
+        OscPattern::literal(pattern)
+    })
+}
+
 fn apply_transform(arg: &OscArgValue, transform: &OscTransform) -> OscArgValue {
+    // Only numeric arguments are scaled; everything else passes through
     let input_f64 = match arg {
         OscArgValue::Float(f) => *f as f64,
         OscArgValue::Int(i) => *i as f64,
-        OscArgValue::String(_) => return arg.clone(),
+        OscArgValue::Double(d) => *d,
+        OscArgValue::Int64(i) => *i as f64,
+        _ => return arg.clone(),
     };
     let result = transform_value(input_f64, transform);
 
@@ -308,7 +1220,9 @@ fn apply_transform(arg: &OscArgValue, transform: &OscTransform) -> OscArgValue {
         OscOutputType::Auto => match arg {
             OscArgValue::Float(_) => OscArgValue::Float(result as f32),
             OscArgValue::Int(_) => OscArgValue::Int(result.round() as i32),
-            OscArgValue::String(_) => unreachable!(),
+            OscArgValue::Double(_) => OscArgValue::Double(result),
+            OscArgValue::Int64(_) => OscArgValue::Int64(result.round() as i64),
+            _ => unreachable!(),
         },
         OscOutputType::Float => OscArgValue::Float(result as f32),
         OscOutputType::Int => OscArgValue::Int(result.floor() as i32),
@@ -485,16 +1399,95 @@ fn monotone_cubic_interpolate(x: f64, points: &[CalibrationPoint]) -> f64 {
         + h11 * h * m[seg + 1]
 }
 
-fn osc_arg_to_midi_value(arg: &OscArgValue) -> u8 {
+/// Convert an OSC arg to a MIDI value in `0..=max` (127, or 16383 for
+/// pitch bend). Floats are scaled from 0.0–1.0; ints are taken as-is.
+fn osc_arg_to_midi_value(arg: &OscArgValue, max: u16) -> u16 {
+    let scale = max as f64;
     match arg {
-        OscArgValue::Float(f) => (f * 127.0).round().clamp(0.0, 127.0) as u8,
-        OscArgValue::Int(i) => (*i).clamp(0, 127) as u8,
-        OscArgValue::String(_) => 0,
+        OscArgValue::Float(f) => (*f as f64 * scale).round().clamp(0.0, scale) as u16,
+        OscArgValue::Double(d) => (d * scale).round().clamp(0.0, scale) as u16,
+        OscArgValue::Int(i) => (*i).clamp(0, max as i32) as u16,
+        OscArgValue::Int64(i) => (*i).clamp(0, max as i64) as u16,
+        OscArgValue::Bool(true) | OscArgValue::Impulse => max,
+        OscArgValue::Midi { data1, data2, .. } if max > 127 => {
+            (*data2 as u16 & 0x7F) << 7 | (*data1 as u16 & 0x7F)
+        }
+        OscArgValue::Midi { data2, .. } => (*data2 & 0x7F) as u16,
+        _ => 0,
     }
 }
 
-fn midi_value_to_osc_float(value: u8) -> f32 {
-    value as f32 / 127.0
+/// Resolve a channel or note/CC field to a plain number. Unlike velocity,
+/// OSC floats are not scaled — `12.0` means 12, not 12 × 127. Fails with
+/// the reason when the arg or capture is missing or not a number.
+fn resolve_midi_field(
+    field: &MidiField,
+    args: &[OscArgValue],
+    captures: &[String],
+) -> Result<u8, String> {
+    match field {
+        MidiField::Fixed(n) => Ok(*n),
+        MidiField::Dynamic(ValueSource::Static { value }) => Ok((*value).min(127) as u8),
+        MidiField::Dynamic(ValueSource::OscArg { index }) => match args.get(*index) {
+            Some(OscArgValue::Int(i)) => Ok((*i).clamp(0, 127) as u8),
+            Some(OscArgValue::Int64(i)) => Ok((*i).clamp(0, 127) as u8),
+            Some(OscArgValue::Float(f)) => Ok(f.round().clamp(0.0, 127.0) as u8),
+            Some(OscArgValue::Double(d)) => Ok(d.round().clamp(0.0, 127.0) as u8),
+            Some(OscArgValue::String(s)) => match s.trim().parse::<u8>() {
+                Ok(n) => Ok(n.min(127)),
+                Err(_) => Err(format!("OSC arg {} (\"{}\") is not a number", index, s)),
+            },
+            Some(OscArgValue::Midi { data1, .. }) => Ok(*data1 & 0x7F),
+            Some(other) => Err(format!("OSC arg {} ({:?}) is not a number", index, other)),
+            None => Err(format!(
+                "OSC arg {} is missing (message has {} args)",
+                index,
+                args.len()
+            )),
+        },
+        MidiField::Dynamic(ValueSource::Capture { index }) => {
+            capture_to_value(captures, *index, 127).map(|n| n as u8)
+        }
+    }
+}
+
+fn capture_to_value(captures: &[String], index: usize, max: u16) -> Result<u16, String> {
+    match captures.get(index) {
+        Some(c) => match c.parse::<u32>() {
+            Ok(n) => Ok(n.min(max as u32) as u16),
+            Err(_) => Err(format!("Capture {} (\"{}\") is not a number", index, c)),
+        },
+        None => Err(format!(
+            "Capture {} is missing (pattern has {} wildcard segments)",
+            index,
+            captures.len()
+        )),
+    }
+}
+
+/// Substitute placeholders in an output address: `{0}`, `{1}`, … for the
+/// wildcard captures of an OSC input, and named `fields` such as `{note}`.
+fn fill_address(template: &str, captures: &[String], fields: &[(&str, u16)]) -> String {
+    if !template.contains('{') {
+        return template.to_string();
+    }
+    let mut out = template.to_string();
+    for (i, c) in captures.iter().enumerate() {
+        out = out.replace(&format!("{{{}}}", i), c);
+    }
+    for (name, value) in fields {
+        out = out.replace(&format!("{{{}}}", name), &value.to_string());
+    }
+    out
+}
+
+/// The `{channel}` and `{note}` fields of a MIDI→OSC output address.
+fn midi_fields(channel: u8, note_or_cc: u16) -> [(&'static str, u16); 2] {
+    [("channel", u16::from(channel)), ("note", note_or_cc)]
+}
+
+fn midi_value_to_osc_float(value: u16, max: u16) -> f32 {
+    value as f32 / max as f32
 }
 
 fn msc_command_to_byte(cmd: &MscCommand) -> u8 {
@@ -502,14 +1495,124 @@ fn msc_command_to_byte(cmd: &MscCommand) -> u8 {
         MscCommand::Go => 0x01,
         MscCommand::Stop => 0x02,
         MscCommand::Resume => 0x03,
+        MscCommand::TimedGo => 0x04,
+        MscCommand::Load => 0x05,
+        MscCommand::Set => 0x06,
+        MscCommand::Fire => 0x07,
+        MscCommand::AllOff => 0x08,
+        MscCommand::Restore => 0x09,
+        MscCommand::Reset => 0x0A,
+        MscCommand::GoOff => 0x0B,
+        MscCommand::GoJamClock => 0x10,
+        MscCommand::StandbyPlus => 0x11,
+        MscCommand::StandbyMinus => 0x12,
+        MscCommand::SequencePlus => 0x13,
+        MscCommand::SequenceMinus => 0x14,
+        MscCommand::StartClock => 0x15,
+        MscCommand::StopClock => 0x16,
+        MscCommand::ZeroClock => 0x17,
+        MscCommand::SetClock => 0x18,
+        MscCommand::MtcChaseOn => 0x19,
+        MscCommand::MtcChaseOff => 0x1A,
+        MscCommand::OpenCueList => 0x1B,
+        MscCommand::CloseCueList => 0x1C,
+        MscCommand::OpenCuePath => 0x1D,
+        MscCommand::CloseCuePath => 0x1E,
     }
 }
 
+/// Inverse of `msc_command_to_byte`.
+pub(crate) fn msc_command_from_byte(byte: u8) -> Option<MscCommand> {
+    Some(match byte {
+        0x01 => MscCommand::Go,
+        0x02 => MscCommand::Stop,
+        0x03 => MscCommand::Resume,
+        0x04 => MscCommand::TimedGo,
+        0x05 => MscCommand::Load,
+        0x06 => MscCommand::Set,
+        0x07 => MscCommand::Fire,
+        0x08 => MscCommand::AllOff,
+        0x09 => MscCommand::Restore,
+        0x0A => MscCommand::Reset,
+        0x0B => MscCommand::GoOff,
+        0x10 => MscCommand::GoJamClock,
+        0x11 => MscCommand::StandbyPlus,
+        0x12 => MscCommand::StandbyMinus,
+        0x13 => MscCommand::SequencePlus,
+        0x14 => MscCommand::SequenceMinus,
+        0x15 => MscCommand::StartClock,
+        0x16 => MscCommand::StopClock,
+        0x17 => MscCommand::ZeroClock,
+        0x18 => MscCommand::SetClock,
+        0x19 => MscCommand::MtcChaseOn,
+        0x1A => MscCommand::MtcChaseOff,
+        0x1B => MscCommand::OpenCueList,
+        0x1C => MscCommand::CloseCueList,
+        0x1D => MscCommand::OpenCuePath,
+        0x1E => MscCommand::CloseCuePath,
+        _ => return None,
+    })
+}
+
 fn msc_command_format_to_byte(fmt: &MscCommandFormat) -> u8 {
+    use MscCommandFormat::*;
     match fmt {
-        MscCommandFormat::All => 0x7F,
-        MscCommandFormat::Lighting => 0x01,
-        MscCommandFormat::Sound => 0x0A,
+        All => 0x7F,
+        Lighting => 0x01,
+        MovingLights => 0x02,
+        ColorChangers => 0x03,
+        Strobes => 0x04,
+        Lasers => 0x05,
+        Chasers => 0x06,
+        Sound => 0x10,
+        Music => 0x11,
+        CdPlayers => 0x12,
+        EpromPlayback => 0x13,
+        AudioTapeMachines => 0x14,
+        Intercoms => 0x15,
+        Amplifiers => 0x16,
+        AudioEffects => 0x17,
+        Equalizers => 0x18,
+        Machinery => 0x20,
+        Rigging => 0x21,
+        Flys => 0x22,
+        Lifts => 0x23,
+        Turntables => 0x24,
+        Trusses => 0x25,
+        Robots => 0x26,
+        Animation => 0x27,
+        Floats => 0x28,
+        Breakaways => 0x29,
+        Barges => 0x2A,
+        Video => 0x30,
+        VideoTapeMachines => 0x31,
+        VideoCassetteMachines => 0x32,
+        VideoDiscPlayers => 0x33,
+        VideoSwitchers => 0x34,
+        VideoEffects => 0x35,
+        VideoCharacterGenerators => 0x36,
+        VideoStillStores => 0x37,
+        VideoMonitors => 0x38,
+        Projection => 0x40,
+        FilmProjectors => 0x41,
+        SlideProjectors => 0x42,
+        VideoProjectors => 0x43,
+        Dissolvers => 0x44,
+        ShutterControls => 0x45,
+        ProcessControl => 0x50,
+        HydraulicOil => 0x51,
+        H2o => 0x52,
+        Co2 => 0x53,
+        CompressedAir => 0x54,
+        NaturalGas => 0x55,
+        Fog => 0x56,
+        Smoke => 0x57,
+        CrackedHaze => 0x58,
+        Pyro => 0x60,
+        Fireworks => 0x61,
+        Explosions => 0x62,
+        Flame => 0x63,
+        SmokePots => 0x64,
     }
 }
 
@@ -522,6 +1625,25 @@ fn msc_command_byte_to_str(cmd: u8) -> &'static str {
         0x05 => "LOAD",
         0x06 => "SET",
         0x07 => "FIRE",
+        0x08 => "ALL_OFF",
+        0x09 => "RESTORE",
+        0x0A => "RESET",
+        0x0B => "GO_OFF",
+        0x10 => "GO/JAM_CLOCK",
+        0x11 => "STANDBY_+",
+        0x12 => "STANDBY_-",
+        0x13 => "SEQUENCE_+",
+        0x14 => "SEQUENCE_-",
+        0x15 => "START_CLOCK",
+        0x16 => "STOP_CLOCK",
+        0x17 => "ZERO_CLOCK",
+        0x18 => "SET_CLOCK",
+        0x19 => "MTC_CHASE_ON",
+        0x1A => "MTC_CHASE_OFF",
+        0x1B => "OPEN_CUE_LIST",
+        0x1C => "CLOSE_CUE_LIST",
+        0x1D => "OPEN_CUE_PATH",
+        0x1E => "CLOSE_CUE_PATH",
         _ => "MSC",
     }
 }
@@ -538,16 +1660,41 @@ fn build_osc_arg_msc(
     cue_number: &str,
     cue_list: &Option<String>,
     cue_path: &Option<String>,
+    time: &Option<MscTime>,
+    set: Option<(u16, u16)>,
 ) -> OscArgValue {
     match &def.source {
+        OscArgSource::MscTime => match def.arg_type {
+            OscArgType::String => {
+                OscArgValue::String(time.as_ref().map(|t| t.to_string()).unwrap_or_default())
+            }
+            ref other => {
+                typed_arg_from_number(other, time.as_ref().map_or(0.0, |t| t.as_seconds()))
+            }
+        },
+        OscArgSource::MscSetControl => {
+            let control = set.map_or(0, |(control, _)| control);
+            match def.arg_type {
+                OscArgType::String => OscArgValue::String(control.to_string()),
+                ref other => typed_arg_from_number(other, control as f64),
+            }
+        }
+        OscArgSource::MscSetValue => {
+            let value = set.map_or(0, |(_, value)| value);
+            match def.arg_type {
+                OscArgType::Float => OscArgValue::Float(value as f32 / 16383.0),
+                OscArgType::Double => OscArgValue::Double(value as f64 / 16383.0),
+                OscArgType::String => OscArgValue::String(value.to_string()),
+                ref other => typed_arg_from_number(other, value as f64),
+            }
+        }
         OscArgSource::MscCueNumber { template } => match def.arg_type {
             OscArgType::String => {
                 OscArgValue::String(apply_template(template, "{cue_number}", cue_number))
             }
-            OscArgType::Float => {
-                OscArgValue::Float(cue_number.parse::<f32>().unwrap_or(0.0))
-            }
+            OscArgType::Float => OscArgValue::Float(cue_number.parse::<f32>().unwrap_or(0.0)),
             OscArgType::Int => OscArgValue::Int(cue_number.parse::<i32>().unwrap_or(0)),
+            ref other => typed_arg_from_str(other, cue_number),
         },
         OscArgSource::MscCueList { template } => {
             let val = cue_list.as_deref().unwrap_or("");
@@ -557,6 +1704,7 @@ fn build_osc_arg_msc(
                 }
                 OscArgType::Float => OscArgValue::Float(val.parse::<f32>().unwrap_or(0.0)),
                 OscArgType::Int => OscArgValue::Int(val.parse::<i32>().unwrap_or(0)),
+                ref other => typed_arg_from_str(other, val),
             }
         }
         OscArgSource::MscCuePath { template } => {
@@ -567,30 +1715,56 @@ fn build_osc_arg_msc(
                 }
                 OscArgType::Float => OscArgValue::Float(val.parse::<f32>().unwrap_or(0.0)),
                 OscArgType::Int => OscArgValue::Int(val.parse::<i32>().unwrap_or(0)),
+                ref other => typed_arg_from_str(other, val),
             }
         }
         OscArgSource::Static { value } => match def.arg_type {
             OscArgType::Float => OscArgValue::Float(value.as_f64().unwrap_or(0.0) as f32),
             OscArgType::Int => OscArgValue::Int(value.as_i64().unwrap_or(0) as i32),
-            OscArgType::String => {
-                OscArgValue::String(value.as_str().unwrap_or("").to_string())
-            }
+            OscArgType::String => OscArgValue::String(value.as_str().unwrap_or("").to_string()),
+            ref other => typed_arg_from_json(other, value),
         },
         OscArgSource::MidiValue | OscArgSource::MidiNote => OscArgValue::Int(0),
+        OscArgSource::Bpm | OscArgSource::Beat | OscArgSource::Bar => OscArgValue::Int(0),
+        OscArgSource::Transport => OscArgValue::String(String::new()),
     }
 }
 
-fn build_osc_arg(def: &OscArgDef, midi_value: u8, midi_note: u8) -> OscArgValue {
+/// Build an OSC argument from a tempo, beat or transport input.
+fn build_osc_arg_clock(def: &OscArgDef, msg: &IncomingMessage) -> OscArgValue {
+    match (&def.source, msg) {
+        (OscArgSource::Bpm, IncomingMessage::Tempo { bpm, .. }) => {
+            typed_arg_from_number(&def.arg_type, *bpm)
+        }
+        (OscArgSource::Beat, IncomingMessage::Beat { beat, .. }) => {
+            typed_arg_from_number(&def.arg_type, f64::from(*beat))
+        }
+        (OscArgSource::Bar, IncomingMessage::Beat { bar, .. }) => {
+            typed_arg_from_number(&def.arg_type, f64::from(*bar))
+        }
+        (OscArgSource::Transport, IncomingMessage::Transport { state, .. }) => {
+            typed_arg_from_str(&def.arg_type, state.as_str())
+        }
+        _ => build_osc_arg(def, 0, 127, 0),
+    }
+}
+
+/// `value_max` is the full-scale MIDI value (127, or 16383 for pitch bend)
+/// used to normalize float args to 0.0–1.0.
+fn build_osc_arg(def: &OscArgDef, midi_value: u16, value_max: u16, midi_note: u16) -> OscArgValue {
     match &def.source {
         OscArgSource::MidiValue => match def.arg_type {
-            OscArgType::Float => OscArgValue::Float(midi_value_to_osc_float(midi_value)),
+            OscArgType::Float => OscArgValue::Float(midi_value_to_osc_float(midi_value, value_max)),
+            OscArgType::Double => OscArgValue::Double(midi_value as f64 / value_max as f64),
             OscArgType::Int => OscArgValue::Int(midi_value as i32),
             OscArgType::String => OscArgValue::String(midi_value.to_string()),
+            ref other => typed_arg_from_number(other, midi_value as f64),
         },
         OscArgSource::MidiNote => match def.arg_type {
             OscArgType::Int => OscArgValue::Int(midi_note as i32),
             OscArgType::Float => OscArgValue::Float(midi_note as f32),
             OscArgType::String => OscArgValue::String(midi_note.to_string()),
+            ref other => typed_arg_from_number(other, midi_note as f64),
         },
         OscArgSource::Static { value } => match def.arg_type {
             OscArgType::Float => {
@@ -602,10 +1776,149 @@ fn build_osc_arg(def: &OscArgDef, midi_value: u8, midi_note: u8) -> OscArgValue
             OscArgType::String => {
                 OscArgValue::String(value.as_str().unwrap_or("").to_string())
             }
+            ref other => typed_arg_from_json(other, value),
         },
         OscArgSource::MscCueNumber { .. }
         | OscArgSource::MscCueList { .. }
         | OscArgSource::MscCuePath { .. } => OscArgValue::String(String::new()),
+        OscArgSource::MscTime
+        | OscArgSource::MscSetControl
+        | OscArgSource::MscSetValue
+        | OscArgSource::Bpm
+        | OscArgSource::Beat
+        | OscArgSource::Bar => OscArgValue::Int(0),
+        OscArgSource::Transport => OscArgValue::String(String::new()),
+    }
+}
+
+/// Build an argument of any type from a number. Types that cannot carry a
+/// number (nil, impulse, timetag, color, MIDI) get their empty value.
+fn typed_arg_from_number(arg_type: &OscArgType, n: f64) -> OscArgValue {
+    match arg_type {
+        OscArgType::Int => OscArgValue::Int(n as i32),
+        OscArgType::Float => OscArgValue::Float(n as f32),
+        OscArgType::String => OscArgValue::String(n.to_string()),
+        OscArgType::Double => OscArgValue::Double(n),
+        OscArgType::Int64 => OscArgValue::Int64(n as i64),
+        OscArgType::Bool => OscArgValue::Bool(n != 0.0),
+        OscArgType::Char => OscArgValue::Char(char::from_u32(n as u32).unwrap_or('\0')),
+        OscArgType::Blob => OscArgValue::Blob(vec![n.clamp(0.0, 255.0) as u8]),
+        OscArgType::Nil => OscArgValue::Nil,
+        OscArgType::Impulse => OscArgValue::Impulse,
+        OscArgType::Timetag => OscArgValue::TimeTag {
+            seconds: 0,
+            fractional: 1,
+        },
+        OscArgType::Color => OscArgValue::Color {
+            red: 0,
+            green: 0,
+            blue: 0,
+            alpha: 0,
+        },
+        OscArgType::Midi => OscArgValue::Midi {
+            port: 0,
+            status: 0,
+            data1: 0,
+            data2: 0,
+        },
+    }
+}
+
+/// Build an argument of any type from text, e.g. an MSC cue field.
+/// Colors accept `RRGGBBAA` hex with an optional leading `#`.
+fn typed_arg_from_str(arg_type: &OscArgType, s: &str) -> OscArgValue {
+    match arg_type {
+        OscArgType::Int => OscArgValue::Int(s.parse::<i32>().unwrap_or(0)),
+        OscArgType::Float => OscArgValue::Float(s.parse::<f32>().unwrap_or(0.0)),
+        OscArgType::String => OscArgValue::String(s.to_string()),
+        OscArgType::Double => OscArgValue::Double(s.parse::<f64>().unwrap_or(0.0)),
+        OscArgType::Int64 => OscArgValue::Int64(s.parse::<i64>().unwrap_or(0)),
+        OscArgType::Bool => {
+            OscArgValue::Bool(!matches!(s.trim(), "" | "0" | "false" | "F"))
+        }
+        OscArgType::Char => OscArgValue::Char(s.chars().next().unwrap_or('\0')),
+        OscArgType::Blob => OscArgValue::Blob(s.as_bytes().to_vec()),
+        OscArgType::Color => {
+            let rgba = u32::from_str_radix(s.trim().trim_start_matches('#'), 16).unwrap_or(0);
+            let [red, green, blue, alpha] = rgba.to_be_bytes();
+            OscArgValue::Color {
+                red,
+                green,
+                blue,
+                alpha,
+            }
+        }
+        other => typed_arg_from_number(other, 0.0),
+    }
+}
+
+/// Build an argument of any type from a static JSON value. Blobs, colors,
+/// MIDI messages and timetags may be given as arrays of numbers
+/// (`[r, g, b, a]`, `[port, status, data1, data2]`, `[seconds, fractional]`).
+fn typed_arg_from_json(arg_type: &OscArgType, value: &serde_json::Value) -> OscArgValue {
+    match value {
+        serde_json::Value::Bool(b) => typed_arg_from_number(arg_type, if *b { 1.0 } else { 0.0 }),
+        serde_json::Value::String(s) => typed_arg_from_str(arg_type, s),
+        serde_json::Value::Array(items) => {
+            let nums: Vec<u64> = items.iter().map(|v| v.as_u64().unwrap_or(0)).collect();
+            let byte = |i: usize| nums.get(i).copied().unwrap_or(0).min(255) as u8;
+            match arg_type {
+                OscArgType::Blob => OscArgValue::Blob((0..nums.len()).map(byte).collect()),
+                OscArgType::Color => OscArgValue::Color {
+                    red: byte(0),
+                    green: byte(1),
+                    blue: byte(2),
+                    alpha: byte(3),
+                },
+                OscArgType::Midi => OscArgValue::Midi {
+                    port: byte(0),
+                    status: byte(1),
+                    data1: byte(2),
+                    data2: byte(3),
+                },
+                OscArgType::Timetag => OscArgValue::TimeTag {
+                    seconds: nums.first().copied().unwrap_or(0) as u32,
+                    fractional: nums.get(1).copied().unwrap_or(0) as u32,
+                },
+                other => typed_arg_from_number(other, nums.first().copied().unwrap_or(0) as f64),
+            }
+        }
+        other => typed_arg_from_number(arg_type, other.as_f64().unwrap_or(0.0)),
+    }
+}
+
+fn format_osc_arg(arg: &OscArgValue) -> String {
+    match arg {
+        OscArgValue::Int(i) => i.to_string(),
+        OscArgValue::Float(f) => format!("{:.3}", f),
+        OscArgValue::String(s) => format!("\"{}\"", s),
+        OscArgValue::Blob(b) => format!("<blob {} bytes>", b.len()),
+        OscArgValue::Bool(b) => b.to_string(),
+        OscArgValue::Nil => "nil".to_string(),
+        OscArgValue::Impulse => "impulse".to_string(),
+        OscArgValue::Double(d) => format!("{:.3}", d),
+        OscArgValue::Int64(i) => format!("{}h", i),
+        OscArgValue::TimeTag {
+            seconds,
+            fractional,
+        } => format!("<time {}.{:08X}>", seconds, fractional),
+        OscArgValue::Char(c) => format!("'{}'", c),
+        OscArgValue::Color {
+            red,
+            green,
+            blue,
+            alpha,
+        } => format!("#{:02X}{:02X}{:02X}{:02X}", red, green, blue, alpha),
+        OscArgValue::Midi {
+            port,
+            status,
+            data1,
+            data2,
+        } => format!("<midi {:02X} {:02X} {:02X} {:02X}>", port, status, data1, data2),
+        OscArgValue::Array(items) => format!(
+            "[{}]",
+            items.iter().map(format_osc_arg).collect::<Vec<_>>().join(" ")
+        ),
     }
 }
 
@@ -614,11 +1927,7 @@ fn format_incoming(msg: &IncomingMessage) -> (String, String) {
         IncomingMessage::Osc { address, args } => {
             let args_str = args
                 .iter()
-                .map(|a| match a {
-                    OscArgValue::Int(i) => i.to_string(),
-                    OscArgValue::Float(f) => format!("{:.3}", f),
-                    OscArgValue::String(s) => format!("\"{}\"", s),
-                })
+                .map(format_osc_arg)
                 .collect::<Vec<_>>()
                 .join(" ");
             ("osc".to_string(), format!("{} ({})", address, args_str))
@@ -628,38 +1937,81 @@ fn format_incoming(msg: &IncomingMessage) -> (String, String) {
             channel,
             note_or_cc,
             value,
-        } => {
-            let type_str = match message_type {
-                MidiMessageType::NoteOn => "Note On",
-                MidiMessageType::NoteOff => "Note Off",
-                MidiMessageType::Cc => "CC",
-                MidiMessageType::ProgramChange => "PC",
-                MidiMessageType::Msc => "MSC",
-            };
-            if matches!(message_type, MidiMessageType::ProgramChange) {
-                ("midi".to_string(), format!("PC {} Ch {}", note_or_cc, channel))
-            } else {
-                (
-                    "midi".to_string(),
-                    format!("{} {} Val {} Ch {}", type_str, note_or_cc, value, channel),
-                )
-            }
-        }
+            ..
+        } => (
+            "midi".to_string(),
+            format_midi(message_type, *channel, *note_or_cc, *value),
+        ),
+        IncomingMessage::Cc14 {
+            mode,
+            channel,
+            parameter,
+            value,
+            ..
+        } => ("midi".to_string(), format_cc14(mode, *channel, *parameter, *value)),
         IncomingMessage::Msc {
             command,
             cue_number,
             ..
-        } => {
-            let cmd_str = msc_command_byte_to_str(*command);
-            if cue_number.is_empty() {
-                ("midi".to_string(), format!("MSC {}", cmd_str))
-            } else {
-                ("midi".to_string(), format!("MSC {} Q{}", cmd_str, cue_number))
-            }
+        } => ("midi".to_string(), format_msc(*command, cue_number)),
+        IncomingMessage::Mtc { time, running, .. } => (
+            "midi".to_string(),
+            format!("MTC {}{}", time, if *running { "" } else { " (locate)" }),
+        ),
+        IncomingMessage::Tempo { bpm, .. } => ("midi".to_string(), format!("Tempo {:.1} BPM", bpm)),
+        IncomingMessage::Beat { beat, bar, .. } => {
+            ("midi".to_string(), format!("Beat {}.{}", bar, beat))
+        }
+        IncomingMessage::Transport { state, .. } => {
+            ("midi".to_string(), format!("Transport {}", state.as_str()))
         }
     }
 }
 
+fn format_midi(message_type: &MidiMessageType, channel: u8, note_or_cc: u8, value: u16) -> String {
+    match message_type {
+        MidiMessageType::ProgramChange => format!("PC {} Ch {}", note_or_cc, channel),
+        MidiMessageType::PitchBend => format!("Pitch Bend {} Ch {}", value, channel),
+        MidiMessageType::ChannelPressure => format!("Pressure {} Ch {}", value, channel),
+        MidiMessageType::PolyAftertouch => {
+            format!("Poly AT {} Val {} Ch {}", note_or_cc, value, channel)
+        }
+        MidiMessageType::NoteOn => format!("Note On {} Val {} Ch {}", note_or_cc, value, channel),
+        MidiMessageType::NoteOff => format!("Note Off {} Val {} Ch {}", note_or_cc, value, channel),
+        MidiMessageType::Cc => format!("CC {} Val {} Ch {}", note_or_cc, value, channel),
+        MidiMessageType::Msc => format!("MSC {} Val {} Ch {}", note_or_cc, value, channel),
+    }
+}
+
+/// The clock input a message belongs to, if it is a clock message.
+fn clock_input_of(msg: &IncomingMessage) -> Option<ClockInput> {
+    match msg {
+        IncomingMessage::Tempo { .. } => Some(ClockInput::Tempo),
+        IncomingMessage::Beat { .. } => Some(ClockInput::Beat),
+        IncomingMessage::Transport { .. } => Some(ClockInput::Transport),
+        _ => None,
+    }
+}
+
+fn format_cc14(mode: &CcMode, channel: u8, parameter: u16, value: u16) -> String {
+    let kind = match mode {
+        CcMode::SevenBit => "CC",
+        CcMode::FourteenBit => "CC14",
+        CcMode::Nrpn => "NRPN",
+        CcMode::Rpn => "RPN",
+    };
+    format!("{} {} Val {} Ch {}", kind, parameter, value, channel)
+}
+
+fn format_msc(command: u8, cue_number: &str) -> String {
+    let cmd_str = msc_command_byte_to_str(command);
+    if cue_number.is_empty() {
+        format!("MSC {}", cmd_str)
+    } else {
+        format!("MSC {} Q{}", cmd_str, cue_number)
+    }
+}
+
 fn format_output(action: &OutputAction) -> (String, String) {
     match action {
         OutputAction::Midi {
@@ -667,107 +2019,342 @@ fn format_output(action: &OutputAction) -> (String, String) {
             channel,
             note_or_cc,
             value,
-        } => {
-            let type_str = match message_type {
-                MidiMessageType::NoteOn => "Note On",
-                MidiMessageType::NoteOff => "Note Off",
-                MidiMessageType::Cc => "CC",
-                MidiMessageType::ProgramChange => "PC",
-                MidiMessageType::Msc => "MSC",
-            };
-            if matches!(message_type, MidiMessageType::ProgramChange) {
-                ("midi".to_string(), format!("PC {} Ch {}", note_or_cc, channel))
-            } else {
-                (
-                    "midi".to_string(),
-                    format!("{} {} Val {} Ch {}", type_str, note_or_cc, value, channel),
-                )
-            }
-        }
-        OutputAction::Osc { address, args } => {
+            ..
+        } => (
+            "midi".to_string(),
+            format_midi(message_type, *channel, *note_or_cc, *value),
+        ),
+        OutputAction::Cc14 {
+            mode,
+            channel,
+            parameter,
+            value,
+            ..
+        } => ("midi".to_string(), format_cc14(mode, *channel, *parameter, *value)),
+        OutputAction::Osc { address, args, .. } => {
             let args_str = args
                 .iter()
-                .map(|a| match a {
-                    OscArgValue::Int(i) => i.to_string(),
-                    OscArgValue::Float(f) => format!("{:.3}", f),
-                    OscArgValue::String(s) => format!("\"{}\"", s),
-                })
+                .map(format_osc_arg)
                 .collect::<Vec<_>>()
                 .join(" ");
             ("osc".to_string(), format!("{} {}", address, args_str))
         }
+        OutputAction::Msc { msc, .. } => {
+            ("midi".to_string(), format_msc(msc.command, &msc.cue_number))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_osc_to_midi_mapping(address: &str, value_source: ValueSource) -> Mapping {
+        Mapping {
+            id: "test-1".to_string(),
+            enabled: true,
+            direction: Direction::OscToMidi,
+            osc_address: address.to_string(),
+            osc_arg_types: vec![],
+            osc_arg_match: OscArgMatch::Any,
+            midi_message_type: MidiMessageType::NoteOn,
+            midi_channel: MidiField::Fixed(1),
+            midi_note_or_cc: MidiField::Fixed(60),
+            midi_velocity_or_value: value_source,
+            midi_input_velocity: None,
+            midi_input_port: None,
+            midi_output_port: None,
+            osc_args: vec![],
+            osc_output_address: String::new(),
+            osc_transform: None,
+            osc_destinations: vec![],
+            msc_device_id: None,
+            msc_command_format: None,
+            msc_command: None,
+            msc_cue_number: None,
+            msc_cue_list: None,
+            msc_cue_path: None,
+            cc_mode: CcMode::SevenBit,
+            midi_parameter: 0,
+            clock_input: None,
+            transport_state: None,
+            midi_remap: MidiRemap::default(),
+            conditions: vec![],
+            mode: MappingMode::Momentary,
+            state_group: None,
+            persist_state: false,
+            slew: None,
+            output_filter: OutputFilter::default(),
+            steps: vec![],
+            retrigger: Retrigger::default(),
+        }
+    }
+
+    fn make_midi_to_osc_mapping() -> Mapping {
+        Mapping {
+            id: "test-2".to_string(),
+            enabled: true,
+            direction: Direction::MidiToOsc,
+            osc_address: "/output".to_string(),
+            osc_arg_types: vec![OscArgType::Float],
+            osc_arg_match: OscArgMatch::Any,
+            midi_message_type: MidiMessageType::Cc,
+            midi_channel: MidiField::Fixed(1),
+            midi_note_or_cc: MidiField::Fixed(7),
+            midi_velocity_or_value: ValueSource::Static { value: 0 },
+            midi_input_velocity: None,
+            midi_input_port: None,
+            midi_output_port: None,
+            osc_args: vec![OscArgDef {
+                arg_type: OscArgType::Float,
+                source: OscArgSource::MidiValue,
+            }],
+            osc_output_address: String::new(),
+            osc_transform: None,
+            osc_destinations: vec![],
+            msc_device_id: None,
+            msc_command_format: None,
+            msc_command: None,
+            msc_cue_number: None,
+            msc_cue_list: None,
+            msc_cue_path: None,
+            cc_mode: CcMode::SevenBit,
+            midi_parameter: 0,
+            clock_input: None,
+            transport_state: None,
+            midi_remap: MidiRemap::default(),
+            conditions: vec![],
+            mode: MappingMode::Momentary,
+            state_group: None,
+            persist_state: false,
+            slew: None,
+            output_filter: OutputFilter::default(),
+            steps: vec![],
+            retrigger: Retrigger::default(),
+        }
+    }
+
+    fn test_router(mappings: Vec<Mapping>) -> Router<tauri::test::MockRuntime> {
+        Router::new(
+            Arc::new(Mutex::new(mappings)),
+            Arc::default(),
+            Arc::default(),
+            Arc::default(),
+            tauri::test::mock_app().handle().clone(),
+        )
+    }
+
+    #[test]
+    fn test_route_pitch_bend_ignores_note() {
+        let mut mapping = make_midi_to_osc_mapping();
+        mapping.midi_message_type = MidiMessageType::PitchBend;
+        mapping.midi_note_or_cc = MidiField::Fixed(60);
+        let router = test_router(vec![mapping]);
+        let routed = router.route(&IncomingMessage::Midi {
+            port: "Keys".to_string(),
+            message_type: MidiMessageType::PitchBend,
+            channel: 1,
+            note_or_cc: 0,
+            value: 16383,
+        });
+        assert_eq!(routed.len(), 1);
+        match &routed[0].action {
+            OutputAction::Osc { address, args, .. } => {
+                assert_eq!(address, "/output");
+                assert_eq!(args, &vec![OscArgValue::Float(1.0)]);
+            }
+            other => panic!("expected OSC output, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_osc_to_midi_value_scaling() {
+        assert_eq!(osc_arg_to_midi_value(&OscArgValue::Float(0.0), 127), 0);
+        assert_eq!(osc_arg_to_midi_value(&OscArgValue::Float(1.0), 127), 127);
+        assert_eq!(osc_arg_to_midi_value(&OscArgValue::Float(0.5), 127), 64);
+    }
+
+    #[test]
+    fn test_midi_to_osc_value_scaling() {
+        assert_eq!(midi_value_to_osc_float(0, 127), 0.0);
+        assert_eq!(midi_value_to_osc_float(127, 127), 1.0);
+        let half = midi_value_to_osc_float(64, 127);
+        assert!((half - 0.504).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_osc_arg_to_midi_static() {
+        assert_eq!(osc_arg_to_midi_value(&OscArgValue::Int(100), 127), 100);
+        assert_eq!(osc_arg_to_midi_value(&OscArgValue::Int(200), 127), 127);
+        assert_eq!(osc_arg_to_midi_value(&OscArgValue::String("x".to_string()), 127), 0);
+    }
+
+    #[test]
+    fn test_resolve_midi_field_from_capture() {
+        let mut mapping =
+            make_osc_to_midi_mapping("/ch/*/fader", ValueSource::OscArg { index: 0 });
+        mapping.midi_message_type = MidiMessageType::Cc;
+        mapping.midi_note_or_cc = MidiField::Dynamic(ValueSource::Capture { index: 0 });
+        let captures = vec!["12".to_string()];
+        let field = mapping.midi_note_or_cc;
+        assert_eq!(resolve_midi_field(&field, &[], &captures), Ok(12));
+        assert_eq!(resolve_midi_field(&MidiField::Fixed(5), &[], &captures), Ok(5));
+        // Missing or non-numeric captures are rejected
+        let missing = MidiField::Dynamic(ValueSource::Capture { index: 1 });
+        assert!(resolve_midi_field(&missing, &[], &captures).is_err());
+        let words = vec!["bus".to_string()];
+        assert_eq!(
+            resolve_midi_field(&field, &[], &words),
+            Err("Capture 0 (\"bus\") is not a number".to_string())
+        );
+    }
+
+    #[test]
+    fn test_route_tripped_mapping_keeps_state() {
+        let mut mapping = make_osc_to_midi_mapping("/go", ValueSource::Static { value: 127 });
+        mapping.mode = MappingMode::Toggle;
+        let router = test_router(vec![mapping]);
+        let press = IncomingMessage::Osc {
+            address: "/go".to_string(),
+            args: vec![OscArgValue::Int(1)],
+        };
+        router.loop_guard.trip("test-1", "loop".to_string());
+        assert!(router.route(&press).is_empty());
+        assert!(router.states.snapshot().is_empty());
+
+        router.loop_guard.reset(None).unwrap();
+        assert_eq!(router.route(&press).len(), 1);
+        assert_eq!(router.states.snapshot().get("test-1"), Some(&true));
+    }
+
+    #[test]
+    fn test_route_rejects_non_numeric_capture() {
+        let mut mapping =
+            make_osc_to_midi_mapping("/ch/*/fader", ValueSource::OscArg { index: 0 });
+        mapping.midi_message_type = MidiMessageType::Cc;
+        mapping.midi_channel = MidiField::Dynamic(ValueSource::Capture { index: 0 });
+        let router = test_router(vec![mapping]);
+        let fader = |channel: &str| IncomingMessage::Osc {
+            address: format!("/ch/{}/fader", channel),
+            args: vec![OscArgValue::Float(0.5)],
+        };
+        assert!(router.route(&fader("abc")).is_empty());
+        let routed = router.route(&fader("3"));
+        assert_eq!(routed.len(), 1);
+        assert!(matches!(routed[0].action, OutputAction::Midi { channel: 3, .. }));
+    }
+
+    #[test]
+    fn test_edited_address_pattern_is_forgotten() {
+        let mapping = make_osc_to_midi_mapping("/old/*", ValueSource::OscArg { index: 0 });
+        let router = test_router(vec![mapping]);
+        let fader = |address: &str| IncomingMessage::Osc {
+            address: address.to_string(),
+            args: vec![OscArgValue::Float(0.5)],
+        };
+        assert_eq!(router.route(&fader("/old/1")).len(), 1);
+
+        router.mappings.lock().unwrap()[0].osc_address = "/new/*".to_string();
+        assert_eq!(router.route(&fader("/new/1")).len(), 1);
+        let patterns = router.patterns.lock().unwrap();
+        assert!(patterns.compiled.contains_key("/new/*"));
+        assert!(!patterns.compiled.contains_key("/old/*"));
+    }
+
+    #[test]
+    fn test_route_rejects_missing_value_arg() {
+        let mut mapping =
+            make_osc_to_midi_mapping("/ch/*/fader", ValueSource::OscArg { index: 0 });
+        mapping.midi_message_type = MidiMessageType::Cc;
+        let router = test_router(vec![mapping]);
+        let fader = |args: Vec<OscArgValue>| IncomingMessage::Osc {
+            address: "/ch/1/fader".to_string(),
+            args,
+        };
+        assert!(router.route(&fader(vec![])).is_empty());
+        assert_eq!(router.route(&fader(vec![OscArgValue::Float(1.0)])).len(), 1);
+    }
+
+    #[test]
+    fn test_resolve_midi_field_from_osc_arg_is_unscaled() {
+        let field = MidiField::Dynamic(ValueSource::OscArg { index: 0 });
+        assert_eq!(resolve_midi_field(&field, &[OscArgValue::Float(12.0)], &[]), Ok(12));
+        assert_eq!(resolve_midi_field(&field, &[OscArgValue::Int(300)], &[]), Ok(127));
+        assert!(resolve_midi_field(&field, &[OscArgValue::Nil], &[]).is_err());
+        assert!(resolve_midi_field(&field, &[], &[]).is_err());
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[test]
+    fn test_check_arg_types_any_ignores_types() {
+        let mut mapping = make_osc_to_midi_mapping("/cue/go", ValueSource::Static { value: 127 });
+        mapping.osc_arg_types = vec![OscArgType::Float];
+        assert!(check_arg_types(&mapping, &[OscArgValue::String("x".into())]).is_ok());
+        assert!(check_arg_types(&mapping, &[]).is_ok());
+    }
 
-    fn make_osc_to_midi_mapping(address: &str, value_source: ValueSource) -> Mapping {
-        Mapping {
-            id: "test-1".to_string(),
-            enabled: true,
-            direction: Direction::OscToMidi,
-            osc_address: address.to_string(),
-            osc_arg_types: vec![],
-            midi_message_type: MidiMessageType::NoteOn,
-            midi_channel: 1,
-            midi_note_or_cc: 60,
-            midi_velocity_or_value: value_source,
-            midi_input_velocity: None,
-            osc_args: vec![],
-            osc_output_address: String::new(),
-            osc_transform: None,
-            msc_device_id: None,
-            msc_command_format: None,
-            msc_command: None,
+    #[test]
+    fn test_check_arg_types_exact() {
+        let mut mapping = make_osc_to_midi_mapping("/cue/go", ValueSource::Static { value: 127 });
+        mapping.osc_arg_types = vec![OscArgType::Float];
+        mapping.osc_arg_match = OscArgMatch::Exact;
+        assert!(check_arg_types(&mapping, &[OscArgValue::Float(1.0)]).is_ok());
+        match check_arg_types(&mapping, &[OscArgValue::String("x".into())]) {
+            Err(NoMatch::Rejected(reason)) => {
+                assert_eq!(reason, "type mismatch: expected ,f got ,s")
+            }
+            other => panic!("Expected type mismatch, got {:?}", other),
         }
+        // Count mismatch is also a type mismatch
+        let two = [OscArgValue::Float(1.0), OscArgValue::Float(2.0)];
+        assert!(matches!(check_arg_types(&mapping, &two), Err(NoMatch::Rejected(_))));
+        assert!(matches!(check_arg_types(&mapping, &[]), Err(NoMatch::Rejected(_))));
     }
 
-    fn make_midi_to_osc_mapping() -> Mapping {
-        Mapping {
-            id: "test-2".to_string(),
-            enabled: true,
-            direction: Direction::MidiToOsc,
-            osc_address: "/output".to_string(),
-            osc_arg_types: vec![OscArgType::Float],
-            midi_message_type: MidiMessageType::Cc,
-            midi_channel: 1,
-            midi_note_or_cc: 7,
-            midi_velocity_or_value: ValueSource::Static { value: 0 },
-            midi_input_velocity: None,
-            osc_args: vec![OscArgDef {
-                arg_type: OscArgType::Float,
-                source: OscArgSource::MidiValue,
-            }],
-            osc_output_address: String::new(),
-            osc_transform: None,
-            msc_device_id: None,
-            msc_command_format: None,
-            msc_command: None,
-        }
+    #[test]
+    fn test_check_arg_types_exact_empty_means_no_args() {
+        let mut mapping = make_osc_to_midi_mapping("/cue/go", ValueSource::Static { value: 127 });
+        mapping.osc_arg_match = OscArgMatch::Exact;
+        assert!(check_arg_types(&mapping, &[]).is_ok());
+        assert!(check_arg_types(&mapping, &[OscArgValue::Int(1)]).is_err());
     }
 
     #[test]
-    fn test_osc_to_midi_value_scaling() {
-        assert_eq!(osc_arg_to_midi_value(&OscArgValue::Float(0.0)), 0);
-        assert_eq!(osc_arg_to_midi_value(&OscArgValue::Float(1.0)), 127);
-        assert_eq!(osc_arg_to_midi_value(&OscArgValue::Float(0.5)), 64);
+    fn test_check_arg_types_prefix_allows_extra_args() {
+        let mut mapping = make_osc_to_midi_mapping("/cue/go", ValueSource::Static { value: 127 });
+        mapping.osc_arg_types = vec![OscArgType::String];
+        mapping.osc_arg_match = OscArgMatch::Prefix;
+        let args = [OscArgValue::String("1".into()), OscArgValue::Float(0.5)];
+        assert!(check_arg_types(&mapping, &args).is_ok());
+        assert!(check_arg_types(&mapping, &[OscArgValue::Float(0.5)]).is_err());
+        assert!(check_arg_types(&mapping, &[]).is_err());
     }
 
     #[test]
-    fn test_midi_to_osc_value_scaling() {
-        assert_eq!(midi_value_to_osc_float(0), 0.0);
-        assert_eq!(midi_value_to_osc_float(127), 1.0);
-        let half = midi_value_to_osc_float(64);
-        assert!((half - 0.504).abs() < 0.01);
+    fn test_check_arg_types_bool_covers_true_and_false() {
+        let mut mapping = make_osc_to_midi_mapping("/mute", ValueSource::Static { value: 127 });
+        mapping.osc_arg_types = vec![OscArgType::Bool];
+        mapping.osc_arg_match = OscArgMatch::Exact;
+        assert!(check_arg_types(&mapping, &[OscArgValue::Bool(true)]).is_ok());
+        assert!(check_arg_types(&mapping, &[OscArgValue::Bool(false)]).is_ok());
     }
 
     #[test]
-    fn test_osc_arg_to_midi_static() {
-        assert_eq!(osc_arg_to_midi_value(&OscArgValue::Int(100)), 100);
-        assert_eq!(osc_arg_to_midi_value(&OscArgValue::Int(200)), 127);
-        assert_eq!(osc_arg_to_midi_value(&OscArgValue::String("x".to_string())), 0);
+    fn test_fill_address_captures() {
+        let captures = vec!["3".to_string(), "aux".to_string()];
+        assert_eq!(
+            fill_address("/mix/{1}/{0}/level", &captures, &[]),
+            "/mix/aux/3/level"
+        );
+        assert_eq!(fill_address("/fixed", &captures, &[]), "/fixed");
+    }
+
+    #[test]
+    fn test_fill_address_midi_fields() {
+        let fill =
+            |template, channel, note| fill_address(template, &[], &midi_fields(channel, note));
+        assert_eq!(fill("/ch/{note}/fader", 1, 12), "/ch/12/fader");
+        assert_eq!(fill("/midi/{channel}/{note}", 10, 60), "/midi/10/60");
+        assert_eq!(fill("/output", 1, 7), "/output");
     }
 
     #[test]
@@ -776,7 +2363,7 @@ mod tests {
             arg_type: OscArgType::Float,
             source: OscArgSource::MidiValue,
         };
-        match build_osc_arg(&def, 127, 60) {
+        match build_osc_arg(&def, 127, 127, 60) {
             OscArgValue::Float(f) => assert_eq!(f, 1.0),
             _ => panic!("Expected float"),
         }
@@ -788,7 +2375,7 @@ mod tests {
             arg_type: OscArgType::Int,
             source: OscArgSource::MidiNote,
         };
-        match build_osc_arg(&def, 127, 60) {
+        match build_osc_arg(&def, 127, 127, 60) {
             OscArgValue::Int(i) => assert_eq!(i, 60),
             _ => panic!("Expected int"),
         }
@@ -802,7 +2389,7 @@ mod tests {
                 value: serde_json::Value::String("hello".to_string()),
             },
         };
-        match build_osc_arg(&def, 0, 0) {
+        match build_osc_arg(&def, 0, 127, 0) {
             OscArgValue::String(s) => assert_eq!(s, "hello"),
             _ => panic!("Expected string"),
         }
@@ -812,11 +2399,12 @@ mod tests {
     fn test_midi_input_velocity_filter_exact_match() {
         let mut mapping = make_midi_to_osc_mapping();
         mapping.midi_message_type = MidiMessageType::NoteOn;
-        mapping.midi_note_or_cc = 60;
+        mapping.midi_note_or_cc = MidiField::Fixed(60);
         mapping.midi_input_velocity = Some(100);
 
         // Matching velocity — should produce an output
         let msg_match = IncomingMessage::Midi {
+            port: "Keys".to_string(),
             message_type: MidiMessageType::NoteOn,
             channel: 1,
             note_or_cc: 60,
@@ -825,6 +2413,7 @@ mod tests {
 
         // Non-matching velocity — should be filtered out
         let msg_no_match = IncomingMessage::Midi {
+            port: "Keys".to_string(),
             message_type: MidiMessageType::NoteOn,
             channel: 1,
             note_or_cc: 60,
@@ -841,15 +2430,16 @@ mod tests {
                     channel,
                     note_or_cc,
                     value,
+                    ..
                 } => {
                     if message_type != &mapping.midi_message_type
-                        || channel != &mapping.midi_channel
-                        || note_or_cc != &mapping.midi_note_or_cc
+                        || !mapping.midi_channel.accepts(*channel)
+                        || !mapping.midi_note_or_cc.accepts(*note_or_cc)
                     {
                         return false;
                     }
                     if let Some(vel) = mapping.midi_input_velocity {
-                        if value != &vel {
+                        if *value != vel {
                             return false;
                         }
                     }
@@ -867,11 +2457,12 @@ mod tests {
     fn test_midi_input_velocity_filter_any() {
         let mut mapping = make_midi_to_osc_mapping();
         mapping.midi_message_type = MidiMessageType::NoteOn;
-        mapping.midi_note_or_cc = 60;
+        mapping.midi_note_or_cc = MidiField::Fixed(60);
         mapping.midi_input_velocity = None; // Any velocity
 
-        let try_match_inline = |value: u8| -> bool {
+        let try_match_inline = |value: u16| -> bool {
             let msg = IncomingMessage::Midi {
+                port: "Keys".to_string(),
                 message_type: MidiMessageType::NoteOn,
                 channel: 1,
                 note_or_cc: 60,
@@ -883,15 +2474,16 @@ mod tests {
                     channel,
                     note_or_cc,
                     value,
+                    ..
                 } => {
                     if message_type != &mapping.midi_message_type
-                        || channel != &mapping.midi_channel
-                        || note_or_cc != &mapping.midi_note_or_cc
+                        || !mapping.midi_channel.accepts(*channel)
+                        || !mapping.midi_note_or_cc.accepts(*note_or_cc)
                     {
                         return false;
                     }
                     if let Some(vel) = mapping.midi_input_velocity {
-                        if value != &vel {
+                        if *value != vel {
                             return false;
                         }
                     }
@@ -1168,6 +2760,287 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_apply_transform_extended_numeric_types() {
+        let t = make_transform(TransformCurve::Linear, 0.0, 100.0, 0.0, 1.0);
+        match apply_transform(&OscArgValue::Double(50.0), &t) {
+            OscArgValue::Double(d) => assert!((d - 0.5).abs() < 1e-9),
+            other => panic!("Expected Double, got {:?}", other),
+        }
+        let t = make_transform(TransformCurve::Linear, 0.0, 1.0, 0.0, 127.0);
+        match apply_transform(&OscArgValue::Int64(1), &t) {
+            OscArgValue::Int64(i) => assert_eq!(i, 127),
+            other => panic!("Expected Int64, got {:?}", other),
+        }
+        // Non-numeric types pass through untouched
+        match apply_transform(&OscArgValue::Bool(true), &t) {
+            OscArgValue::Bool(b) => assert!(b),
+            other => panic!("Expected Bool passthrough, got {:?}", other),
+        }
+        assert!(matches!(apply_transform(&OscArgValue::Nil, &t), OscArgValue::Nil));
+    }
+
+    #[test]
+    fn test_osc_arg_to_midi_value_extended_types() {
+        assert_eq!(osc_arg_to_midi_value(&OscArgValue::Double(1.0), 127), 127);
+        assert_eq!(osc_arg_to_midi_value(&OscArgValue::Int64(64), 127), 64);
+        assert_eq!(osc_arg_to_midi_value(&OscArgValue::Bool(true), 127), 127);
+        assert_eq!(osc_arg_to_midi_value(&OscArgValue::Bool(false), 127), 0);
+        assert_eq!(osc_arg_to_midi_value(&OscArgValue::Impulse, 127), 127);
+        assert_eq!(osc_arg_to_midi_value(&OscArgValue::Nil, 127), 0);
+    }
+
+    #[test]
+    fn test_pitch_bend_value_scaling() {
+        assert_eq!(osc_arg_to_midi_value(&OscArgValue::Float(0.5), 16383), 8192);
+        assert_eq!(
+            osc_arg_to_midi_value(&OscArgValue::Float(1.0), 16383),
+            16383
+        );
+        assert_eq!(
+            osc_arg_to_midi_value(&OscArgValue::Int(20000), 16383),
+            16383
+        );
+        let midi = OscArgValue::Midi {
+            port: 0,
+            status: 0xE0,
+            data1: 0x00,
+            data2: 0x40,
+        };
+        assert_eq!(osc_arg_to_midi_value(&midi, 16383), 8192);
+        let def = OscArgDef {
+            arg_type: OscArgType::Float,
+            source: OscArgSource::MidiValue,
+        };
+        match build_osc_arg(&def, 16383, 16383, 0) {
+            OscArgValue::Float(f) => assert_eq!(f, 1.0),
+            other => panic!("expected Float, got {:?}", other),
+        }
+        let def = OscArgDef {
+            arg_type: OscArgType::Int,
+            source: OscArgSource::MidiValue,
+        };
+        assert!(matches!(
+            build_osc_arg(&def, 8192, 16383, 0),
+            OscArgValue::Int(8192)
+        ));
+    }
+
+    #[test]
+    fn test_format_new_midi_types() {
+        let fmt = |message_type, note_or_cc, value| {
+            format_output(&OutputAction::Midi {
+                port: None,
+                message_type,
+                channel: 2,
+                note_or_cc,
+                value,
+            })
+            .1
+        };
+        assert_eq!(
+            fmt(MidiMessageType::PitchBend, 0, 8192),
+            "Pitch Bend 8192 Ch 2"
+        );
+        assert_eq!(
+            fmt(MidiMessageType::ChannelPressure, 0, 90),
+            "Pressure 90 Ch 2"
+        );
+        assert_eq!(
+            fmt(MidiMessageType::PolyAftertouch, 60, 45),
+            "Poly AT 60 Val 45 Ch 2"
+        );
+        assert_eq!(
+            fmt(MidiMessageType::NoteOn, 60, 100),
+            "Note On 60 Val 100 Ch 2"
+        );
+    }
+
+    #[test]
+    fn test_cc14_mapping_and_format() {
+        let mut mapping = make_osc_to_midi_mapping("/fader", ValueSource::OscArg { index: 0 });
+        mapping.midi_message_type = MidiMessageType::Cc;
+        assert!(!is_cc14(&mapping));
+        mapping.cc_mode = CcMode::Nrpn;
+        assert!(is_cc14(&mapping));
+        mapping.midi_message_type = MidiMessageType::NoteOn;
+        assert!(!is_cc14(&mapping));
+        assert_eq!(osc_arg_to_midi_value(&OscArgValue::Float(0.5), 16383), 8192);
+
+        let action = OutputAction::Cc14 {
+            port: None,
+            mode: CcMode::Nrpn,
+            channel: 1,
+            parameter: 1234,
+            value: 8192,
+        };
+        assert_eq!(format_output(&action).1, "NRPN 1234 Val 8192 Ch 1");
+    }
+
+    #[test]
+    fn test_route_nrpn_to_osc() {
+        let mut mapping = make_midi_to_osc_mapping();
+        mapping.cc_mode = CcMode::Nrpn;
+        mapping.midi_parameter = 1234;
+        mapping.osc_address = "/nrpn/{note}".to_string();
+        let router = test_router(vec![mapping]);
+        let nrpn = |parameter: u16, value: u16| IncomingMessage::Cc14 {
+            port: "Keys".to_string(),
+            mode: CcMode::Nrpn,
+            channel: 1,
+            parameter,
+            value,
+        };
+        let routed = router.route(&nrpn(1234, 16383));
+        assert_eq!(routed.len(), 1);
+        match &routed[0].action {
+            OutputAction::Osc { address, args, .. } => {
+                assert_eq!(address, "/nrpn/1234");
+                assert_eq!(args, &vec![OscArgValue::Float(1.0)]);
+            }
+            other => panic!("Expected Osc, got {:?}", other),
+        }
+        assert!(router.route(&nrpn(1235, 16383)).is_empty());
+        // A 7-bit CC on the same channel doesn't match a 14-bit mapping
+        assert!(router
+            .route(&IncomingMessage::Midi {
+                port: "Keys".to_string(),
+                message_type: MidiMessageType::Cc,
+                channel: 1,
+                note_or_cc: 7,
+                value: 127,
+            })
+            .is_empty());
+    }
+
+    #[test]
+    fn test_route_cc14_applies_input_velocity() {
+        let mut mapping = make_midi_to_osc_mapping();
+        mapping.cc_mode = CcMode::FourteenBit;
+        mapping.midi_input_velocity = Some(8192);
+        let router = test_router(vec![mapping]);
+        let cc14 = |value: u16| IncomingMessage::Cc14 {
+            port: "Keys".to_string(),
+            mode: CcMode::FourteenBit,
+            channel: 1,
+            parameter: 7,
+            value,
+        };
+        assert_eq!(router.route(&cc14(8192)).len(), 1);
+        assert!(router.route(&cc14(8191)).is_empty());
+    }
+
+    #[test]
+    fn test_route_osc_to_cc14() {
+        let mut mapping = make_osc_to_midi_mapping("/fader", ValueSource::OscArg { index: 0 });
+        mapping.midi_message_type = MidiMessageType::Cc;
+        mapping.cc_mode = CcMode::FourteenBit;
+        mapping.midi_note_or_cc = MidiField::Fixed(7);
+        let mut nrpn = make_osc_to_midi_mapping("/nrpn", ValueSource::OscArg { index: 0 });
+        nrpn.id = "test-nrpn".to_string();
+        nrpn.midi_message_type = MidiMessageType::Cc;
+        nrpn.cc_mode = CcMode::Nrpn;
+        nrpn.midi_parameter = 1234;
+        let router = test_router(vec![mapping, nrpn]);
+        let osc = |address: &str| IncomingMessage::Osc {
+            address: address.to_string(),
+            args: vec![OscArgValue::Float(0.5)],
+        };
+
+        let routed = router.route(&osc("/fader"));
+        assert_eq!(routed.len(), 1);
+        assert_eq!(
+            routed[0].action,
+            OutputAction::Cc14 {
+                port: None,
+                mode: CcMode::FourteenBit,
+                channel: 1,
+                parameter: 7,
+                value: 8192,
+            }
+        );
+        let routed = router.route(&osc("/nrpn"));
+        assert!(matches!(
+            routed[0].action,
+            OutputAction::Cc14 {
+                mode: CcMode::Nrpn,
+                parameter: 1234,
+                value: 8192,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_route_osc_to_cc14_rejects_controller_above_31() {
+        let mut mapping = make_osc_to_midi_mapping("/fader", ValueSource::OscArg { index: 0 });
+        mapping.midi_message_type = MidiMessageType::Cc;
+        mapping.cc_mode = CcMode::FourteenBit;
+        mapping.midi_note_or_cc = MidiField::Fixed(40);
+        let router = test_router(vec![mapping]);
+        assert!(router
+            .route(&IncomingMessage::Osc {
+                address: "/fader".to_string(),
+                args: vec![OscArgValue::Float(0.5)],
+            })
+            .is_empty());
+    }
+
+    #[test]
+    fn test_build_osc_arg_static_extended_types() {
+        let def = |arg_type: OscArgType, value: serde_json::Value| OscArgDef {
+            arg_type,
+            source: OscArgSource::Static { value },
+        };
+        match build_osc_arg(&def(OscArgType::Bool, serde_json::json!(true)), 0, 127, 0) {
+            OscArgValue::Bool(b) => assert!(b),
+            other => panic!("Expected Bool, got {:?}", other),
+        }
+        match build_osc_arg(&def(OscArgType::Color, serde_json::json!("#FF800040")), 0, 127, 0) {
+            OscArgValue::Color {
+                red,
+                green,
+                blue,
+                alpha,
+            } => assert_eq!((red, green, blue, alpha), (255, 128, 0, 64)),
+            other => panic!("Expected Color, got {:?}", other),
+        }
+        match build_osc_arg(&def(OscArgType::Midi, serde_json::json!([0, 144, 60, 100])), 0, 127, 0) {
+            OscArgValue::Midi { status, data1, data2, .. } => {
+                assert_eq!((status, data1, data2), (0x90, 60, 100))
+            }
+            other => panic!("Expected Midi, got {:?}", other),
+        }
+        assert!(matches!(
+            build_osc_arg(&def(OscArgType::Impulse, serde_json::Value::Null), 0, 127, 0),
+            OscArgValue::Impulse
+        ));
+    }
+
+    #[test]
+    fn test_build_osc_arg_midi_value_double() {
+        let def = OscArgDef {
+            arg_type: OscArgType::Double,
+            source: OscArgSource::MidiValue,
+        };
+        match build_osc_arg(&def, 127, 127, 60) {
+            OscArgValue::Double(d) => assert_eq!(d, 1.0),
+            other => panic!("Expected Double, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_format_osc_arg_extended_types() {
+        assert_eq!(format_osc_arg(&OscArgValue::Bool(true)), "true");
+        assert_eq!(format_osc_arg(&OscArgValue::Nil), "nil");
+        assert_eq!(format_osc_arg(&OscArgValue::Blob(vec![1, 2, 3])), "<blob 3 bytes>");
+        assert_eq!(format_osc_arg(&OscArgValue::Char('a')), "'a'");
+        assert_eq!(
+            format_osc_arg(&OscArgValue::Array(vec![OscArgValue::Int(1), OscArgValue::Nil])),
+            "[1 nil]"
+        );
+    }
+
     // --- Calibration interpolation tests ---
 
     fn make_calibration_points() -> Vec<CalibrationPoint> {
@@ -1349,7 +3222,7 @@ mod tests {
             arg_type: OscArgType::String,
             source: OscArgSource::MscCueNumber { template: None },
         };
-        match build_osc_arg_msc(&def, "10", &None, &None) {
+        match build_osc_arg_msc(&def, "10", &None, &None, &None, None) {
             OscArgValue::String(s) => assert_eq!(s, "10"),
             _ => panic!("Expected string"),
         }
@@ -1361,7 +3234,7 @@ mod tests {
             arg_type: OscArgType::Float,
             source: OscArgSource::MscCueNumber { template: None },
         };
-        match build_osc_arg_msc(&def, "1.5", &None, &None) {
+        match build_osc_arg_msc(&def, "1.5", &None, &None, &None, None) {
             OscArgValue::Float(f) => assert!((f - 1.5).abs() < 0.001),
             _ => panic!("Expected float"),
         }
@@ -1375,7 +3248,7 @@ mod tests {
                 template: Some("Lighting Cue {cue_number}".to_string()),
             },
         };
-        match build_osc_arg_msc(&def, "10", &None, &None) {
+        match build_osc_arg_msc(&def, "10", &None, &None, &None, None) {
             OscArgValue::String(s) => assert_eq!(s, "Lighting Cue 10"),
             _ => panic!("Expected string"),
         }
@@ -1388,7 +3261,7 @@ mod tests {
             source: OscArgSource::MscCueList { template: None },
         };
         let cue_list = Some("5".to_string());
-        match build_osc_arg_msc(&def, "10", &cue_list, &None) {
+        match build_osc_arg_msc(&def, "10", &cue_list, &None, &None, None) {
             OscArgValue::String(s) => assert_eq!(s, "5"),
             _ => panic!("Expected string"),
         }
@@ -1400,7 +3273,7 @@ mod tests {
             arg_type: OscArgType::String,
             source: OscArgSource::MscCueList { template: None },
         };
-        match build_osc_arg_msc(&def, "10", &None, &None) {
+        match build_osc_arg_msc(&def, "10", &None, &None, &None, None) {
             OscArgValue::String(s) => assert_eq!(s, ""),
             _ => panic!("Expected string"),
         }
@@ -1415,7 +3288,7 @@ mod tests {
             },
         };
         let cue_list = Some("main".to_string());
-        match build_osc_arg_msc(&def, "10", &cue_list, &None) {
+        match build_osc_arg_msc(&def, "10", &cue_list, &None, &None, None) {
             OscArgValue::String(s) => assert_eq!(s, "List: main"),
             _ => panic!("Expected string"),
         }
@@ -1425,7 +3298,10 @@ mod tests {
     fn test_msc_command_format_matching() {
         assert_eq!(msc_command_format_to_byte(&MscCommandFormat::All), 0x7F);
         assert_eq!(msc_command_format_to_byte(&MscCommandFormat::Lighting), 0x01);
-        assert_eq!(msc_command_format_to_byte(&MscCommandFormat::Sound), 0x0A);
+        assert_eq!(msc_command_format_to_byte(&MscCommandFormat::Sound), 0x10);
+        assert_eq!(msc_command_format_to_byte(&MscCommandFormat::Machinery), 0x20);
+        assert_eq!(msc_command_format_to_byte(&MscCommandFormat::Video), 0x30);
+        assert_eq!(msc_command_format_to_byte(&MscCommandFormat::Pyro), 0x60);
     }
 
     #[test]
@@ -1433,5 +3309,440 @@ mod tests {
         assert_eq!(msc_command_to_byte(&MscCommand::Go), 0x01);
         assert_eq!(msc_command_to_byte(&MscCommand::Stop), 0x02);
         assert_eq!(msc_command_to_byte(&MscCommand::Resume), 0x03);
+        assert_eq!(msc_command_to_byte(&MscCommand::AllOff), 0x08);
+        assert_eq!(msc_command_to_byte(&MscCommand::GoJamClock), 0x10);
+        assert_eq!(msc_command_to_byte(&MscCommand::CloseCuePath), 0x1E);
+        assert_eq!(msc_command_byte_to_str(0x1B), "OPEN_CUE_LIST");
+    }
+
+    #[test]
+    fn test_msc_command_from_byte_round_trip() {
+        for byte in 0..=0x7F {
+            if let Some(cmd) = msc_command_from_byte(byte) {
+                assert_eq!(msc_command_to_byte(&cmd), byte);
+            }
+        }
+        assert_eq!(msc_command_from_byte(0x1D), Some(MscCommand::OpenCuePath));
+        assert_eq!(msc_command_from_byte(0x0C), None);
+    }
+
+    fn make_msc_output_mapping(cue_number: Option<MscCueSource>) -> Mapping {
+        Mapping {
+            midi_message_type: MidiMessageType::Msc,
+            msc_device_id: Some(1),
+            msc_command_format: Some(MscCommandFormat::Lighting),
+            msc_command: Some(MscCommand::Go),
+            msc_cue_number: cue_number,
+            ..make_osc_to_midi_mapping("/cue/*/go", ValueSource::Static { value: 0 })
+        }
+    }
+
+    #[test]
+    fn test_msc_output_from_osc_arg() {
+        let mapping = make_msc_output_mapping(Some(MscCueSource::OscArg { index: 0 }));
+        let Ok(OutputAction::Msc { msc, .. }) = msc_output(&mapping, &[OscArgValue::Float(12.5)], &[])
+        else {
+            panic!("expected MSC output");
+        };
+        assert_eq!(
+            (msc.device_id, msc.command_format, msc.command),
+            (1, 0x01, 0x01)
+        );
+        assert_eq!(msc.cue_number, "12.5");
+        assert_eq!(msc.cue_list, None);
+    }
+
+    #[test]
+    fn test_msc_output_static_and_capture() {
+        let mut mapping = make_msc_output_mapping(Some(MscCueSource::Capture { index: 0 }));
+        mapping.msc_cue_list = Some(MscCueSource::Static {
+            value: "2".to_string(),
+        });
+        let action = msc_output(&mapping, &[], &["7".to_string()]).unwrap();
+        let (_, display) = format_output(&action);
+        assert_eq!(display, "MSC GO Q7");
+        let OutputAction::Msc { msc, .. } = action else {
+            panic!("expected MSC output");
+        };
+        assert_eq!(msc.cue_number, "7");
+        assert_eq!(msc.cue_list.as_deref(), Some("2"));
+    }
+
+    #[test]
+    fn test_msc_output_round_trips_through_parser() {
+        let mut mapping = make_msc_output_mapping(Some(MscCueSource::OscArg { index: 0 }));
+        mapping.msc_cue_list = Some(MscCueSource::OscArg { index: 1 });
+        let args = [OscArgValue::String("3.1".to_string()), OscArgValue::Int(4)];
+        let Ok(OutputAction::Msc { msc, .. }) = msc_output(&mapping, &args, &[]) else {
+            panic!("expected MSC output");
+        };
+        let bytes = crate::midi_engine::build_msc_sysex(&msc);
+        let parsed = crate::midi_engine::parse_msc_sysex(&bytes).unwrap();
+        assert_eq!(parsed, msc);
+        assert_eq!(parsed.device_id, 1);
+        assert_eq!(parsed.command_format, 0x01);
+        assert_eq!(parsed.command, 0x01);
+        assert_eq!(parsed.cue_number, "3.1");
+        assert_eq!(parsed.cue_list.as_deref(), Some("4"));
+    }
+
+    #[test]
+    fn test_msc_output_cue_list_command() {
+        let mapping = Mapping {
+            msc_command: Some(MscCommand::OpenCueList),
+            msc_cue_list: Some(MscCueSource::Static {
+                value: "5".to_string(),
+            }),
+            ..make_msc_output_mapping(None)
+        };
+        let Ok(OutputAction::Msc { msc, .. }) = msc_output(&mapping, &[], &[]) else {
+            panic!("expected MSC output");
+        };
+        let bytes = crate::midi_engine::build_msc_sysex(&msc);
+        assert_eq!(crate::midi_engine::parse_msc_sysex(&bytes), Some(msc));
+    }
+
+    #[test]
+    fn test_msc_output_rejections() {
+        let mapping = make_msc_output_mapping(Some(MscCueSource::OscArg { index: 0 }));
+        assert!(matches!(
+            msc_output(&mapping, &[OscArgValue::String("go".to_string())], &[]),
+            Err(NoMatch::Rejected(_))
+        ));
+        assert!(matches!(
+            msc_output(&mapping, &[], &[]),
+            Err(NoMatch::Rejected(_))
+        ));
+        let no_command = Mapping {
+            msc_command: None,
+            ..make_msc_output_mapping(None)
+        };
+        assert!(matches!(
+            msc_output(&no_command, &[], &[]),
+            Err(NoMatch::Rejected(_))
+        ));
+        for command in [MscCommand::Set, MscCommand::TimedGo, MscCommand::SetClock] {
+            let timed = Mapping {
+                msc_command: Some(command),
+                ..make_msc_output_mapping(None)
+            };
+            assert!(matches!(
+                msc_output(&timed, &[], &[]),
+                Err(NoMatch::Rejected(reason)) if reason.contains("not supported")
+            ));
+        }
+        let fire = Mapping {
+            msc_command: Some(MscCommand::Fire),
+            ..make_msc_output_mapping(Some(MscCueSource::OscArg { index: 0 }))
+        };
+        assert!(matches!(
+            msc_output(&fire, &[OscArgValue::Int(200)], &[]),
+            Err(NoMatch::Rejected(_))
+        ));
+    }
+
+    #[test]
+    fn test_msc_output_defaults_to_all_call() {
+        let mapping = Mapping {
+            msc_device_id: None,
+            msc_command_format: None,
+            ..make_msc_output_mapping(None)
+        };
+        let Ok(OutputAction::Msc { msc, .. }) = msc_output(&mapping, &[], &[]) else {
+            panic!("expected MSC output");
+        };
+        assert_eq!((msc.device_id, msc.command_format), (0x7F, 0x7F));
+        assert_eq!(msc.cue_number, "");
+    }
+
+    fn msc_arg(arg_type: OscArgType, source: OscArgSource) -> OscArgDef {
+        OscArgDef { arg_type, source }
+    }
+
+    #[test]
+    fn test_msc_time_arg() {
+        let time = Some(MscTime {
+            hours: 0,
+            minutes: 1,
+            seconds: 2,
+            frames: 15,
+            subframes: 0,
+            rate: 3,
+        });
+        match build_osc_arg_msc(
+            &msc_arg(OscArgType::String, OscArgSource::MscTime),
+            "",
+            &None,
+            &None,
+            &time,
+            None,
+        ) {
+            OscArgValue::String(s) => assert_eq!(s, "00:01:02:15.00"),
+            other => panic!("Expected string, got {:?}", other),
+        }
+        match build_osc_arg_msc(
+            &msc_arg(OscArgType::Float, OscArgSource::MscTime),
+            "",
+            &None,
+            &None,
+            &time,
+            None,
+        ) {
+            OscArgValue::Float(f) => assert!((f - 62.5).abs() < 1e-4),
+            other => panic!("Expected float, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_msc_set_args() {
+        let set = Some((300, 16383));
+        match build_osc_arg_msc(
+            &msc_arg(OscArgType::Int, OscArgSource::MscSetControl),
+            "",
+            &None,
+            &None,
+            &None,
+            set,
+        ) {
+            OscArgValue::Int(i) => assert_eq!(i, 300),
+            other => panic!("Expected int, got {:?}", other),
+        }
+        match build_osc_arg_msc(
+            &msc_arg(OscArgType::Float, OscArgSource::MscSetValue),
+            "",
+            &None,
+            &None,
+            &None,
+            set,
+        ) {
+            OscArgValue::Float(f) => assert_eq!(f, 1.0),
+            other => panic!("Expected float, got {:?}", other),
+        }
+        match build_osc_arg_msc(
+            &msc_arg(OscArgType::Int, OscArgSource::MscSetValue),
+            "",
+            &None,
+            &None,
+            &None,
+            set,
+        ) {
+            OscArgValue::Int(i) => assert_eq!(i, 16383),
+            other => panic!("Expected int, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_remap_midi_rewrites_channel_type_and_number() {
+        let mut mapping = make_midi_to_osc_mapping();
+        mapping.direction = Direction::MidiToMidi;
+        mapping.midi_output_port = Some("Synth".to_string());
+        mapping.midi_remap = MidiRemap {
+            message_type: Some(MidiMessageType::Cc),
+            channel: Some(10),
+            number: Some(20),
+        };
+        match remap_midi(&mapping, &MidiMessageType::NoteOn, 1, 60, 100) {
+            Ok(OutputAction::Midi {
+                port,
+                message_type,
+                channel,
+                note_or_cc,
+                value,
+            }) => {
+                assert_eq!(port.as_deref(), Some("Synth"));
+                assert_eq!(message_type, MidiMessageType::Cc);
+                assert_eq!((channel, note_or_cc, value), (10, 20, 100));
+            }
+            other => panic!("Expected Midi, got {:?}", other),
+        }
+
+        // Unset fields keep the incoming values
+        mapping.midi_remap = MidiRemap::default();
+        match remap_midi(&mapping, &MidiMessageType::NoteOn, 3, 60, 100) {
+            Ok(OutputAction::Midi {
+                message_type,
+                channel,
+                note_or_cc,
+                ..
+            }) => {
+                assert_eq!(message_type, MidiMessageType::NoteOn);
+                assert_eq!((channel, note_or_cc), (3, 60));
+            }
+            other => panic!("Expected Midi, got {:?}", other),
+        }
+
+        mapping.midi_remap.message_type = Some(MidiMessageType::Msc);
+        assert!(matches!(
+            remap_midi(&mapping, &MidiMessageType::NoteOn, 1, 60, 100),
+            Err(NoMatch::Rejected(_))
+        ));
+    }
+
+    #[test]
+    fn test_remap_midi_value() {
+        // Rescales between 7-bit and 14-bit ranges
+        assert_eq!(remap_midi_value(127, 127, 16383, None), 16383);
+        assert_eq!(remap_midi_value(16383, 16383, 127, None), 127);
+        assert_eq!(remap_midi_value(64, 127, 127, None), 64);
+
+        // A transform works in raw MIDI units
+        let t = make_transform(TransformCurve::Linear, 0.0, 127.0, 127.0, 0.0);
+        assert_eq!(remap_midi_value(0, 127, 127, Some(&t)), 127);
+        assert_eq!(remap_midi_value(127, 127, 127, Some(&t)), 0);
+        let t = make_transform(TransformCurve::Linear, 0.0, 127.0, 0.0, 500.0);
+        assert_eq!(remap_midi_value(127, 127, 127, Some(&t)), 127);
+    }
+
+    #[test]
+    fn test_remap_cc14_keeps_mode() {
+        let mut mapping = make_midi_to_osc_mapping();
+        mapping.direction = Direction::MidiToMidi;
+        mapping.cc_mode = CcMode::FourteenBit;
+        mapping.midi_remap.number = Some(20);
+        match remap_cc14(&mapping, 1, 7, 8192) {
+            Ok(OutputAction::Cc14 {
+                mode,
+                parameter,
+                value,
+                ..
+            }) => {
+                assert_eq!(mode, CcMode::FourteenBit);
+                assert_eq!((parameter, value), (20, 8192));
+            }
+            other => panic!("Expected Cc14, got {:?}", other),
+        }
+        // Controller 40 has no LSB partner
+        mapping.midi_remap.number = Some(40);
+        assert!(matches!(
+            remap_cc14(&mapping, 1, 7, 8192),
+            Err(NoMatch::Rejected(_))
+        ));
+    }
+
+    #[test]
+    fn test_latched_output() {
+        assert!(osc_is_press(&[]));
+        assert!(osc_is_press(&[OscArgValue::Float(1.0)]));
+        assert!(!osc_is_press(&[OscArgValue::Float(0.0)]));
+        assert!(!midi_is_press(&MidiMessageType::NoteOff, 64));
+        assert!(!midi_is_press(&MidiMessageType::NoteOn, 0));
+        assert!(midi_is_press(&MidiMessageType::ProgramChange, 0));
+
+        assert_eq!(latched_value(100, 127, None), 100);
+        assert_eq!(latched_value(100, 127, Some(false)), 0);
+        assert_eq!(latched_value(0, 127, Some(true)), 127);
+
+        let args = latched_args(&[OscArgValue::Float(0.7), OscArgValue::Int(3)], false);
+        assert!(matches!(args[..], [OscArgValue::Float(f), OscArgValue::Int(3)] if f == 0.0));
+        assert!(matches!(latched_args(&[], true)[..], [OscArgValue::Int(1)]));
+    }
+
+    #[test]
+    fn test_macro_steps() {
+        let mut mapping = make_midi_to_osc_mapping();
+        mapping.steps = vec![
+            MacroStep {
+                delay_ms: 200,
+                output: MacroOutput::Osc {
+                    address: "/qlab/go".to_string(),
+                    args: vec![OscArgDef {
+                        arg_type: OscArgType::Int,
+                        source: OscArgSource::MidiNote,
+                    }],
+                    destinations: vec!["qlab".to_string()],
+                },
+            },
+            MacroStep {
+                delay_ms: 0,
+                output: MacroOutput::Midi {
+                    message_type: MidiMessageType::Cc,
+                    channel: 1,
+                    note_or_cc: 20,
+                    value: 127,
+                    port: None,
+                },
+            },
+        ];
+        let msg = IncomingMessage::Midi {
+            port: "In".to_string(),
+            message_type: MidiMessageType::NoteOn,
+            channel: 1,
+            note_or_cc: 60,
+            value: 100,
+        };
+        let steps = macro_steps(&mapping, &msg);
+        assert_eq!(steps.len(), 2);
+        assert_eq!(steps[0].delay, Duration::from_millis(200));
+        assert!(matches!(
+            &steps[0].action,
+            OutputAction::Osc { address, args, .. }
+                if address == "/qlab/go" && args[..] == [OscArgValue::Int(60)]
+        ));
+        assert!(matches!(
+            steps[1].action,
+            OutputAction::Midi { note_or_cc: 20, value: 127, .. }
+        ));
+    }
+
+    #[test]
+    fn test_route_clock_input_only_matches_clock_mappings() {
+        let mut tempo_mapping = make_midi_to_osc_mapping();
+        tempo_mapping.id = "tempo".to_string();
+        tempo_mapping.clock_input = Some(ClockInput::Tempo);
+        let router = test_router(vec![tempo_mapping, make_midi_to_osc_mapping()]);
+
+        let routed = router.route(&IncomingMessage::Tempo {
+            port: "Clock".to_string(),
+            bpm: 120.0,
+        });
+        assert_eq!(routed.len(), 1);
+        assert_eq!(routed[0].mapping_id, "tempo");
+
+        let routed = router.route(&IncomingMessage::Midi {
+            port: "Keys".to_string(),
+            message_type: MidiMessageType::Cc,
+            channel: 1,
+            note_or_cc: 7,
+            value: 64,
+        });
+        assert_eq!(routed.len(), 1);
+        assert_eq!(routed[0].mapping_id, "test-2");
+    }
+
+    #[test]
+    fn test_build_osc_arg_clock() {
+        let def = |arg_type: OscArgType, source: OscArgSource| OscArgDef { arg_type, source };
+        let tempo = IncomingMessage::Tempo {
+            port: "Clock".to_string(),
+            bpm: 128.5,
+        };
+        assert_eq!(
+            build_osc_arg_clock(&def(OscArgType::Float, OscArgSource::Bpm), &tempo),
+            OscArgValue::Float(128.5)
+        );
+        let beat = IncomingMessage::Beat {
+            port: "Clock".to_string(),
+            beat: 3,
+            bar: 17,
+        };
+        assert_eq!(
+            build_osc_arg_clock(&def(OscArgType::Int, OscArgSource::Bar), &beat),
+            OscArgValue::Int(17)
+        );
+        let start = IncomingMessage::Transport {
+            port: "Clock".to_string(),
+            state: TransportState::Start,
+        };
+        assert_eq!(
+            build_osc_arg_clock(&def(OscArgType::String, OscArgSource::Transport), &start),
+            OscArgValue::String("start".into())
+        );
+        // Sources that don't apply to the input get their empty value
+        assert_eq!(
+            build_osc_arg_clock(&def(OscArgType::Int, OscArgSource::Bpm), &start),
+            OscArgValue::Int(0)
+        );
+        assert_eq!(format_incoming(&tempo).1, "Tempo 128.5 BPM");
+        assert_eq!(format_incoming(&beat).1, "Beat 17.3");
     }
 }
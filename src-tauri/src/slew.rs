@@ -0,0 +1,388 @@
+use crate::models::{Slew, SlewMode};
+use crate::router::{OscArgValue, OutputAction};
+use log::error;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio_util::sync::CancellationToken;
+
+/// Once this many outputs are tracked, finished ramps idle for `RAMP_IDLE`
+/// are forgotten. Keeps templated and capture mappings from growing the
+/// map without bound.
+const MAX_RAMPS: usize = 1024;
+const RAMP_IDLE: Duration = Duration::from_secs(60);
+
+/// Sends an output action.
+pub type Dispatch = Arc<dyn Fn(&OutputAction) + Send + Sync>;
+
+/// A running or finished ramp for one output. The last values are kept
+/// after the ramp finishes so the next one starts from there.
+struct Ramp {
+    /// Latest target action; ramp steps copy it with interpolated values
+    target: OutputAction,
+    target_values: Vec<f64>,
+    current: Vec<f64>,
+    slew: Slew,
+    running: bool,
+    /// When the ramp last received or reached a value
+    last_active: Instant,
+}
+
+/// Ramps slewed mappings' outputs toward their latest value on background
+/// tasks, one per output that is currently moving.
+pub struct Slewer {
+    ramps: Arc<Mutex<HashMap<String, Ramp>>>,
+    dispatch: Dispatch,
+    token: CancellationToken,
+}
+
+impl Slewer {
+    pub fn new(dispatch: Dispatch, token: CancellationToken) -> Self {
+        Self {
+            ramps: Arc::new(Mutex::new(HashMap::new())),
+            dispatch,
+            token,
+        }
+    }
+
+    /// Ramp toward `action`. The first value for an output is sent as is;
+    /// later values retarget the ramp, starting one if none is running.
+    pub fn submit(&self, mapping_id: &str, slew: &Slew, action: OutputAction) {
+        let Some(target_values) = values_of(&action) else {
+            (self.dispatch)(&action);
+            return;
+        };
+        let key = format!("{}:{}", mapping_id, output_key(&action));
+        let mut ramps = match self.ramps.lock() {
+            Ok(guard) => guard,
+            Err(e) => {
+                error!("Slew ramps mutex poisoned in submit(): {}", e);
+                return;
+            }
+        };
+        match ramps.get_mut(&key) {
+            Some(ramp) if ramp.current.len() == target_values.len() => {
+                ramp.target = action;
+                ramp.target_values = target_values;
+                ramp.slew = slew.clone();
+                ramp.last_active = Instant::now();
+                if !ramp.running {
+                    ramp.running = true;
+                    self.spawn_ramp(key, slew.rate_hz);
+                }
+            }
+            _ => {
+                (self.dispatch)(&action);
+                if ramps.len() >= MAX_RAMPS {
+                    let now = Instant::now();
+                    ramps.retain(|_, r| r.running || now.duration_since(r.last_active) < RAMP_IDLE);
+                }
+                ramps.insert(
+                    key,
+                    Ramp {
+                        target: action,
+                        current: target_values.clone(),
+                        target_values,
+                        slew: slew.clone(),
+                        running: false,
+                        last_active: Instant::now(),
+                    },
+                );
+            }
+        }
+    }
+
+    fn spawn_ramp(&self, key: String, rate_hz: f64) {
+        let ramps = self.ramps.clone();
+        let dispatch = self.dispatch.clone();
+        let token = self.token.clone();
+        let period = Duration::from_secs_f64(1.0 / rate_hz.clamp(1.0, 1000.0));
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+            // The first tick completes immediately
+            interval.tick().await;
+            loop {
+                tokio::select! {
+                    _ = token.cancelled() => break,
+                    _ = interval.tick() => {}
+                }
+                let (action, done) = {
+                    let mut guard = match ramps.lock() {
+                        Ok(guard) => guard,
+                        Err(e) => {
+                            error!("Slew ramps mutex poisoned in ramp task: {}", e);
+                            break;
+                        }
+                    };
+                    let Some(ramp) = guard.get_mut(&key) else {
+                        break;
+                    };
+                    let done = step(ramp, period.as_secs_f64());
+                    ramp.running = !done;
+                    ramp.last_active = Instant::now();
+                    (with_values(&ramp.target, &ramp.current), done)
+                };
+                dispatch(&action);
+                if done {
+                    break;
+                }
+            }
+        });
+    }
+}
+
+/// Move the ramp one tick of `dt` seconds toward its target. Returns true
+/// once the target is reached.
+fn step(ramp: &mut Ramp, dt: f64) -> bool {
+    let integer = integer_values(&ramp.target);
+    let mut done = true;
+    for (i, (current, target)) in ramp.current.iter_mut().zip(&ramp.target_values).enumerate() {
+        let diff = target - *current;
+        let next = match ramp.slew.mode {
+            _ if ramp.slew.amount <= 0.0 => *target,
+            SlewMode::TimeConstant => {
+                *current + diff * (1.0 - (-dt * 1000.0 / ramp.slew.amount).exp())
+            }
+            SlewMode::MaxRate => {
+                let max_step = ramp.slew.amount * dt;
+                *current + diff.clamp(-max_step, max_step)
+            }
+        };
+        // Exponential ramps never land exactly; snap once within a step
+        // of the output's resolution.
+        let tolerance = if integer.get(i).copied().unwrap_or(false) {
+            0.5
+        } else {
+            1e-4
+        };
+        if (target - next).abs() < tolerance {
+            *current = *target;
+        } else {
+            *current = next;
+            done = false;
+        }
+    }
+    done
+}
+
+/// The values a ramp interpolates: the MIDI value, or every numeric OSC
+/// argument. `None` for outputs that can't be slewed.
+pub fn values_of(action: &OutputAction) -> Option<Vec<f64>> {
+    match action {
+        OutputAction::Midi { value, .. } | OutputAction::Cc14 { value, .. } => {
+            Some(vec![f64::from(*value)])
+        }
+        OutputAction::Osc { args, .. } => {
+            let values: Vec<f64> = args.iter().filter_map(arg_value).collect();
+            (!values.is_empty()).then_some(values)
+        }
+        OutputAction::Msc { .. } => None,
+    }
+}
+
+/// Which of `values_of` are whole numbers on the wire.
+fn integer_values(action: &OutputAction) -> Vec<bool> {
+    match action {
+        OutputAction::Osc { args, .. } => args
+            .iter()
+            .filter(|a| arg_value(a).is_some())
+            .map(|a| matches!(a, OscArgValue::Int(_) | OscArgValue::Int64(_)))
+            .collect(),
+        _ => vec![true],
+    }
+}
+
+fn arg_value(arg: &OscArgValue) -> Option<f64> {
+    match arg {
+        OscArgValue::Int(i) => Some(f64::from(*i)),
+        OscArgValue::Float(f) => Some(f64::from(*f)),
+        OscArgValue::Double(d) => Some(*d),
+        OscArgValue::Int64(i) => Some(*i as f64),
+        _ => None,
+    }
+}
+
+/// Copy `action` with its slewable values replaced by `values`.
+pub fn with_values(action: &OutputAction, values: &[f64]) -> OutputAction {
+    let mut action = action.clone();
+    match &mut action {
+        OutputAction::Midi { value, .. } | OutputAction::Cc14 { value, .. } => {
+            if let Some(v) = values.first() {
+                *value = v.round() as u16;
+            }
+        }
+        OutputAction::Osc { args, .. } => {
+            let slots = args.iter_mut().filter(|a| arg_value(a).is_some());
+            for (arg, v) in slots.zip(values) {
+                *arg = match arg {
+                    OscArgValue::Int(_) => OscArgValue::Int(v.round() as i32),
+                    OscArgValue::Float(_) => OscArgValue::Float(*v as f32),
+                    OscArgValue::Int64(_) => OscArgValue::Int64(v.round() as i64),
+                    _ => OscArgValue::Double(*v),
+                };
+            }
+        }
+        OutputAction::Msc { .. } => {}
+    }
+    action
+}
+
+/// Identifies one output of a mapping, so a capture-driven mapping gets a
+/// separate ramp per address or note.
+pub fn output_key(action: &OutputAction) -> String {
+    match action {
+        OutputAction::Midi {
+            port,
+            message_type,
+            channel,
+            note_or_cc,
+            ..
+        } => format!("{:?}/{:?}/{}/{}", port, message_type, channel, note_or_cc),
+        OutputAction::Cc14 {
+            port,
+            mode,
+            channel,
+            parameter,
+            ..
+        } => format!("{:?}/{:?}/{}/{}", port, mode, channel, parameter),
+        OutputAction::Osc { address, .. } => address.clone(),
+        OutputAction::Msc { .. } => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::MidiMessageType;
+
+    fn osc(value: f32) -> OutputAction {
+        OutputAction::Osc {
+            address: "/vol".to_string(),
+            args: vec![OscArgValue::String("ch1".into()), OscArgValue::Float(value)],
+            destinations: vec![],
+        }
+    }
+
+    fn ramp(mode: SlewMode, amount: f64, from: f64, to: f64) -> Ramp {
+        let target = osc(to as f32);
+        Ramp {
+            target_values: values_of(&target).unwrap(),
+            target,
+            current: vec![from],
+            slew: Slew {
+                mode,
+                amount,
+                rate_hz: 100.0,
+            },
+            running: true,
+            last_active: Instant::now(),
+        }
+    }
+
+    #[test]
+    fn test_max_rate_is_linear() {
+        let mut r = ramp(SlewMode::MaxRate, 1.0, 0.0, 0.5);
+        assert!(!step(&mut r, 0.1));
+        assert!((r.current[0] - 0.1).abs() < 1e-9);
+        for _ in 0..3 {
+            step(&mut r, 0.1);
+        }
+        assert!(step(&mut r, 0.1));
+        assert!((r.current[0] - 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_time_constant_approaches_and_snaps() {
+        let mut r = ramp(SlewMode::TimeConstant, 100.0, 0.0, 1.0);
+        step(&mut r, 0.1);
+        // One time constant covers ~63% of the distance
+        assert!((r.current[0] - 0.632).abs() < 1e-3);
+        let mut ticks = 0;
+        while !step(&mut r, 0.1) {
+            ticks += 1;
+            assert!(ticks < 100);
+        }
+        assert!((r.current[0] - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_with_values_keeps_arg_types() {
+        match with_values(&osc(0.0), &[0.25]) {
+            OutputAction::Osc { args, .. } => {
+                assert!(matches!(&args[0], OscArgValue::String(s) if s == "ch1"));
+                assert!(matches!(args[1], OscArgValue::Float(f) if f == 0.25));
+            }
+            other => panic!("Expected Osc, got {:?}", other),
+        }
+        let midi = OutputAction::Midi {
+            port: None,
+            message_type: MidiMessageType::Cc,
+            channel: 1,
+            note_or_cc: 7,
+            value: 0,
+        };
+        assert!(matches!(
+            with_values(&midi, &[63.6]),
+            OutputAction::Midi { value: 64, .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_submit_ramps_and_retargets() {
+        let sent: Arc<Mutex<Vec<f64>>> = Arc::default();
+        let sink = sent.clone();
+        let dispatch: Dispatch = Arc::new(move |action| {
+            if let Some(v) = values_of(action) {
+                sink.lock().unwrap().push(v[0]);
+            }
+        });
+        let slewer = Slewer::new(dispatch, CancellationToken::new());
+        let slew = Slew {
+            mode: SlewMode::MaxRate,
+            amount: 10.0,
+            rate_hz: 200.0,
+        };
+
+        // The first value is sent straight away
+        slewer.submit("m", &slew, osc(0.0));
+        assert_eq!(*sent.lock().unwrap(), vec![0.0]);
+
+        slewer.submit("m", &slew, osc(1.0));
+        slewer.submit("m", &slew, osc(0.5));
+        tokio::time::sleep(Duration::from_millis(300)).await;
+
+        let sent = sent.lock().unwrap();
+        assert!(
+            sent.len() > 3,
+            "expected intermediate values, got {:?}",
+            sent
+        );
+        assert!(sent.windows(2).all(|w| w[1] >= w[0]));
+        assert!((sent.last().unwrap() - 0.5).abs() < 1e-6);
+    }
+
+    #[tokio::test]
+    async fn test_idle_ramps_are_forgotten() {
+        let slewer = Slewer::new(Arc::new(|_: &OutputAction| {}), CancellationToken::new());
+        let slew = Slew {
+            mode: SlewMode::MaxRate,
+            amount: 10.0,
+            rate_hz: 100.0,
+        };
+        let fader = |n: usize| OutputAction::Osc {
+            address: format!("/ch/{}/fader", n),
+            args: vec![OscArgValue::Float(0.0)],
+            destinations: vec![],
+        };
+        for n in 0..MAX_RAMPS {
+            slewer.submit("m", &slew, fader(n));
+        }
+        let long_ago = Instant::now() - RAMP_IDLE - Duration::from_secs(1);
+        for ramp in slewer.ramps.lock().unwrap().values_mut().take(10) {
+            ramp.last_active = long_ago;
+        }
+        slewer.submit("m", &slew, fader(MAX_RAMPS));
+        assert_eq!(slewer.ramps.lock().unwrap().len(), MAX_RAMPS - 9);
+    }
+}
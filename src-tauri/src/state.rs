@@ -1,25 +1,30 @@
+use crate::learn::LearnSlot;
+use crate::mapping_state::MappingStates;
+use crate::loop_guard::LoopGuard;
+use crate::midi_engine::MidiInputs;
 use crate::models::{Mapping, Settings};
-use midir::MidiInputConnection;
+use crate::osc_tcp::TcpSenderPool;
+use crate::output_filter::DropCounts;
 use std::sync::{Arc, Mutex};
 use tokio_util::sync::CancellationToken;
 
 pub struct EngineHandle {
     pub cancel_token: CancellationToken,
-    pub _midi_input: Option<MidiInputConnection<()>>,
+    /// Open MIDI inputs, kept alive until the engine stops
+    pub midi_inputs: Arc<MidiInputs>,
+    pub osc_tcp: Arc<TcpSenderPool>,
 }
 
-// SAFETY: MidiInputConnection<()> is not Send because the underlying platform MIDI handle
-// types (e.g. CoreMIDI's MIDIPortRef on macOS) are raw pointers. However, this is safe because:
-// 1. Platform MIDI handles (CoreMIDI, ALSA, WinMM) are thread-safe in practice — they are
-//    opaque handles managed by the OS and do not carry thread-affine mutable state.
-// 2. We only store the connection here to keep it alive and drop it on engine stop.
-//    No cross-thread method calls are made on the connection itself.
-// 3. The callback closure captured by midir only captures an mpsc::UnboundedSender<IncomingMessage>,
-//    which is Send + Sync.
-unsafe impl Send for EngineHandle {}
-
 pub struct AppState {
     pub settings: Arc<Mutex<Settings>>,
     pub mappings: Arc<Mutex<Vec<Mapping>>>,
     pub engine: Mutex<Option<EngineHandle>>,
+    /// Pending MIDI learn request, checked by the router task
+    pub learn: Arc<LearnSlot>,
+    /// Toggle/latch state, kept across engine restarts
+    pub mapping_states: Arc<MappingStates>,
+    /// Outputs dropped by mapping filters since the engine started
+    pub drop_counts: Arc<DropCounts>,
+    /// Echo suppression and loop breakers, reset when the engine starts
+    pub loop_guard: Arc<LoopGuard>,
 }
@@ -15,7 +15,7 @@ function App() {
   const { settings, updateSettings, refetch: refetchSettings } = useSettings();
   const { mappings, addMapping, updateMapping, deleteMapping, refetch: refetchMappings } = useMappings();
   const { inputs, outputs, refresh: refreshMidi } = useMidi();
-  const { status, start, stop } = useEngine();
+  const { status, start, stop, loopBreakers, resetLoopBreakers } = useEngine();
   const { entries, paused, clear, togglePause } = useActivityLog();
 
   const autoStarted = useRef(false);
@@ -101,6 +101,10 @@ function App() {
         engineStatus={status}
         onStartEngine={handleStart}
         onStopEngine={handleStop}
+        loopBreakers={loopBreakers}
+        onResetLoopBreakers={() =>
+          resetLoopBreakers().catch((e) => toast.error(String(e)))
+        }
         onExport={handleExport}
         onImport={handleImport}
       />
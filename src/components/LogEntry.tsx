@@ -14,6 +14,9 @@ export function LogEntry({ entry }: LogEntryProps) {
         <span>
           ? {entry.protocol?.toUpperCase()} {entry.display}
         </span>
+        {entry.reason && (
+          <span className="text-yellow-500/80"> ({entry.reason})</span>
+        )}
       </div>
     );
   }
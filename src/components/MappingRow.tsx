@@ -1,4 +1,7 @@
-import { Trash2, ArrowRight } from "lucide-react";
+import { useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { toast } from "sonner";
+import { Trash2, ArrowRight, Crosshair } from "lucide-react";
 import { Switch } from "@/components/ui/switch";
 import { Button } from "@/components/ui/button";
 import {
@@ -22,6 +25,12 @@ import {
 import { OscInputFields } from "@/components/mapping/OscInputFields";
 import { MidiInputFields } from "@/components/mapping/MidiInputFields";
 import { MidiOutputFields } from "@/components/mapping/MidiOutputFields";
+import { MidiRemapFields } from "@/components/mapping/MidiRemapFields";
+import { ConditionFields } from "@/components/mapping/ConditionFields";
+import { ModeFields } from "@/components/mapping/ModeFields";
+import { SlewFields } from "@/components/mapping/SlewFields";
+import { FilterFields } from "@/components/mapping/FilterFields";
+import { MacroFields } from "@/components/mapping/MacroFields";
 import { OscOutputFields } from "@/components/mapping/OscOutputFields";
 import { OscToOscFields } from "@/components/mapping/OscToOscFields";
 import { cn } from "@/lib/utils";
@@ -36,6 +45,33 @@ interface MappingRowProps {
 export function MappingRow({ mapping, onChange, onDelete }: MappingRowProps) {
   const isOscToMidi = mapping.direction === "osc_to_midi";
   const isOscToOsc = mapping.direction === "osc_to_osc";
+  const isMidiToMidi = mapping.direction === "midi_to_midi";
+  const [learning, setLearning] = useState(false);
+
+  const toggleLearn = async () => {
+    if (learning) {
+      await invoke("cancel_learn").catch(console.error);
+      return;
+    }
+    setLearning(true);
+    try {
+      // Learn the input side. OSC → OSC collects a burst of messages so
+      // the transform input range can be taken from the fader's travel.
+      const learned =
+        mapping.direction === "midi_to_osc" || isMidiToMidi
+          ? await invoke<Mapping>("start_midi_learn", { mappingId: mapping.id })
+          : await invoke<Mapping>("start_osc_learn", {
+              mappingId: mapping.id,
+              count: isOscToOsc ? 200 : 1,
+              timeoutMs: isOscToOsc ? 5000 : undefined,
+            });
+      onChange(learned);
+    } catch (e) {
+      if (!String(e).endsWith("learn cancelled")) toast.error(String(e));
+    } finally {
+      setLearning(false);
+    }
+  };
 
   return (
     <div
@@ -54,7 +90,12 @@ export function MappingRow({ mapping, onChange, onDelete }: MappingRowProps) {
       <Select
         value={mapping.direction}
         onValueChange={(v) =>
-          onChange({ ...mapping, direction: v as Direction })
+          onChange({
+            ...mapping,
+            direction: v as Direction,
+            // Only MIDI → OSC mappings follow clock input
+            clock_input: v === "midi_to_osc" ? mapping.clock_input : null,
+          })
         }
       >
         <SelectTrigger className="h-7 text-xs w-32">
@@ -64,6 +105,7 @@ export function MappingRow({ mapping, onChange, onDelete }: MappingRowProps) {
           <SelectItem value="osc_to_midi">OSC → MIDI</SelectItem>
           <SelectItem value="midi_to_osc">MIDI → OSC</SelectItem>
           <SelectItem value="osc_to_osc">OSC → OSC</SelectItem>
+          <SelectItem value="midi_to_midi">MIDI → MIDI</SelectItem>
         </SelectContent>
       </Select>
 
@@ -84,14 +126,35 @@ export function MappingRow({ mapping, onChange, onDelete }: MappingRowProps) {
             {/* Output fields */}
             {isOscToMidi ? (
               <MidiOutputFields mapping={mapping} onChange={onChange} />
+            ) : isMidiToMidi ? (
+              <MidiRemapFields mapping={mapping} onChange={onChange} />
             ) : (
               <OscOutputFields mapping={mapping} onChange={onChange} />
             )}
           </>
         )}
+        <ModeFields mapping={mapping} onChange={onChange} />
+        <SlewFields mapping={mapping} onChange={onChange} />
+        <FilterFields mapping={mapping} onChange={onChange} />
+        <ConditionFields mapping={mapping} onChange={onChange} />
+        <MacroFields mapping={mapping} onChange={onChange} />
       </div>
 
-      <div className="shrink-0">
+      <div className="shrink-0 flex items-center">
+        <Button
+          variant="ghost"
+          size="sm"
+          className="h-7 w-7 p-0"
+          title={learning ? "Cancel learn" : "Learn input"}
+          onClick={toggleLearn}
+        >
+          <Crosshair
+            className={cn(
+              "h-3.5 w-3.5",
+              learning ? "text-primary animate-pulse" : "text-muted-foreground",
+            )}
+          />
+        </Button>
         <AlertDialog>
           <AlertDialogTrigger asChild>
             <Button variant="ghost" size="sm" className="h-7 w-7 p-0">
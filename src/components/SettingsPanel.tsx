@@ -1,10 +1,18 @@
 import { useState, useEffect } from "react";
 import { getVersion } from "@tauri-apps/api/app";
-import { Settings, ChevronUp, RefreshCw, Lock, Download, Upload } from "lucide-react";
+import {
+  Settings,
+  ChevronUp,
+  RefreshCw,
+  Lock,
+  Download,
+  Upload,
+  TriangleAlert,
+} from "lucide-react";
 import { Button } from "@/components/ui/button";
 import { Input } from "@/components/ui/input";
 import { ValidatedInput } from "@/components/ui/validated-input";
-import { validatePort } from "@/lib/validators";
+import { validateOscInt, validatePort } from "@/lib/validators";
 import {
   Select,
   SelectContent,
@@ -14,12 +22,18 @@ import {
 } from "@/components/ui/select";
 import { Separator } from "@/components/ui/separator";
 import { Switch } from "@/components/ui/switch";
+import { MidiPortField } from "./mapping/MidiPortField";
 import sndwrksLogo from "../../resources/sndwrks-logo.svg";
 import constructionDino from "../../resources/sndwrks-construction-dino.svg";
 import type {
   Settings as SettingsType,
   OscListenProtocol,
+  OscTcpFraming,
+  OscTcpListenFraming,
   OscSendProtocol,
+  MtcArgFormat,
+  MtcGenerator,
+  MtcRate,
   MidiPort,
   EngineStatus,
 } from "@/types";
@@ -33,10 +47,19 @@ interface SettingsPanelProps {
   engineStatus: EngineStatus;
   onStartEngine: () => void;
   onStopEngine: () => void;
+  /** Mappings disabled by a feedback-loop breaker, with the reason */
+  loopBreakers: Record<string, string>;
+  onResetLoopBreakers: () => void;
   onExport: () => void;
   onImport: () => void;
 }
 
+/** Add or remove an extra MIDI port from a settings list. */
+function togglePort(names: string[], name: string, on: boolean): string[] {
+  const rest = names.filter((n) => n !== name);
+  return on ? [...rest, name] : rest;
+}
+
 export function SettingsPanel({
   settings,
   onUpdateSettings,
@@ -46,6 +69,8 @@ export function SettingsPanel({
   engineStatus,
   onStartEngine,
   onStopEngine,
+  loopBreakers,
+  onResetLoopBreakers,
   onExport,
   onImport,
 }: SettingsPanelProps) {
@@ -59,11 +84,16 @@ export function SettingsPanel({
   if (!settings) return null;
 
   const locked = engineStatus.running;
+  const tripped = Object.values(loopBreakers);
 
   const update = (partial: Partial<SettingsType>) => {
     onUpdateSettings({ ...settings, ...partial });
   };
 
+  const updateMtcGenerator = (partial: Partial<MtcGenerator>) => {
+    update({ mtc_generator: { ...settings.mtc_generator, ...partial } });
+  };
+
   return (
     <div className="border-b border-border">
       {/* Header - always visible */}
@@ -76,6 +106,18 @@ export function SettingsPanel({
           {version && (
             <span className="text-xs text-muted-foreground px-2">v{version}</span>
           )}
+          {tripped.length > 0 && (
+            <Button
+              variant="destructive"
+              size="sm"
+              className="text-xs h-9"
+              title={`${tripped.join("\n")}\n\nClick to re-enable`}
+              onClick={onResetLoopBreakers}
+            >
+              <TriangleAlert className="h-3 w-3" />
+              Reset loop breakers ({tripped.length})
+            </Button>
+          )}
           <div className="flex items-center gap-1.5">
             <span
               className={`inline-block h-2 w-2 rounded-full ${engineStatus.running ? "bg-green-400" : "bg-red-400"}`}
@@ -213,6 +255,115 @@ export function SettingsPanel({
                   </Select>
                 </div>
               </div>
+              <div className="grid grid-cols-3 gap-2">
+                <div>
+                  <label className="text-xs text-muted-foreground">
+                    TCP Listen Framing
+                  </label>
+                  <Select
+                    value={settings.osc_tcp_listen_framing}
+                    disabled={locked || settings.osc_listen_protocol === "udp"}
+                    onValueChange={(v) =>
+                      update({
+                        osc_tcp_listen_framing: v as OscTcpListenFraming,
+                      })
+                    }
+                  >
+                    <SelectTrigger className="h-9 text-xs">
+                      <SelectValue />
+                    </SelectTrigger>
+                    <SelectContent>
+                      <SelectItem value="auto">Auto</SelectItem>
+                      <SelectItem value="slip">SLIP (1.1)</SelectItem>
+                      <SelectItem value="length_prefix">Length Prefix (1.0)</SelectItem>
+                    </SelectContent>
+                  </Select>
+                </div>
+                <div>
+                  <label className="text-xs text-muted-foreground">
+                    TCP Send Framing
+                  </label>
+                  <Select
+                    value={settings.osc_tcp_send_framing}
+                    disabled={locked || settings.osc_send_protocol === "udp"}
+                    onValueChange={(v) =>
+                      update({
+                        osc_tcp_send_framing: v as OscTcpFraming,
+                      })
+                    }
+                  >
+                    <SelectTrigger className="h-9 text-xs">
+                      <SelectValue />
+                    </SelectTrigger>
+                    <SelectContent>
+                      <SelectItem value="slip">SLIP (1.1)</SelectItem>
+                      <SelectItem value="length_prefix">Length Prefix (1.0)</SelectItem>
+                    </SelectContent>
+                  </Select>
+                </div>
+              </div>
+              <div className="grid grid-cols-3 gap-2 items-end">
+                <label className="flex items-center gap-2 text-xs h-9">
+                  <Switch
+                    size="sm"
+                    checked={settings.mtc_output.enabled}
+                    disabled={locked}
+                    onCheckedChange={(checked) =>
+                      update({
+                        mtc_output: {
+                          ...settings.mtc_output,
+                          enabled: checked === true,
+                        },
+                      })
+                    }
+                  />
+                  <span className="text-muted-foreground">MTC to OSC</span>
+                </label>
+                <div>
+                  <label className="text-xs text-muted-foreground">
+                    Timecode Address
+                  </label>
+                  <Input
+                    className="h-9 text-xs font-mono"
+                    value={settings.mtc_output.address}
+                    disabled={locked}
+                    onChange={(e) =>
+                      update({
+                        mtc_output: {
+                          ...settings.mtc_output,
+                          address: e.target.value,
+                        },
+                      })
+                    }
+                  />
+                </div>
+                <div>
+                  <label className="text-xs text-muted-foreground">
+                    Timecode Args
+                  </label>
+                  <Select
+                    value={settings.mtc_output.format}
+                    disabled={locked}
+                    onValueChange={(v) =>
+                      update({
+                        mtc_output: {
+                          ...settings.mtc_output,
+                          format: v as MtcArgFormat,
+                        },
+                      })
+                    }
+                  >
+                    <SelectTrigger className="h-9 text-xs">
+                      <SelectValue />
+                    </SelectTrigger>
+                    <SelectContent>
+                      <SelectItem value="string">String</SelectItem>
+                      <SelectItem value="numeric">Numbers</SelectItem>
+                      <SelectItem value="both">Both</SelectItem>
+                    </SelectContent>
+                  </Select>
+                </div>
+              </div>
             </div>
 
             {/* MIDI Settings */}
@@ -244,6 +395,30 @@ export function SettingsPanel({
                     ))}
                   </SelectContent>
                 </Select>
+                {midiInputs
+                  .filter((p) => p.name !== settings.midi_input_port_name)
+                  .map((p) => (
+                    <label
+                      key={p.index}
+                      className="flex items-center gap-2 text-xs mt-1"
+                    >
+                      <Switch
+                        size="sm"
+                        disabled={locked}
+                        checked={settings.midi_input_port_names.includes(p.name)}
+                        onCheckedChange={(checked) =>
+                          update({
+                            midi_input_port_names: togglePort(
+                              settings.midi_input_port_names,
+                              p.name,
+                              checked === true,
+                            ),
+                          })
+                        }
+                      />
+                      <span className="text-muted-foreground">{p.name}</span>
+                    </label>
+                  ))}
               </div>
               <div>
                 <label className="text-xs text-muted-foreground">
@@ -269,6 +444,79 @@ export function SettingsPanel({
                     ))}
                   </SelectContent>
                 </Select>
+                {midiOutputs
+                  .filter((p) => p.name !== settings.midi_output_port_name)
+                  .map((p) => (
+                    <label
+                      key={p.index}
+                      className="flex items-center gap-2 text-xs mt-1"
+                    >
+                      <Switch
+                        size="sm"
+                        disabled={locked}
+                        checked={settings.midi_output_port_names.includes(p.name)}
+                        onCheckedChange={(checked) =>
+                          update({
+                            midi_output_port_names: togglePort(
+                              settings.midi_output_port_names,
+                              p.name,
+                              checked === true,
+                            ),
+                          })
+                        }
+                      />
+                      <span className="text-muted-foreground">{p.name}</span>
+                    </label>
+                  ))}
+              </div>
+              <div>
+                <label className="flex items-center gap-2 text-xs">
+                  <Switch
+                    size="sm"
+                    checked={settings.mtc_generator.enabled}
+                    disabled={locked}
+                    onCheckedChange={(checked) =>
+                      updateMtcGenerator({ enabled: checked === true })
+                    }
+                  />
+                  <span className="text-muted-foreground">MTC Generator</span>
+                </label>
+                {settings.mtc_generator.enabled && (
+                  <div className="flex items-center gap-1 mt-1">
+                    <Input
+                      className="h-9 text-xs font-mono w-24"
+                      value={settings.mtc_generator.address}
+                      disabled={locked}
+                      title="Transport address prefix: /play, /stop, /locate"
+                      onChange={(e) =>
+                        updateMtcGenerator({ address: e.target.value })
+                      }
+                    />
+                    <Select
+                      value={settings.mtc_generator.rate}
+                      disabled={locked}
+                      onValueChange={(v) =>
+                        updateMtcGenerator({ rate: v as MtcRate })
+                      }
+                    >
+                      <SelectTrigger className="h-9 text-xs w-28">
+                        <SelectValue />
+                      </SelectTrigger>
+                      <SelectContent>
+                        <SelectItem value="fps24">24 fps</SelectItem>
+                        <SelectItem value="fps25">25 fps</SelectItem>
+                        <SelectItem value="fps2997_drop">29.97 drop</SelectItem>
+                        <SelectItem value="fps30">30 fps</SelectItem>
+                      </SelectContent>
+                    </Select>
+                    <MidiPortField
+                      port={settings.mtc_generator.port}
+                      placeholder="Default output"
+                      disabled={locked}
+                      onChange={(port) => updateMtcGenerator({ port })}
+                    />
+                  </div>
+                )}
               </div>
             </div>
 
@@ -313,6 +561,23 @@ export function SettingsPanel({
                 />
                 <span className="text-muted-foreground">Auto-start engine</span>
               </label>
+              <label
+                className="flex items-center gap-2 text-xs"
+                title="Messages per second on one address that trip a mapping's loop breaker (0 = off)"
+              >
+                <span className="text-muted-foreground">Loop limit</span>
+                <ValidatedInput
+                  inputMode="numeric"
+                  className="h-6 w-16 text-xs"
+                  value={settings.loop_max_rate_hz}
+                  validate={(raw) => {
+                    const result = validateOscInt(raw);
+                    return result.valid && result.value >= 0 ? result : { valid: false };
+                  }}
+                  errorMessage="Must be a whole number, 0 or more"
+                  onCommit={(v) => update({ loop_max_rate_hz: v })}
+                />
+              </label>
             </div>
             <div className="flex items-center gap-1">
               <Button
@@ -0,0 +1,48 @@
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from "@/components/ui/select";
+import { ValidatedInput } from "@/components/ui/validated-input";
+import { validateMidi14BitValue } from "@/lib/validators";
+import type { CcMode, Mapping } from "@/types";
+
+interface CcModeFieldsProps {
+  mapping: Mapping;
+  onChange: (mapping: Mapping) => void;
+}
+
+export function CcModeFields({ mapping, onChange }: CcModeFieldsProps) {
+  const isParameter = mapping.cc_mode === "nrpn" || mapping.cc_mode === "rpn";
+
+  return (
+    <>
+      <Select
+        value={mapping.cc_mode}
+        onValueChange={(v) => onChange({ ...mapping, cc_mode: v as CcMode })}
+      >
+        <SelectTrigger className="h-9 text-xs w-24">
+          <SelectValue />
+        </SelectTrigger>
+        <SelectContent>
+          <SelectItem value="seven_bit">7-bit</SelectItem>
+          <SelectItem value="fourteen_bit">14-bit</SelectItem>
+          <SelectItem value="nrpn">NRPN</SelectItem>
+          <SelectItem value="rpn">RPN</SelectItem>
+        </SelectContent>
+      </Select>
+      {isParameter && (
+        <ValidatedInput
+          inputMode="numeric"
+          className="h-9 text-xs w-20"
+          value={mapping.midi_parameter}
+          validate={validateMidi14BitValue}
+          errorMessage="Parameter must be 0–16383"
+          onCommit={(v) => onChange({ ...mapping, midi_parameter: v })}
+        />
+      )}
+    </>
+  );
+}
@@ -0,0 +1,175 @@
+import { Input } from "@/components/ui/input";
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from "@/components/ui/select";
+import { ValidatedInput } from "@/components/ui/validated-input";
+import { Button } from "@/components/ui/button";
+import { formatConditionValue, parseConditionValue } from "@/lib/conditions";
+import { validateOscFloat } from "@/lib/validators";
+import type { Comparison, Condition, ConditionValue, Mapping } from "@/types";
+import { Plus, X } from "lucide-react";
+
+interface ConditionFieldsProps {
+  mapping: Mapping;
+  onChange: (mapping: Mapping) => void;
+}
+
+const COMPARISONS: { value: Comparison; label: string }[] = [
+  { value: "eq", label: "=" },
+  { value: "neq", label: "≠" },
+  { value: "lt", label: "<" },
+  { value: "le", label: "≤" },
+  { value: "gt", label: ">" },
+  { value: "ge", label: "≥" },
+];
+
+function subject(condition: Condition): ConditionValue {
+  return condition.op === "compare" ? condition.left : condition.value;
+}
+
+/** Change a condition's operator, keeping the value it tests. */
+function withOp(condition: Condition, op: string): Condition {
+  const value = subject(condition);
+  if (op === "range") return { op: "range", value, min: 0, max: 1 };
+  if (op === "regex") return { op: "regex", value, pattern: ".*" };
+  const right =
+    condition.op === "compare"
+      ? condition.right
+      : ({ type: "static", value: 0.5 } as ConditionValue);
+  return { op: "compare", left: value, cmp: op as Comparison, right };
+}
+
+export function ConditionFields({ mapping, onChange }: ConditionFieldsProps) {
+  const fromMidi = mapping.direction.startsWith("midi_");
+
+  const update = (index: number, condition: Condition) => {
+    const conditions = [...mapping.conditions];
+    conditions[index] = condition;
+    onChange({ ...mapping, conditions });
+  };
+
+  const add = () => {
+    const condition: Condition = {
+      op: "compare",
+      left: fromMidi ? { type: "midi_value" } : { type: "osc_arg", index: 0 },
+      cmp: "gt",
+      right: { type: "static", value: fromMidi ? 63 : 0.5 },
+    };
+    onChange({ ...mapping, conditions: [...mapping.conditions, condition] });
+  };
+
+  const remove = (index: number) => {
+    onChange({
+      ...mapping,
+      conditions: mapping.conditions.filter((_, i) => i !== index),
+    });
+  };
+
+  return (
+    <div className="flex flex-col gap-1">
+      {mapping.conditions.map((condition, i) => (
+        <div key={i} className="flex items-center gap-1">
+          <span className="text-xs text-muted-foreground w-6">
+            {i === 0 ? "If" : "and"}
+          </span>
+          <Input
+            className="h-9 text-xs w-16 font-mono"
+            title="arg0, value, note or a constant"
+            value={formatConditionValue(subject(condition))}
+            onChange={(e) => {
+              const value = parseConditionValue(e.target.value);
+              update(
+                i,
+                condition.op === "compare"
+                  ? { ...condition, left: value }
+                  : { ...condition, value },
+              );
+            }}
+          />
+          <Select
+            value={condition.op === "compare" ? condition.cmp : condition.op}
+            onValueChange={(v) => update(i, withOp(condition, v))}
+          >
+            <SelectTrigger className="h-9 text-xs w-24">
+              <SelectValue />
+            </SelectTrigger>
+            <SelectContent>
+              {COMPARISONS.map(({ value, label }) => (
+                <SelectItem key={value} value={value}>
+                  {label}
+                </SelectItem>
+              ))}
+              <SelectItem value="range">in range</SelectItem>
+              <SelectItem value="regex">matches</SelectItem>
+            </SelectContent>
+          </Select>
+          {condition.op === "compare" && (
+            <Input
+              className="h-9 text-xs w-20 font-mono"
+              value={formatConditionValue(condition.right)}
+              onChange={(e) =>
+                update(i, {
+                  ...condition,
+                  right: parseConditionValue(e.target.value),
+                })
+              }
+            />
+          )}
+          {condition.op === "range" && (
+            <>
+              <ValidatedInput
+                inputMode="decimal"
+                className="h-9 text-xs w-16 font-mono"
+                value={condition.min}
+                validate={validateOscFloat}
+                errorMessage="Must be a number"
+                onCommit={(min) => update(i, { ...condition, min })}
+                placeholder="min"
+              />
+              <ValidatedInput
+                inputMode="decimal"
+                className="h-9 text-xs w-16 font-mono"
+                value={condition.max}
+                validate={validateOscFloat}
+                errorMessage="Must be a number"
+                onCommit={(max) => update(i, { ...condition, max })}
+                placeholder="max"
+              />
+            </>
+          )}
+          {condition.op === "regex" && (
+            <Input
+              className="h-9 text-xs w-32 font-mono"
+              placeholder="regex"
+              value={condition.pattern}
+              onChange={(e) =>
+                update(i, { ...condition, pattern: e.target.value })
+              }
+            />
+          )}
+          <Button
+            variant="ghost"
+            size="sm"
+            className="h-7 w-7 p-0"
+            onClick={() => remove(i)}
+          >
+            <X className="h-3 w-3" />
+          </Button>
+        </div>
+      ))}
+      <Button
+        variant="ghost"
+        size="sm"
+        className="h-7 text-xs gap-0.5 self-start"
+        onClick={add}
+      >
+        <Plus className="h-3 w-3" />
+        If
+      </Button>
+    </div>
+  );
+}
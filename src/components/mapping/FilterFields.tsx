@@ -0,0 +1,84 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { Input } from "@/components/ui/input";
+import { Switch } from "@/components/ui/switch";
+import type { DropCount, Mapping, OutputFilter } from "@/types";
+
+interface FilterFieldsProps {
+  mapping: Mapping;
+  onChange: (mapping: Mapping) => void;
+}
+
+const DROP_POLL_MS = 2000;
+
+export function FilterFields({ mapping, onChange }: FilterFieldsProps) {
+  const filter = mapping.output_filter;
+  const active = filter.max_rate_hz !== null || filter.dedupe || filter.deadband > 0;
+  const [drops, setDrops] = useState<DropCount | null>(null);
+
+  useEffect(() => {
+    if (!active) {
+      setDrops(null);
+      return;
+    }
+    const poll = () =>
+      invoke<Record<string, DropCount>>("get_drop_counts")
+        .then((counts) => setDrops(counts[mapping.id] ?? null))
+        .catch(() => setDrops(null));
+    poll();
+    const timer = setInterval(poll, DROP_POLL_MS);
+    return () => clearInterval(timer);
+  }, [active, mapping.id]);
+
+  const update = (patch: Partial<OutputFilter>) =>
+    onChange({ ...mapping, output_filter: { ...filter, ...patch } });
+
+  const dropped = drops ? drops.throttled + drops.duplicate + drops.deadband : 0;
+
+  return (
+    <div className="flex items-center gap-1">
+      <Input
+        type="number"
+        min={0}
+        className="h-9 text-xs w-16"
+        placeholder="Hz"
+        title="Most messages per second (empty for no limit)"
+        value={filter.max_rate_hz ?? ""}
+        onChange={(e) =>
+          update({
+            max_rate_hz: e.target.value === "" ? null : Number(e.target.value),
+          })
+        }
+      />
+      <Input
+        type="number"
+        min={0}
+        step="any"
+        className="h-9 text-xs w-16"
+        placeholder="Δ"
+        title="Deadband: drop changes smaller than this"
+        value={filter.deadband || ""}
+        onChange={(e) => update({ deadband: Number(e.target.value) || 0 })}
+      />
+      <label
+        className="flex items-center gap-1 text-xs"
+        title="Drop repeated identical values"
+      >
+        <Switch
+          size="sm"
+          checked={filter.dedupe}
+          onCheckedChange={(checked) => update({ dedupe: checked === true })}
+        />
+        <span className="text-muted-foreground">Changes</span>
+      </label>
+      {drops && dropped > 0 && (
+        <span
+          className="text-xs text-muted-foreground tabular-nums"
+          title={`Rate limit ${drops.throttled}, duplicate ${drops.duplicate}, deadband ${drops.deadband}`}
+        >
+          −{dropped}
+        </span>
+      )}
+    </div>
+  );
+}
@@ -0,0 +1,224 @@
+import { Input } from "@/components/ui/input";
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from "@/components/ui/select";
+import { Button } from "@/components/ui/button";
+import type {
+  MacroOutput,
+  MacroStep,
+  Mapping,
+  MidiMessageType,
+  Retrigger,
+} from "@/types";
+import { Plus, X } from "lucide-react";
+
+interface MacroFieldsProps {
+  mapping: Mapping;
+  onChange: (mapping: Mapping) => void;
+}
+
+const MIDI_TYPES: { value: MidiMessageType; label: string }[] = [
+  { value: "note_on", label: "Note On" },
+  { value: "note_off", label: "Note Off" },
+  { value: "cc", label: "CC" },
+  { value: "program_change", label: "Program" },
+];
+
+function defaultOutput(type: MacroOutput["type"]): MacroOutput {
+  return type === "osc"
+    ? { type: "osc", address: "/", args: [], destinations: [] }
+    : {
+        type: "midi",
+        message_type: "cc",
+        channel: 1,
+        note_or_cc: 0,
+        value: 127,
+        port: null,
+      };
+}
+
+/** Parse a whole number from an input, clamped to `min`–`max`. */
+function clampInt(raw: string, min: number, max: number): number {
+  const n = Math.round(Number(raw));
+  return Number.isFinite(n) ? Math.min(max, Math.max(min, n)) : min;
+}
+
+export function MacroFields({ mapping, onChange }: MacroFieldsProps) {
+  const update = (index: number, step: MacroStep) => {
+    const steps = [...mapping.steps];
+    steps[index] = step;
+    onChange({ ...mapping, steps });
+  };
+
+  const updateOutput = (index: number, output: MacroOutput) =>
+    update(index, { ...mapping.steps[index], output });
+
+  const add = () => {
+    const step: MacroStep = { delay_ms: 0, output: defaultOutput("osc") };
+    onChange({ ...mapping, steps: [...mapping.steps, step] });
+  };
+
+  const remove = (index: number) => {
+    onChange({
+      ...mapping,
+      steps: mapping.steps.filter((_, i) => i !== index),
+    });
+  };
+
+  return (
+    <div className="flex flex-col gap-1">
+      {mapping.steps.map((step, i) => (
+        <div key={i} className="flex items-center gap-1">
+          <span className="text-xs text-muted-foreground">then wait</span>
+          <Input
+            inputMode="numeric"
+            className="h-9 text-xs w-16 font-mono"
+            title="Delay after the previous output (ms)"
+            value={step.delay_ms}
+            onChange={(e) =>
+              update(i, {
+                ...step,
+                delay_ms: clampInt(e.target.value, 0, 600000),
+              })
+            }
+          />
+          <span className="text-xs text-muted-foreground">ms, send</span>
+          <Select
+            value={step.output.type}
+            onValueChange={(v) =>
+              updateOutput(i, defaultOutput(v as MacroOutput["type"]))
+            }
+          >
+            <SelectTrigger className="h-9 text-xs w-20">
+              <SelectValue />
+            </SelectTrigger>
+            <SelectContent>
+              <SelectItem value="osc">OSC</SelectItem>
+              <SelectItem value="midi">MIDI</SelectItem>
+            </SelectContent>
+          </Select>
+          {step.output.type === "osc" ? (
+            <Input
+              className="h-9 text-xs w-40 font-mono"
+              placeholder="/address"
+              value={step.output.address}
+              onChange={(e) =>
+                step.output.type === "osc" &&
+                updateOutput(i, { ...step.output, address: e.target.value })
+              }
+            />
+          ) : (
+            <MidiStepFields
+              output={step.output}
+              onChange={(output) => updateOutput(i, output)}
+            />
+          )}
+          <Button
+            variant="ghost"
+            size="sm"
+            className="h-7 w-7 p-0"
+            onClick={() => remove(i)}
+          >
+            <X className="h-3 w-3" />
+          </Button>
+        </div>
+      ))}
+      <div className="flex items-center gap-1">
+        <Button
+          variant="ghost"
+          size="sm"
+          className="h-7 text-xs gap-0.5"
+          onClick={add}
+        >
+          <Plus className="h-3 w-3" />
+          Step
+        </Button>
+        {mapping.steps.length > 0 && (
+          <Select
+            value={mapping.retrigger}
+            onValueChange={(v) =>
+              onChange({ ...mapping, retrigger: v as Retrigger })
+            }
+          >
+            <SelectTrigger
+              className="h-7 text-xs w-36"
+              title="What a new trigger does while the steps are running"
+            >
+              <SelectValue />
+            </SelectTrigger>
+            <SelectContent>
+              <SelectItem value="cancel">Retrigger cancels</SelectItem>
+              <SelectItem value="queue">Retrigger queues</SelectItem>
+            </SelectContent>
+          </Select>
+        )}
+      </div>
+    </div>
+  );
+}
+
+type MidiOutput = Extract<MacroOutput, { type: "midi" }>;
+
+function MidiStepFields({
+  output,
+  onChange,
+}: {
+  output: MidiOutput;
+  onChange: (output: MidiOutput) => void;
+}) {
+  return (
+    <>
+      <Select
+        value={output.message_type}
+        onValueChange={(v) =>
+          onChange({ ...output, message_type: v as MidiMessageType })
+        }
+      >
+        <SelectTrigger className="h-9 text-xs w-24">
+          <SelectValue />
+        </SelectTrigger>
+        <SelectContent>
+          {MIDI_TYPES.map(({ value, label }) => (
+            <SelectItem key={value} value={value}>
+              {label}
+            </SelectItem>
+          ))}
+        </SelectContent>
+      </Select>
+      <Input
+        inputMode="numeric"
+        className="h-9 text-xs w-12 font-mono"
+        title="Channel"
+        value={output.channel}
+        onChange={(e) =>
+          onChange({ ...output, channel: clampInt(e.target.value, 1, 16) })
+        }
+      />
+      <Input
+        inputMode="numeric"
+        className="h-9 text-xs w-12 font-mono"
+        title="Note, CC or program number"
+        value={output.note_or_cc}
+        onChange={(e) =>
+          onChange({
+            ...output,
+            note_or_cc: clampInt(e.target.value, 0, 127),
+          })
+        }
+      />
+      <Input
+        inputMode="numeric"
+        className="h-9 text-xs w-12 font-mono"
+        title="Value"
+        value={output.value}
+        onChange={(e) =>
+          onChange({ ...output, value: clampInt(e.target.value, 0, 127) })
+        }
+      />
+    </>
+  );
+}
@@ -0,0 +1,47 @@
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from "@/components/ui/select";
+import { formatMidiField, validateMidiField } from "@/lib/midi";
+import { validateMidiValue } from "@/lib/validators";
+import type { MidiField } from "@/types";
+
+interface MidiChannelFieldProps {
+  channel: MidiField;
+  /** Offer reading the channel from OSC arg 0 or address capture 0 */
+  dynamic?: boolean;
+  onChange: (channel: MidiField) => void;
+}
+
+/** MIDI channel 1–16, or a channel read from each OSC message. */
+export function MidiChannelField({ channel, dynamic, onChange }: MidiChannelFieldProps) {
+  const value = formatMidiField(channel);
+  const options = Array.from({ length: 16 }, (_, i) => String(i + 1));
+  if (dynamic) options.push("arg0", "cap0");
+  // Keep a saved dynamic channel selectable so it isn't overwritten
+  if (!options.includes(value)) options.push(value);
+
+  return (
+    <Select
+      value={value}
+      onValueChange={(v) => {
+        const result = validateMidiField(v, validateMidiValue);
+        if (result.valid) onChange(result.value);
+      }}
+    >
+      <SelectTrigger className="h-9 text-xs w-24">
+        <SelectValue />
+      </SelectTrigger>
+      <SelectContent>
+        {options.map((option) => (
+          <SelectItem key={option} value={option}>
+            Ch {option}
+          </SelectItem>
+        ))}
+      </SelectContent>
+    </Select>
+  );
+}
@@ -6,9 +6,24 @@ import {
   SelectValue,
 } from "@/components/ui/select";
 import { ValidatedInput } from "@/components/ui/validated-input";
-import { midiNoteToName } from "@/lib/midi";
-import { validateMidiNote, validateMidiValue } from "@/lib/validators";
-import type { Mapping, MidiMessageType, MscCommand, MscCommandFormat } from "@/types";
+import { formatMidiField, midiNoteToName, validateMidiField } from "@/lib/midi";
+import {
+  validateMidi14BitValue,
+  validateMidiNote,
+  validateMidiValue,
+} from "@/lib/validators";
+import { CLOCK_INPUTS, MSC_COMMANDS, MSC_COMMAND_FORMATS } from "@/types";
+import type {
+  ClockInput,
+  Mapping,
+  MidiMessageType,
+  MscCommand,
+  MscCommandFormat,
+  TransportState,
+} from "@/types";
+import { CcModeFields } from "./CcModeFields";
+import { MidiChannelField } from "./MidiChannelField";
+import { MidiPortField } from "./MidiPortField";
 
 interface MidiInputFieldsProps {
   mapping: Mapping;
@@ -19,14 +34,30 @@ export function MidiInputFields({ mapping, onChange }: MidiInputFieldsProps) {
   const isCC = mapping.midi_message_type === "cc";
   const isPC = mapping.midi_message_type === "program_change";
   const isMsc = mapping.midi_message_type === "msc";
+  const isClock = mapping.clock_input !== null;
+  const isPitchBend = mapping.midi_message_type === "pitch_bend";
+  const is14Bit = isPitchBend || (isCC && mapping.cc_mode !== "seven_bit");
+  const hasNote =
+    !isPitchBend && mapping.midi_message_type !== "channel_pressure";
   const velocityMode = mapping.midi_input_velocity !== null ? "exact" : "any";
 
   return (
     <div className="flex items-center gap-1">
+      <MidiPortField
+        port={mapping.midi_input_port}
+        placeholder="Any port"
+        onChange={(midi_input_port) => onChange({ ...mapping, midi_input_port })}
+      />
       <Select
-        value={mapping.midi_message_type}
+        value={mapping.clock_input ?? mapping.midi_message_type}
         onValueChange={(v) =>
-          onChange({ ...mapping, midi_message_type: v as MidiMessageType })
+          CLOCK_INPUTS.includes(v as ClockInput)
+            ? onChange({ ...mapping, clock_input: v as ClockInput })
+            : onChange({
+                ...mapping,
+                midi_message_type: v as MidiMessageType,
+                clock_input: null,
+              })
         }
       >
         <SelectTrigger className="h-9 text-xs w-38">
@@ -37,10 +68,42 @@ export function MidiInputFields({ mapping, onChange }: MidiInputFieldsProps) {
           <SelectItem value="note_off">Note Off</SelectItem>
           <SelectItem value="cc">CC</SelectItem>
           <SelectItem value="program_change">Program Change</SelectItem>
+          <SelectItem value="pitch_bend">Pitch Bend</SelectItem>
+          <SelectItem value="channel_pressure">Channel Pressure</SelectItem>
+          <SelectItem value="poly_aftertouch">Poly Aftertouch</SelectItem>
           <SelectItem value="msc">MSC</SelectItem>
+          {mapping.direction === "midi_to_osc" && (
+            <>
+              <SelectItem value="tempo">Clock Tempo</SelectItem>
+              <SelectItem value="beat">Clock Beat</SelectItem>
+              <SelectItem value="transport">Transport</SelectItem>
+            </>
+          )}
         </SelectContent>
       </Select>
-      {isMsc ? (
+      {isClock ? (
+        mapping.clock_input === "transport" && (
+          <Select
+            value={mapping.transport_state ?? "any"}
+            onValueChange={(v) =>
+              onChange({
+                ...mapping,
+                transport_state: v === "any" ? null : (v as TransportState),
+              })
+            }
+          >
+            <SelectTrigger className="h-9 text-xs w-28">
+              <SelectValue />
+            </SelectTrigger>
+            <SelectContent>
+              <SelectItem value="any">Any</SelectItem>
+              <SelectItem value="start">Start</SelectItem>
+              <SelectItem value="continue">Continue</SelectItem>
+              <SelectItem value="stop">Stop</SelectItem>
+            </SelectContent>
+          </Select>
+        )
+      ) : isMsc ? (
         <>
           <Select
             value={mapping.msc_command ?? "any"}
@@ -56,9 +119,11 @@ export function MidiInputFields({ mapping, onChange }: MidiInputFieldsProps) {
             </SelectTrigger>
             <SelectContent>
               <SelectItem value="any">Any Cmd</SelectItem>
-              <SelectItem value="go">GO</SelectItem>
-              <SelectItem value="stop">STOP</SelectItem>
-              <SelectItem value="resume">RESUME</SelectItem>
+              {MSC_COMMANDS.map(({ value, label }) => (
+                <SelectItem key={value} value={value}>
+                  {label}
+                </SelectItem>
+              ))}
             </SelectContent>
           </Select>
           <Select
@@ -75,9 +140,11 @@ export function MidiInputFields({ mapping, onChange }: MidiInputFieldsProps) {
             </SelectTrigger>
             <SelectContent>
               <SelectItem value="any">Any Type</SelectItem>
-              <SelectItem value="all">All Types</SelectItem>
-              <SelectItem value="lighting">Lighting</SelectItem>
-              <SelectItem value="sound">Sound</SelectItem>
+              {MSC_COMMAND_FORMATS.map(({ value, label }) => (
+                <SelectItem key={value} value={value}>
+                  {label}
+                </SelectItem>
+              ))}
             </SelectContent>
           </Select>
           <Select
@@ -104,18 +171,27 @@ export function MidiInputFields({ mapping, onChange }: MidiInputFieldsProps) {
         </>
       ) : (
         <>
-          <div className="flex items-center gap-1">
-            <ValidatedInput
-              className="h-9 text-xs w-18"
-              value={mapping.midi_note_or_cc}
-              validate={validateMidiNote}
-              errorMessage="Note must be 0–127 or a note name (e.g. C3)"
-              onCommit={(v) => onChange({ ...mapping, midi_note_or_cc: v })}
-            />
-            <span className="text-xs text-muted-foreground w-8">
-              {isCC ? "CC" : isPC ? "Pgm" : midiNoteToName(mapping.midi_note_or_cc)}
-            </span>
-          </div>
+          {isCC && <CcModeFields mapping={mapping} onChange={onChange} />}
+          {hasNote && (
+            <div className="flex items-center gap-1">
+              <ValidatedInput
+                className="h-9 text-xs w-18"
+                value={formatMidiField(mapping.midi_note_or_cc)}
+                validate={(raw) => validateMidiField(raw, validateMidiNote)}
+                errorMessage="Note must be 0–127 or a note name (e.g. C3)"
+                onCommit={(v) => onChange({ ...mapping, midi_note_or_cc: v })}
+              />
+              <span className="text-xs text-muted-foreground w-8">
+                {isCC
+                  ? "CC"
+                  : isPC
+                    ? "Pgm"
+                    : typeof mapping.midi_note_or_cc === "number"
+                      ? midiNoteToName(mapping.midi_note_or_cc)
+                      : "Any"}
+              </span>
+            </div>
+          )}
           {!isPC && (
             <Select
               value={velocityMode}
@@ -140,30 +216,17 @@ export function MidiInputFields({ mapping, onChange }: MidiInputFieldsProps) {
               inputMode="numeric"
               className="h-9 text-xs w-18"
               value={mapping.midi_input_velocity ?? 0}
-              validate={validateMidiValue}
-              errorMessage="Value must be 0–127"
+              validate={is14Bit ? validateMidi14BitValue : validateMidiValue}
+              errorMessage={is14Bit ? "Value must be 0–16383" : "Value must be 0–127"}
               onCommit={(v) =>
                 onChange({ ...mapping, midi_input_velocity: v })
               }
             />
           )}
-          <Select
-            value={String(mapping.midi_channel)}
-            onValueChange={(v) =>
-              onChange({ ...mapping, midi_channel: parseInt(v) })
-            }
-          >
-            <SelectTrigger className="h-9 text-xs w-24">
-              <SelectValue />
-            </SelectTrigger>
-            <SelectContent>
-              {Array.from({ length: 16 }, (_, i) => (
-                <SelectItem key={i + 1} value={String(i + 1)}>
-                  Ch {i + 1}
-                </SelectItem>
-              ))}
-            </SelectContent>
-          </Select>
+          <MidiChannelField
+            channel={mapping.midi_channel}
+            onChange={(midi_channel) => onChange({ ...mapping, midi_channel })}
+          />
         </>
       )}
     </div>
@@ -6,9 +6,13 @@ import {
   SelectValue,
 } from "@/components/ui/select";
 import { ValidatedInput } from "@/components/ui/validated-input";
-import { midiNoteToName } from "@/lib/midi";
-import { validateMidiNote, validateMidiValue } from "@/lib/validators";
+import { formatMidiField, midiNoteToName, validateMidiField } from "@/lib/midi";
+import { validateMidi14BitValue, validateMidiNote, validateMidiValue } from "@/lib/validators";
 import type { Mapping, MidiMessageType, ValueSource } from "@/types";
+import { CcModeFields } from "./CcModeFields";
+import { MidiChannelField } from "./MidiChannelField";
+import { MidiPortField } from "./MidiPortField";
+import { MscOutputFields } from "./MscOutputFields";
 
 interface MidiOutputFieldsProps {
   mapping: Mapping;
@@ -18,12 +22,17 @@ interface MidiOutputFieldsProps {
 export function MidiOutputFields({ mapping, onChange }: MidiOutputFieldsProps) {
   const isCC = mapping.midi_message_type === "cc";
   const isPC = mapping.midi_message_type === "program_change";
+  const isMsc = mapping.midi_message_type === "msc";
+  const isPitchBend = mapping.midi_message_type === "pitch_bend";
+  const is14Bit = isPitchBend || (isCC && mapping.cc_mode !== "seven_bit");
   const valueSource = mapping.midi_velocity_or_value;
 
   const handleValueSourceChange = (type: string) => {
     let vs: ValueSource;
     if (type === "static") {
-      vs = { type: "static", value: isCC ? 0 : 127 };
+      vs = { type: "static", value: isPitchBend ? 8192 : isCC ? 0 : 127 };
+    } else if (type === "capture") {
+      vs = { type: "capture", index: 0 };
     } else {
       vs = { type: "osc_arg", index: 0 };
     }
@@ -32,10 +41,20 @@ export function MidiOutputFields({ mapping, onChange }: MidiOutputFieldsProps) {
 
   return (
     <div className="flex items-center gap-1">
+      <MidiPortField
+        port={mapping.midi_output_port}
+        placeholder="Default port"
+        onChange={(midi_output_port) => onChange({ ...mapping, midi_output_port })}
+      />
       <Select
         value={mapping.midi_message_type}
         onValueChange={(v) =>
-          onChange({ ...mapping, midi_message_type: v as MidiMessageType })
+          onChange({
+            ...mapping,
+            midi_message_type: v as MidiMessageType,
+            // MSC output needs a command; start from GO
+            msc_command: v === "msc" ? (mapping.msc_command ?? "go") : mapping.msc_command,
+          })
         }
       >
         <SelectTrigger className="h-7 text-xs w-38">
@@ -46,66 +65,87 @@ export function MidiOutputFields({ mapping, onChange }: MidiOutputFieldsProps) {
           <SelectItem value="note_off">Note Off</SelectItem>
           <SelectItem value="cc">CC</SelectItem>
           <SelectItem value="program_change">Program Change</SelectItem>
+          <SelectItem value="pitch_bend">Pitch Bend</SelectItem>
+          <SelectItem value="channel_pressure">Channel Pressure</SelectItem>
+          <SelectItem value="poly_aftertouch">Poly Aftertouch</SelectItem>
+          <SelectItem value="msc">MSC</SelectItem>
         </SelectContent>
       </Select>
-      <div className="flex items-center gap-1">
-        <ValidatedInput
-          className="h-9 text-xs w-18"
-          value={mapping.midi_note_or_cc}
-          validate={validateMidiNote}
-          errorMessage="Note must be 0–127 or a note name (e.g. C3)"
-          onCommit={(v) => onChange({ ...mapping, midi_note_or_cc: v })}
-        />
-        <span className="text-xs text-muted-foreground w-8">
-          {isCC ? "CC" : isPC ? "Pgm" : midiNoteToName(mapping.midi_note_or_cc)}
-        </span>
-      </div>
-      {!isPC && (
-        <Select
-          value={valueSource.type}
-          onValueChange={handleValueSourceChange}
-        >
-          <SelectTrigger className="h-9 text-xs w-28">
-            <SelectValue />
-          </SelectTrigger>
-          <SelectContent>
-            <SelectItem value="static">Static</SelectItem>
-            <SelectItem value="osc_arg">From OSC Arg 0</SelectItem>
-          </SelectContent>
-        </Select>
+      {isMsc ? (
+        <MscOutputFields mapping={mapping} onChange={onChange} />
+      ) : (
+        <>
+          {isCC && <CcModeFields mapping={mapping} onChange={onChange} />}
+          <div className="flex items-center gap-1">
+            <ValidatedInput
+              className="h-9 text-xs w-18"
+              value={formatMidiField(mapping.midi_note_or_cc)}
+              validate={(raw) => validateMidiField(raw, validateMidiNote)}
+              errorMessage="Note must be 0–127, a note name (e.g. C3), argN or capN"
+              onCommit={(v) => onChange({ ...mapping, midi_note_or_cc: v })}
+            />
+            <span className="text-xs text-muted-foreground w-8">
+              {isCC
+                ? "CC"
+                : isPC
+                  ? "Pgm"
+                  : typeof mapping.midi_note_or_cc === "number"
+                    ? midiNoteToName(mapping.midi_note_or_cc)
+                    : ""}
+            </span>
+          </div>
+          {!isPC && (
+            <Select
+              value={valueSource.type}
+              onValueChange={handleValueSourceChange}
+            >
+              <SelectTrigger className="h-9 text-xs w-28">
+                <SelectValue />
+              </SelectTrigger>
+              <SelectContent>
+                <SelectItem value="static">Static</SelectItem>
+                <SelectItem value="osc_arg">From OSC Arg</SelectItem>
+                <SelectItem value="capture">From Capture</SelectItem>
+              </SelectContent>
+            </Select>
+          )}
+          {!isPC && valueSource.type !== "static" && (
+            <ValidatedInput
+              inputMode="numeric"
+              className="h-9 text-xs w-12"
+              value={valueSource.index}
+              validate={validateMidiValue}
+              errorMessage="Index must be 0–127"
+              onCommit={(index) =>
+                onChange({
+                  ...mapping,
+                  midi_velocity_or_value: { type: valueSource.type, index },
+                })
+              }
+            />
+          )}
+          {!isPC && valueSource.type === "static" && (
+            <ValidatedInput
+              inputMode="numeric"
+              className="h-9 text-xs w-18"
+              value={valueSource.value}
+              validate={is14Bit ? validateMidi14BitValue : validateMidiValue}
+              errorMessage={is14Bit ? "Value must be 0–16383" : "Value must be 0–127"}
+              onCommit={(v) =>
+                onChange({
+                  ...mapping,
+                  midi_velocity_or_value: { type: "static", value: v },
+                })
+              }
+            />
+          )}
+          <MidiChannelField
+            channel={mapping.midi_channel}
+            dynamic
+            onChange={(midi_channel) => onChange({ ...mapping, midi_channel })}
+          />
+        </>
       )}
-      {!isPC && valueSource.type === "static" && (
-        <ValidatedInput
-          inputMode="numeric"
-          className="h-9 text-xs w-18"
-          value={valueSource.value}
-          validate={validateMidiValue}
-          errorMessage="Value must be 0–127"
-          onCommit={(v) =>
-            onChange({
-              ...mapping,
-              midi_velocity_or_value: { type: "static", value: v },
-            })
-          }
-        />
-      )}
-      <Select
-        value={String(mapping.midi_channel)}
-        onValueChange={(v) =>
-          onChange({ ...mapping, midi_channel: parseInt(v) })
-        }
-      >
-        <SelectTrigger className="h-9 text-xs w-24">
-          <SelectValue />
-        </SelectTrigger>
-        <SelectContent>
-          {Array.from({ length: 16 }, (_, i) => (
-            <SelectItem key={i + 1} value={String(i + 1)}>
-              Ch {i + 1}
-            </SelectItem>
-          ))}
-        </SelectContent>
-      </Select>
     </div>
   );
 }
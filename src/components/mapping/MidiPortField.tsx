@@ -0,0 +1,22 @@
+import { Input } from "@/components/ui/input";
+
+interface MidiPortFieldProps {
+  port: string | null;
+  placeholder: string;
+  disabled?: boolean;
+  onChange: (port: string | null) => void;
+}
+
+/** Optional MIDI port name; blank leaves the choice to the engine. */
+export function MidiPortField({ port, placeholder, disabled, onChange }: MidiPortFieldProps) {
+  return (
+    <Input
+      className="h-9 text-xs w-28"
+      placeholder={placeholder}
+      value={port ?? ""}
+      disabled={disabled}
+      onChange={(e) => onChange(e.target.value || null)}
+      title="MIDI port name"
+    />
+  );
+}
@@ -0,0 +1,172 @@
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from "@/components/ui/select";
+import { ValidatedInput } from "@/components/ui/validated-input";
+import { validateMidi14BitValue, validateOscFloat } from "@/lib/validators";
+import type {
+  Mapping,
+  MidiMessageType,
+  MidiRemap,
+  OscTransform,
+  TransformCurve,
+} from "@/types";
+import { MidiPortField } from "./MidiPortField";
+
+const defaultTransform: OscTransform = {
+  curve: "linear",
+  input_min: 0,
+  input_max: 127,
+  output_min: 0,
+  output_max: 127,
+  calibration_points: [],
+  output_type: "auto",
+  smoothing: 1,
+};
+
+interface MidiRemapFieldsProps {
+  mapping: Mapping;
+  onChange: (mapping: Mapping) => void;
+}
+
+/** Output side of a MIDI → MIDI mapping. "Same" keeps the incoming value. */
+export function MidiRemapFields({ mapping, onChange }: MidiRemapFieldsProps) {
+  const remap = mapping.midi_remap;
+  const isMsc = mapping.midi_message_type === "msc";
+  const transform = mapping.osc_transform;
+
+  const updateRemap = (next: Partial<MidiRemap>) =>
+    onChange({ ...mapping, midi_remap: { ...remap, ...next } });
+
+  const updateTransformField = (field: keyof OscTransform, value: number) =>
+    onChange({
+      ...mapping,
+      osc_transform: { ...(transform ?? defaultTransform), [field]: value },
+    });
+
+  return (
+    <div className="flex items-center gap-1">
+      <MidiPortField
+        port={mapping.midi_output_port}
+        placeholder="Default port"
+        onChange={(midi_output_port) => onChange({ ...mapping, midi_output_port })}
+      />
+      {isMsc ? (
+        <span className="text-xs text-muted-foreground">MSC passthrough</span>
+      ) : (
+        <>
+          <Select
+            value={remap.message_type ?? "same"}
+            onValueChange={(v) =>
+              updateRemap({
+                message_type: v === "same" ? null : (v as MidiMessageType),
+              })
+            }
+          >
+            <SelectTrigger className="h-9 text-xs w-38">
+              <SelectValue />
+            </SelectTrigger>
+            <SelectContent>
+              <SelectItem value="same">Same Type</SelectItem>
+              <SelectItem value="note_on">Note On</SelectItem>
+              <SelectItem value="note_off">Note Off</SelectItem>
+              <SelectItem value="cc">CC</SelectItem>
+              <SelectItem value="program_change">Program Change</SelectItem>
+              <SelectItem value="pitch_bend">Pitch Bend</SelectItem>
+              <SelectItem value="channel_pressure">Channel Pressure</SelectItem>
+              <SelectItem value="poly_aftertouch">Poly Aftertouch</SelectItem>
+            </SelectContent>
+          </Select>
+          <Select
+            value={remap.number === null ? "same" : "set"}
+            onValueChange={(v) =>
+              updateRemap({ number: v === "same" ? null : 0 })
+            }
+          >
+            <SelectTrigger className="h-9 text-xs w-24">
+              <SelectValue />
+            </SelectTrigger>
+            <SelectContent>
+              <SelectItem value="same">Same #</SelectItem>
+              <SelectItem value="set">Set #</SelectItem>
+            </SelectContent>
+          </Select>
+          {remap.number !== null && (
+            <ValidatedInput
+              inputMode="numeric"
+              className="h-9 text-xs w-18"
+              value={remap.number}
+              validate={validateMidi14BitValue}
+              errorMessage="Number must be 0–16383"
+              onCommit={(v) => updateRemap({ number: v })}
+            />
+          )}
+          <Select
+            value={remap.channel === null ? "same" : String(remap.channel)}
+            onValueChange={(v) =>
+              updateRemap({ channel: v === "same" ? null : parseInt(v) })
+            }
+          >
+            <SelectTrigger className="h-9 text-xs w-24">
+              <SelectValue />
+            </SelectTrigger>
+            <SelectContent>
+              <SelectItem value="same">Same Ch</SelectItem>
+              {Array.from({ length: 16 }, (_, i) => (
+                <SelectItem key={i + 1} value={String(i + 1)}>
+                  Ch {i + 1}
+                </SelectItem>
+              ))}
+            </SelectContent>
+          </Select>
+          <Select
+            value={transform?.curve ?? "none"}
+            onValueChange={(v) =>
+              onChange({
+                ...mapping,
+                osc_transform:
+                  v === "none"
+                    ? null
+                    : {
+                        ...(transform ?? defaultTransform),
+                        curve: v as TransformCurve,
+                      },
+              })
+            }
+          >
+            <SelectTrigger className="h-9 text-xs w-28">
+              <SelectValue />
+            </SelectTrigger>
+            <SelectContent>
+              <SelectItem value="none">Scale</SelectItem>
+              <SelectItem value="linear">Linear</SelectItem>
+              <SelectItem value="logarithmic">Logarithmic</SelectItem>
+              <SelectItem value="logarithmic_inverse">Inverse Log</SelectItem>
+            </SelectContent>
+          </Select>
+          {transform && (
+            <>
+              {(["input_min", "input_max", "output_min", "output_max"] as const).map(
+                (field) => (
+                  <ValidatedInput
+                    key={field}
+                    inputMode="decimal"
+                    className="h-9 text-xs w-16 font-mono"
+                    value={transform[field]}
+                    validate={validateOscFloat}
+                    errorMessage="Must be a number"
+                    onCommit={(v) => updateTransformField(field, v)}
+                    placeholder={field.replace("_", " ")}
+                  />
+                ),
+              )}
+            </>
+          )}
+        </>
+      )}
+    </div>
+  );
+}
@@ -0,0 +1,83 @@
+import { invoke } from "@tauri-apps/api/core";
+import { Input } from "@/components/ui/input";
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from "@/components/ui/select";
+import { Switch } from "@/components/ui/switch";
+import { Button } from "@/components/ui/button";
+import type { Mapping, MappingMode } from "@/types";
+import { RotateCcw } from "lucide-react";
+import { toast } from "sonner";
+
+interface ModeFieldsProps {
+  mapping: Mapping;
+  onChange: (mapping: Mapping) => void;
+}
+
+export function ModeFields({ mapping, onChange }: ModeFieldsProps) {
+  const stateful = mapping.mode !== "momentary";
+
+  const resetState = () => {
+    invoke("reset_mapping_states", {
+      key: mapping.state_group ?? mapping.id,
+    }).catch((e) => toast.error(String(e)));
+  };
+
+  return (
+    <div className="flex items-center gap-1">
+      <Select
+        value={mapping.mode}
+        onValueChange={(v) => onChange({ ...mapping, mode: v as MappingMode })}
+      >
+        <SelectTrigger className="h-9 text-xs w-28">
+          <SelectValue />
+        </SelectTrigger>
+        <SelectContent>
+          <SelectItem value="momentary">Momentary</SelectItem>
+          <SelectItem value="toggle">Toggle</SelectItem>
+          <SelectItem value="latch_on">Latch On</SelectItem>
+          <SelectItem value="latch_off">Latch Off</SelectItem>
+        </SelectContent>
+      </Select>
+      {stateful && (
+        <>
+          <Input
+            className="h-9 text-xs w-20"
+            placeholder="Group"
+            title="Mappings in the same group share one state"
+            value={mapping.state_group ?? ""}
+            onChange={(e) =>
+              onChange({ ...mapping, state_group: e.target.value || null })
+            }
+          />
+          <label
+            className="flex items-center gap-1 text-xs"
+            title="Keep state across restarts"
+          >
+            <Switch
+              size="sm"
+              checked={mapping.persist_state}
+              onCheckedChange={(checked) =>
+                onChange({ ...mapping, persist_state: checked === true })
+              }
+            />
+            <span className="text-muted-foreground">Keep</span>
+          </label>
+          <Button
+            variant="ghost"
+            size="sm"
+            className="h-7 w-7 p-0"
+            title="Reset state"
+            onClick={resetState}
+          >
+            <RotateCcw className="h-3 w-3 text-muted-foreground" />
+          </Button>
+        </>
+      )}
+    </div>
+  );
+}
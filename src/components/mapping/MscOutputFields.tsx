@@ -0,0 +1,148 @@
+import { Input } from "@/components/ui/input";
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from "@/components/ui/select";
+import { ValidatedInput } from "@/components/ui/validated-input";
+import { validateMidiValue } from "@/lib/validators";
+import { MSC_COMMANDS, MSC_COMMAND_FORMATS } from "@/types";
+import type { Mapping, MscCommand, MscCommandFormat, MscCueSource } from "@/types";
+
+interface MscCueFieldProps {
+  label: string;
+  source: MscCueSource | null;
+  onChange: (source: MscCueSource | null) => void;
+}
+
+/** A cue number, list or path: omitted, static, or read from each OSC message. */
+function MscCueField({ label, source, onChange }: MscCueFieldProps) {
+  return (
+    <div className="flex items-center gap-0.5">
+      <Select
+        value={source?.type ?? "none"}
+        onValueChange={(v) => {
+          if (v === "static") onChange({ type: "static", value: "" });
+          else if (v === "osc_arg" || v === "capture") onChange({ type: v, index: 0 });
+          else onChange(null);
+        }}
+      >
+        <SelectTrigger className="h-9 text-xs w-32">
+          <SelectValue />
+        </SelectTrigger>
+        <SelectContent>
+          <SelectItem value="none">No {label}</SelectItem>
+          <SelectItem value="static">{label}</SelectItem>
+          <SelectItem value="osc_arg">{label} from Arg</SelectItem>
+          <SelectItem value="capture">{label} from Capture</SelectItem>
+        </SelectContent>
+      </Select>
+      {source?.type === "static" && (
+        <Input
+          className="h-9 text-xs w-16 font-mono"
+          placeholder="1.5"
+          value={source.value}
+          onChange={(e) => onChange({ type: "static", value: e.target.value })}
+        />
+      )}
+      {(source?.type === "osc_arg" || source?.type === "capture") && (
+        <ValidatedInput
+          inputMode="numeric"
+          className="h-9 text-xs w-12"
+          value={source.index}
+          validate={validateMidiValue}
+          errorMessage="Index must be 0–127"
+          onCommit={(index) => onChange({ type: source.type, index })}
+        />
+      )}
+    </div>
+  );
+}
+
+/** Commands carrying a time or SET control/value, which mappings can't send yet. */
+const TIMED_COMMANDS: MscCommand[] = ["timed_go", "set", "set_clock"];
+
+interface MscOutputFieldsProps {
+  mapping: Mapping;
+  onChange: (mapping: Mapping) => void;
+}
+
+/** Command, target and cue fields for a sent MIDI Show Control message. */
+export function MscOutputFields({ mapping, onChange }: MscOutputFieldsProps) {
+  return (
+    <>
+      <Select
+        value={mapping.msc_command ?? ""}
+        onValueChange={(v) => onChange({ ...mapping, msc_command: v as MscCommand })}
+      >
+        <SelectTrigger className="h-9 text-xs w-28">
+          <SelectValue placeholder="Command" />
+        </SelectTrigger>
+        <SelectContent>
+          {MSC_COMMANDS.filter(({ value }) => !TIMED_COMMANDS.includes(value)).map(
+            ({ value, label }) => (
+              <SelectItem key={value} value={value}>
+                {label}
+              </SelectItem>
+            ),
+          )}
+        </SelectContent>
+      </Select>
+      <Select
+        value={mapping.msc_command_format ?? "all"}
+        onValueChange={(v) =>
+          onChange({ ...mapping, msc_command_format: v as MscCommandFormat })
+        }
+      >
+        <SelectTrigger className="h-9 text-xs w-28">
+          <SelectValue />
+        </SelectTrigger>
+        <SelectContent>
+          {MSC_COMMAND_FORMATS.map(({ value, label }) => (
+            <SelectItem key={value} value={value}>
+              {label}
+            </SelectItem>
+          ))}
+        </SelectContent>
+      </Select>
+      <Select
+        value={mapping.msc_device_id === null ? "all" : String(mapping.msc_device_id)}
+        onValueChange={(v) =>
+          onChange({
+            ...mapping,
+            msc_device_id: v === "all" ? null : parseInt(v),
+          })
+        }
+      >
+        <SelectTrigger className="h-9 text-xs w-24">
+          <SelectValue />
+        </SelectTrigger>
+        <SelectContent>
+          <SelectItem value="all">All IDs</SelectItem>
+          {Array.from({ length: 127 }, (_, i) => (
+            <SelectItem key={i} value={String(i)}>
+              ID {i}
+            </SelectItem>
+          ))}
+        </SelectContent>
+      </Select>
+      <MscCueField
+        label="Cue"
+        source={mapping.msc_cue_number}
+        onChange={(msc_cue_number) => onChange({ ...mapping, msc_cue_number })}
+      />
+      <MscCueField
+        label="List"
+        source={mapping.msc_cue_list}
+        onChange={(msc_cue_list) => onChange({ ...mapping, msc_cue_list })}
+      />
+      <MscCueField
+        label="Path"
+        source={mapping.msc_cue_path}
+        onChange={(msc_cue_path) => onChange({ ...mapping, msc_cue_path })}
+      />
+    </>
+  );
+}
@@ -8,11 +8,65 @@ import {
 } from "@/components/ui/select";
 import { ValidatedInput } from "@/components/ui/validated-input";
 import { isValidOscAddress } from "@/lib/midi";
-import { validateOscFloat, validateOscInt } from "@/lib/validators";
-import type { Mapping, OscArgDef, OscArgType, OscArgSource } from "@/types";
+import {
+  validateOscFloat,
+  validateOscInt,
+  validateOscNumberList,
+} from "@/lib/validators";
+import { OSC_ARG_TYPES } from "@/types";
+import type {
+  ClockInput,
+  Mapping,
+  OscArgDef,
+  OscArgType,
+  OscArgSource,
+} from "@/types";
 import { Plus, X } from "lucide-react";
 import { Button } from "@/components/ui/button";
 
+/** The argument added by default for clock input mappings. */
+const CLOCK_ARG_SOURCES: Record<
+  ClockInput,
+  { type: OscArgType; source: "bpm" | "beat" | "transport" }
+> = {
+  tempo: { type: "float", source: "bpm" },
+  beat: { type: "int", source: "beat" },
+  transport: { type: "string", source: "transport" },
+};
+
+/** How a static value of each argument type is edited. */
+type StaticInputKind = "number" | "text" | "list" | "none";
+
+function staticInputKind(type: OscArgType): StaticInputKind {
+  switch (type) {
+    case "string":
+    case "char":
+    case "blob":
+    case "color":
+      return "text";
+    case "midi":
+    case "timetag":
+      return "list";
+    case "nil":
+    case "impulse":
+      return "none";
+    default:
+      return "number";
+  }
+}
+
+function defaultStaticValue(type: OscArgType): number | string | number[] {
+  switch (staticInputKind(type)) {
+    case "text":
+      return "";
+    case "list":
+      // Timetag 0.1 means "immediately"
+      return type === "timetag" ? [0, 1] : [0, 0, 0, 0];
+    default:
+      return 0;
+  }
+}
+
 interface OscOutputFieldsProps {
   mapping: Mapping;
   onChange: (mapping: Mapping) => void;
@@ -28,13 +82,15 @@ export function OscOutputFields({ mapping, onChange }: OscOutputFieldsProps) {
     onChange({ ...mapping, osc_args: args });
   };
 
-  const isMsc = mapping.midi_message_type === "msc";
+  const isMsc = mapping.clock_input === null && mapping.midi_message_type === "msc";
+  const clockSource = mapping.clock_input && CLOCK_ARG_SOURCES[mapping.clock_input];
 
   const addArg = () => {
-    const newArg: OscArgDef = {
-      type: isMsc ? "string" : "float",
-      source: isMsc ? { type: "msc_cue_number" } : { type: "midi_value" },
-    };
+    const newArg: OscArgDef = isMsc
+      ? { type: "string", source: { type: "msc_cue_number" } }
+      : clockSource
+        ? { type: clockSource.type, source: { type: clockSource.source } }
+        : { type: "float", source: { type: "midi_value" } };
     onChange({ ...mapping, osc_args: [...mapping.osc_args, newArg] });
   };
 
@@ -49,6 +105,7 @@ export function OscOutputFields({ mapping, onChange }: OscOutputFieldsProps) {
         <Input
           className={`h-9 text-xs w-52 font-mono ${invalid ? "border-red-500" : ""}`}
           placeholder="/osc/address"
+          title="{channel} and {note} insert the incoming MIDI channel and number"
           value={mapping.osc_address}
           onChange={(e) => onChange({ ...mapping, osc_address: e.target.value })}
         />
@@ -66,17 +123,26 @@ export function OscOutputFields({ mapping, onChange }: OscOutputFieldsProps) {
         <div key={i} className="flex items-center gap-0.5">
           <Select
             value={arg.type}
-            onValueChange={(v) =>
-              updateArg(i, { ...arg, type: v as OscArgType })
-            }
+            onValueChange={(v) => {
+              const type = v as OscArgType;
+              // A static value of another kind would no longer parse
+              const source =
+                arg.source.type === "static" &&
+                staticInputKind(type) !== staticInputKind(arg.type)
+                  ? { type: "static" as const, value: defaultStaticValue(type) }
+                  : arg.source;
+              updateArg(i, { ...arg, type, source });
+            }}
           >
             <SelectTrigger className="h-7 text-xs w-32">
               <SelectValue />
             </SelectTrigger>
             <SelectContent>
-              <SelectItem value="float">float</SelectItem>
-              <SelectItem value="int">int</SelectItem>
-              <SelectItem value="string">string</SelectItem>
+              {OSC_ARG_TYPES.map((t) => (
+                <SelectItem key={t.value} value={t.value}>
+                  {t.label}
+                </SelectItem>
+              ))}
             </SelectContent>
           </Select>
           <Select
@@ -84,7 +150,7 @@ export function OscOutputFields({ mapping, onChange }: OscOutputFieldsProps) {
             onValueChange={(v) => {
               let source: OscArgSource;
               if (v === "static") {
-                source = { type: "static", value: arg.type === "string" ? "" : 0 };
+                source = { type: "static", value: defaultStaticValue(arg.type) };
               } else if (v === "midi_value") {
                 source = { type: "midi_value" };
               } else if (v === "midi_note") {
@@ -95,6 +161,16 @@ export function OscOutputFields({ mapping, onChange }: OscOutputFieldsProps) {
                 source = { type: "msc_cue_list" };
               } else if (v === "msc_cue_path") {
                 source = { type: "msc_cue_path" };
+              } else if (
+                v === "msc_time" ||
+                v === "msc_set_control" ||
+                v === "msc_set_value" ||
+                v === "bpm" ||
+                v === "beat" ||
+                v === "bar" ||
+                v === "transport"
+              ) {
+                source = { type: v };
               } else {
                 source = { type: "midi_value" };
               }
@@ -118,6 +194,25 @@ export function OscOutputFields({ mapping, onChange }: OscOutputFieldsProps) {
                   <SelectItem value="msc_cue_number">Cue Number</SelectItem>
                   <SelectItem value="msc_cue_list">Cue List</SelectItem>
                   <SelectItem value="msc_cue_path">Cue Path</SelectItem>
+                  <SelectItem value="msc_time">Time</SelectItem>
+                  <SelectItem value="msc_set_control">SET Control</SelectItem>
+                  <SelectItem value="msc_set_value">SET Value</SelectItem>
+                  <SelectItem value="static">Static</SelectItem>
+                </>
+              ) : mapping.clock_input === "tempo" ? (
+                <>
+                  <SelectItem value="bpm">BPM</SelectItem>
+                  <SelectItem value="static">Static</SelectItem>
+                </>
+              ) : mapping.clock_input === "beat" ? (
+                <>
+                  <SelectItem value="beat">Beat</SelectItem>
+                  <SelectItem value="bar">Bar</SelectItem>
+                  <SelectItem value="static">Static</SelectItem>
+                </>
+              ) : mapping.clock_input === "transport" ? (
+                <>
+                  <SelectItem value="transport">State</SelectItem>
                   <SelectItem value="static">Static</SelectItem>
                 </>
               ) : (
@@ -144,10 +239,11 @@ export function OscOutputFields({ mapping, onChange }: OscOutputFieldsProps) {
               }}
             />
           )}
-          {arg.source.type === "static" && arg.type === "string" && (
+          {arg.source.type === "static" && staticInputKind(arg.type) === "text" && (
             <Input
               className="h-9 text-xs w-16 font-mono"
-              value={arg.source.value}
+              placeholder={arg.type === "color" ? "#RRGGBBAA" : undefined}
+              value={String(arg.source.value)}
               onChange={(e) =>
                 updateArg(i, {
                   ...arg,
@@ -156,14 +252,37 @@ export function OscOutputFields({ mapping, onChange }: OscOutputFieldsProps) {
               }
             />
           )}
-          {arg.source.type === "static" && arg.type !== "string" && (
+          {arg.source.type === "static" && staticInputKind(arg.type) === "list" && (
+            <ValidatedInput<number[]>
+              className="h-9 text-xs w-28 font-mono"
+              placeholder={arg.type === "timetag" ? "seconds, fraction" : "port, status, d1, d2"}
+              value={
+                Array.isArray(arg.source.value)
+                  ? arg.source.value.join(", ")
+                  : arg.source.value
+              }
+              validate={validateOscNumberList}
+              errorMessage="Must be comma-separated whole numbers"
+              onCommit={(v) =>
+                updateArg(i, {
+                  ...arg,
+                  source: { type: "static", value: v },
+                })
+              }
+            />
+          )}
+          {arg.source.type === "static" && staticInputKind(arg.type) === "number" && (
             <ValidatedInput
               inputMode="decimal"
               className="h-9 text-xs w-16 font-mono"
-              value={arg.source.value}
-              validate={arg.type === "float" ? validateOscFloat : validateOscInt}
+              value={String(arg.source.value)}
+              validate={
+                arg.type === "float" || arg.type === "double"
+                  ? validateOscFloat
+                  : validateOscInt
+              }
               errorMessage={
-                arg.type === "float"
+                arg.type === "float" || arg.type === "double"
                   ? "Must be a valid number"
                   : "Must be a valid integer"
               }
@@ -188,7 +188,11 @@ export function OscToOscFields({ mapping, onChange }: OscToOscFieldsProps) {
           onChange={(e) =>
             onChange({ ...mapping, osc_output_address: e.target.value })
           }
-          title={outputInvalid ? "OSC address must start with /" : ""}
+          title={
+            outputInvalid
+              ? "OSC address must start with /"
+              : "{0}, {1}, … insert the input address's wildcard captures"
+          }
         />
         <Select value={curveValue} onValueChange={handleCurveChange}>
           <SelectTrigger className="h-7 text-xs w-40">
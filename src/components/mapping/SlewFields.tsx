@@ -0,0 +1,68 @@
+import { Input } from "@/components/ui/input";
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from "@/components/ui/select";
+import type { Mapping, SlewMode } from "@/types";
+
+interface SlewFieldsProps {
+  mapping: Mapping;
+  onChange: (mapping: Mapping) => void;
+}
+
+export function SlewFields({ mapping, onChange }: SlewFieldsProps) {
+  const slew = mapping.slew;
+
+  const setMode = (v: string) => {
+    if (v === "off") {
+      onChange({ ...mapping, slew: null });
+      return;
+    }
+    const mode = v as SlewMode;
+    onChange({
+      ...mapping,
+      slew: {
+        mode,
+        amount: slew?.amount ?? (mode === "time_constant" ? 100 : 1),
+        rate_hz: slew?.rate_hz ?? 60,
+      },
+    });
+  };
+
+  return (
+    <div className="flex items-center gap-1">
+      <Select value={slew?.mode ?? "off"} onValueChange={setMode}>
+        <SelectTrigger className="h-9 text-xs w-24" title="Slew">
+          <SelectValue />
+        </SelectTrigger>
+        <SelectContent>
+          <SelectItem value="off">No slew</SelectItem>
+          <SelectItem value="time_constant">Smooth</SelectItem>
+          <SelectItem value="max_rate">Max rate</SelectItem>
+        </SelectContent>
+      </Select>
+      {slew && (
+        <Input
+          type="number"
+          min={0}
+          className="h-9 text-xs w-20"
+          title={
+            slew.mode === "time_constant"
+              ? "Time constant (ms)"
+              : "Largest change per second"
+          }
+          value={slew.amount}
+          onChange={(e) =>
+            onChange({
+              ...mapping,
+              slew: { ...slew, amount: Number(e.target.value) || 0 },
+            })
+          }
+        />
+      )}
+    </div>
+  );
+}
@@ -3,10 +3,10 @@ import { Input } from "@/components/ui/input";
 import { toast } from "sonner";
 import type { ValidationResult } from "@/lib/validators";
 
-interface ValidatedInputProps {
+interface ValidatedInputProps<T = number> {
   value: number | string;
-  onCommit: (value: number) => void;
-  validate: (raw: string) => ValidationResult;
+  onCommit: (value: T) => void;
+  validate: (raw: string) => ValidationResult<T>;
   errorMessage: string;
   className?: string;
   disabled?: boolean;
@@ -14,7 +14,7 @@ interface ValidatedInputProps {
   inputMode?: "numeric" | "text" | "decimal";
 }
 
-export function ValidatedInput({
+export function ValidatedInput<T = number>({
   value,
   onCommit,
   validate,
@@ -23,7 +23,7 @@ export function ValidatedInput({
   disabled,
   placeholder,
   inputMode,
-}: ValidatedInputProps) {
+}: ValidatedInputProps<T>) {
   const [text, setText] = useState(String(value));
   const focused = useRef(false);
 
@@ -12,6 +12,7 @@ export interface LogEntry {
   outputDisplay?: string;
   protocol?: string;
   display?: string;
+  reason?: string;
 }
 
 const MAX_ENTRIES = 500;
@@ -54,6 +55,7 @@ export function useActivityLog() {
           type: "unmatched",
           protocol: payload.protocol,
           display: payload.display,
+          reason: payload.reason,
         };
         setEntries((prev) => [...prev.slice(-(MAX_ENTRIES - 1)), entry]);
       },
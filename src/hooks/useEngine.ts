@@ -6,6 +6,14 @@ import type { EngineStatus } from "@/types";
 
 export function useEngine() {
   const [status, setStatus] = useState<EngineStatus>({ running: false });
+  // Mappings disabled by a feedback-loop breaker, with the reason
+  const [loopBreakers, setLoopBreakers] = useState<Record<string, string>>({});
+
+  const refreshLoopBreakers = useCallback(() => {
+    invoke<Record<string, string>>("get_loop_breakers")
+      .then(setLoopBreakers)
+      .catch(console.error);
+  }, []);
 
   useEffect(() => {
     invoke<EngineStatus>("get_engine_status")
@@ -15,15 +23,19 @@ export function useEngine() {
         toast.error("Failed to get engine status");
       });
 
-    const unlisten = typedListen<EngineStatus>("engine-status", setStatus);
+    const unlisten = typedListen<EngineStatus>("engine-status", (next) => {
+      setStatus(next);
+      refreshLoopBreakers();
+    });
     return () => {
       unlisten.then((fn) => fn());
     };
-  }, []);
+  }, [refreshLoopBreakers]);
 
   const start = useCallback(async () => {
     await invoke("start_engine");
     setStatus({ running: true });
+    setLoopBreakers({});
   }, []);
 
   const stop = useCallback(async () => {
@@ -31,5 +43,10 @@ export function useEngine() {
     setStatus({ running: false });
   }, []);
 
-  return { status, start, stop };
+  const resetLoopBreakers = useCallback(async () => {
+    await invoke("reset_loop_breakers");
+    refreshLoopBreakers();
+  }, [refreshLoopBreakers]);
+
+  return { status, start, stop, loopBreakers, resetLoopBreakers };
 }
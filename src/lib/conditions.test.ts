@@ -0,0 +1,22 @@
+import { describe, it, expect } from "vitest";
+import { formatConditionValue, parseConditionValue } from "./conditions";
+
+describe("parseConditionValue", () => {
+  it("reads message values", () => {
+    expect(parseConditionValue("arg2")).toEqual({ type: "osc_arg", index: 2 });
+    expect(parseConditionValue("value")).toEqual({ type: "midi_value" });
+    expect(parseConditionValue(" note ")).toEqual({ type: "midi_note" });
+  });
+
+  it("treats other input as a constant", () => {
+    expect(parseConditionValue("0.5")).toEqual({ type: "static", value: 0.5 });
+    expect(parseConditionValue("go")).toEqual({ type: "static", value: "go" });
+    expect(parseConditionValue("")).toEqual({ type: "static", value: "" });
+  });
+
+  it("round-trips through formatConditionValue", () => {
+    for (const raw of ["arg0", "value", "note", "0.5", "go"]) {
+      expect(formatConditionValue(parseConditionValue(raw))).toBe(raw);
+    }
+  });
+});
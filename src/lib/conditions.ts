@@ -0,0 +1,32 @@
+import type { ConditionValue } from "@/types";
+
+/**
+ * Parse a condition operand typed by the user: `arg0`, `value` and `note`
+ * read from the message; anything else is a constant, numeric if it parses.
+ */
+export function parseConditionValue(raw: string): ConditionValue {
+  const trimmed = raw.trim();
+  const arg = /^arg(\d+)$/.exec(trimmed);
+  if (arg) return { type: "osc_arg", index: parseInt(arg[1]) };
+  if (trimmed === "value") return { type: "midi_value" };
+  if (trimmed === "note") return { type: "midi_note" };
+  const num = Number(trimmed);
+  if (trimmed !== "" && Number.isFinite(num)) {
+    return { type: "static", value: num };
+  }
+  return { type: "static", value: trimmed };
+}
+
+/** Inverse of `parseConditionValue`. */
+export function formatConditionValue(value: ConditionValue): string {
+  switch (value.type) {
+    case "osc_arg":
+      return `arg${value.index}`;
+    case "midi_value":
+      return "value";
+    case "midi_note":
+      return "note";
+    case "static":
+      return String(value.value);
+  }
+}
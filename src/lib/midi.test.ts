@@ -1,5 +1,12 @@
 import { describe, it, expect } from "vitest";
-import { midiNoteToName, noteNameToMidi, isValidOscAddress } from "./midi";
+import {
+  formatMidiField,
+  midiNoteToName,
+  noteNameToMidi,
+  isValidOscAddress,
+  validateMidiField,
+} from "./midi";
+import { validateMidiNote } from "./validators";
 
 describe("midiNoteToName", () => {
   it("converts middle C (60) to C3", () => {
@@ -98,3 +105,25 @@ describe("isValidOscAddress", () => {
     expect(isValidOscAddress("/cue\tgo")).toBe(false);
   });
 });
+
+describe("validateMidiField", () => {
+  it("parses fixed notes, OSC args and captures", () => {
+    expect(validateMidiField("C3", validateMidiNote)).toEqual({ valid: true, value: 60 });
+    expect(validateMidiField("arg1", validateMidiNote)).toEqual({
+      valid: true,
+      value: { type: "osc_arg", index: 1 },
+    });
+    expect(validateMidiField(" cap0 ", validateMidiNote)).toEqual({
+      valid: true,
+      value: { type: "capture", index: 0 },
+    });
+    expect(validateMidiField("bus", validateMidiNote)).toEqual({ valid: false });
+  });
+
+  it("round-trips through formatMidiField", () => {
+    expect(formatMidiField(7)).toBe("7");
+    expect(formatMidiField({ type: "static", value: 12 })).toBe("12");
+    expect(formatMidiField({ type: "osc_arg", index: 2 })).toBe("arg2");
+    expect(formatMidiField({ type: "capture", index: 0 })).toBe("cap0");
+  });
+});
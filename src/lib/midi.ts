@@ -1,3 +1,6 @@
+import type { ValidationResult } from "@/lib/validators";
+import type { MidiField } from "@/types";
+
 const NOTE_NAMES = [
   "C",
   "C#",
@@ -52,6 +55,35 @@ export function noteNameToMidi(name: string): number | null {
   return midi;
 }
 
+/** Format a channel or note/CC field: a number, `argN` or `capN`. */
+export function formatMidiField(field: MidiField): string {
+  if (typeof field === "number") return String(field);
+  switch (field.type) {
+    case "static":
+      return String(field.value);
+    case "osc_arg":
+      return `arg${field.index}`;
+    case "capture":
+      return `cap${field.index}`;
+  }
+}
+
+/**
+ * Parse a channel or note/CC field typed by the user: `arg0` reads OSC
+ * arg 0, `cap1` reads address capture 1, anything else goes to `fixed`.
+ */
+export function validateMidiField(
+  raw: string,
+  fixed: (raw: string) => ValidationResult,
+): ValidationResult<MidiField> {
+  const trimmed = raw.trim();
+  const arg = /^arg(\d+)$/.exec(trimmed);
+  if (arg) return { valid: true, value: { type: "osc_arg", index: parseInt(arg[1]) } };
+  const cap = /^cap(\d+)$/.exec(trimmed);
+  if (cap) return { valid: true, value: { type: "capture", index: parseInt(cap[1]) } };
+  return fixed(trimmed);
+}
+
 /** Validate an OSC address — must start with / and contain only valid OSC characters */
 export function isValidOscAddress(addr: string): boolean {
   return /^\/[a-zA-Z0-9_/.*?\[\]{},# -]*$/.test(addr);
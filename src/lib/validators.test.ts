@@ -5,6 +5,7 @@ import {
   validateMidiNote,
   validateOscFloat,
   validateOscInt,
+  validateOscNumberList,
 } from "./validators";
 
 describe("validatePort", () => {
@@ -118,3 +119,20 @@ describe("validateOscInt", () => {
     expect(validateOscInt("")).toEqual({ valid: false });
   });
 });
+
+describe("validateOscNumberList", () => {
+  it("accepts comma-separated integers", () => {
+    expect(validateOscNumberList("0, 144, 60, 127")).toEqual({
+      valid: true,
+      value: [0, 144, 60, 127],
+    });
+    expect(validateOscNumberList("5")).toEqual({ valid: true, value: [5] });
+  });
+
+  it("rejects empty entries, negatives and non-integers", () => {
+    expect(validateOscNumberList("")).toEqual({ valid: false });
+    expect(validateOscNumberList("1,,2")).toEqual({ valid: false });
+    expect(validateOscNumberList("-1")).toEqual({ valid: false });
+    expect(validateOscNumberList("1.5")).toEqual({ valid: false });
+  });
+});
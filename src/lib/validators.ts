@@ -20,6 +20,14 @@ export function validateMidiValue(raw: string): ValidationResult {
   return { valid: true, value: num };
 }
 
+export function validateMidi14BitValue(raw: string): ValidationResult {
+  const trimmed = raw.trim();
+  if (trimmed === "") return { valid: false };
+  const num = Number(trimmed);
+  if (!Number.isInteger(num) || num < 0 || num > 16383) return { valid: false };
+  return { valid: true, value: num };
+}
+
 export function validateMidiNote(raw: string): ValidationResult {
   const trimmed = raw.trim();
   if (trimmed === "") return { valid: false };
@@ -49,3 +57,12 @@ export function validateOscInt(raw: string): ValidationResult {
   if (!Number.isInteger(num) || !Number.isFinite(num)) return { valid: false };
   return { valid: true, value: num };
 }
+
+/** Comma-separated non-negative integers, e.g. MIDI bytes or a timetag. */
+export function validateOscNumberList(raw: string): ValidationResult<number[]> {
+  const parts = raw.split(",").map((part) => part.trim());
+  if (parts.some((part) => part === "")) return { valid: false };
+  const nums = parts.map(Number);
+  if (nums.some((num) => !Number.isInteger(num) || num < 0)) return { valid: false };
+  return { valid: true, value: nums };
+}
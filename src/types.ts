@@ -5,14 +5,53 @@ export interface Settings {
   osc_send_port: number;
   osc_send_protocol: OscSendProtocol;
   osc_tcp_send_timeout_ms: number;
+  osc_tcp_send_framing: OscTcpFraming;
+  osc_tcp_listen_framing: OscTcpListenFraming;
+  osc_destinations: OscDestination[];
   midi_input_port_name: string | null;
   midi_output_port_name: string | null;
+  midi_input_port_names: string[];
+  midi_output_port_names: string[];
   engine_auto_start: boolean;
   launch_on_startup: boolean;
+  /** Messages per second on one address that count as a feedback loop; 0 is off */
+  loop_max_rate_hz: number;
+  mtc_output: MtcOutput;
+  mtc_generator: MtcGenerator;
 }
 
+/** Publishes timecode decoded from MIDI inputs as OSC */
+export interface MtcOutput {
+  enabled: boolean;
+  address: string;
+  format: MtcArgFormat;
+  destinations: string[];
+}
+
+/** MTC master on a MIDI output, driven by OSC play/stop/locate */
+export interface MtcGenerator {
+  enabled: boolean;
+  address: string;
+  rate: MtcRate;
+  port: string | null;
+}
+
+export type MtcArgFormat = "string" | "numeric" | "both";
+export type MtcRate = "fps24" | "fps25" | "fps2997_drop" | "fps30";
+
 export type OscListenProtocol = "udp" | "tcp" | "both";
 export type OscSendProtocol = "udp" | "tcp";
+export type OscTcpFraming = "slip" | "length_prefix";
+export type OscTcpListenFraming = OscTcpFraming | "auto";
+
+export interface OscDestination {
+  name: string;
+  host: string;
+  port: number;
+  protocol: OscSendProtocol;
+  timeout_ms: number;
+  framing: OscTcpFraming;
+}
 
 export type TransformCurve =
   | "linear"
@@ -44,28 +83,350 @@ export interface Mapping {
   direction: Direction;
   osc_address: string;
   osc_arg_types: OscArgType[];
+  osc_arg_match: OscArgMatch;
   midi_message_type: MidiMessageType;
-  midi_channel: number;
-  midi_note_or_cc: number;
+  midi_channel: MidiField;
+  midi_note_or_cc: MidiField;
   midi_velocity_or_value: ValueSource;
   midi_input_velocity: number | null;
+  midi_input_port: string | null;
+  midi_output_port: string | null;
   osc_args: OscArgDef[];
   osc_output_address: string;
   osc_transform: OscTransform | null;
+  osc_destinations: string[];
   msc_device_id: number | null;
   msc_command_format: MscCommandFormat | null;
   msc_command: MscCommand | null;
+  msc_cue_number: MscCueSource | null;
+  msc_cue_list: MscCueSource | null;
+  msc_cue_path: MscCueSource | null;
+  cc_mode: CcMode;
+  midi_parameter: number;
+  /** Clock input a MIDI → OSC mapping follows instead of midi_message_type */
+  clock_input: ClockInput | null;
+  /** Transport message a transport mapping reacts to; null for all */
+  transport_state: TransportState | null;
+  midi_remap: MidiRemap;
+  conditions: Condition[];
+  mode: MappingMode;
+  state_group: string | null;
+  persist_state: boolean;
+  slew: Slew | null;
+  output_filter: OutputFilter;
+  steps: MacroStep[];
+  retrigger: Retrigger;
+}
+
+export interface MacroStep {
+  /** Wait this long after the previous output */
+  delay_ms: number;
+  output: MacroOutput;
+}
+
+export type MacroOutput =
+  | {
+      type: "osc";
+      address: string;
+      args: OscArgDef[];
+      destinations: string[];
+    }
+  | {
+      type: "midi";
+      message_type: MidiMessageType;
+      channel: number;
+      note_or_cc: number;
+      value: number;
+      port: string | null;
+    };
+
+export type Retrigger = "cancel" | "queue";
+
+export type MappingMode = "momentary" | "toggle" | "latch_on" | "latch_off";
+
+export interface OutputFilter {
+  /** Most messages per second; the latest value is sent once allowed */
+  max_rate_hz: number | null;
+  dedupe: boolean;
+  deadband: number;
+}
+
+/** Outputs a mapping's filter dropped since the engine started */
+export interface DropCount {
+  throttled: number;
+  duplicate: number;
+  deadband: number;
 }
 
-export type Direction = "osc_to_midi" | "midi_to_osc" | "osc_to_osc";
-export type MidiMessageType = "note_on" | "note_off" | "cc" | "program_change" | "msc";
-export type MscCommand = "go" | "stop" | "resume";
-export type MscCommandFormat = "all" | "lighting" | "sound";
-export type OscArgType = "int" | "float" | "string";
+export type SlewMode = "time_constant" | "max_rate";
+
+export interface Slew {
+  mode: SlewMode;
+  /** Time constant in ms, or the largest change per second */
+  amount: number;
+  rate_hz: number;
+}
+
+export type ConditionValue =
+  | { type: "osc_arg"; index: number }
+  | { type: "midi_value" }
+  | { type: "midi_note" }
+  | { type: "static"; value: number | string | boolean };
+
+export type Comparison = "eq" | "neq" | "lt" | "le" | "gt" | "ge";
+
+export type Condition =
+  | { op: "range"; value: ConditionValue; min: number; max: number }
+  | {
+      op: "compare";
+      left: ConditionValue;
+      cmp: Comparison;
+      right: ConditionValue;
+    }
+  | { op: "regex"; value: ConditionValue; pattern: string };
+
+export type Direction =
+  | "osc_to_midi"
+  | "midi_to_osc"
+  | "osc_to_osc"
+  | "midi_to_midi";
+
+/** Output rewrites for MIDI → MIDI; null keeps the incoming value. */
+export interface MidiRemap {
+  message_type: MidiMessageType | null;
+  channel: number | null;
+  number: number | null;
+}
+export type CcMode = "seven_bit" | "fourteen_bit" | "nrpn" | "rpn";
+export type MidiMessageType =
+  | "note_on"
+  | "note_off"
+  | "cc"
+  | "program_change"
+  | "msc"
+  | "pitch_bend"
+  | "channel_pressure"
+  | "poly_aftertouch";
+/** MIDI clock input kinds; only matched by MIDI → OSC mappings */
+export type ClockInput = "tempo" | "beat" | "transport";
+export const CLOCK_INPUTS: ClockInput[] = ["tempo", "beat", "transport"];
+export type TransportState = "start" | "continue" | "stop";
+export type MscCommand =
+  | "go"
+  | "stop"
+  | "resume"
+  | "timed_go"
+  | "load"
+  | "set"
+  | "fire"
+  | "all_off"
+  | "restore"
+  | "reset"
+  | "go_off"
+  | "go_jam_clock"
+  | "standby_plus"
+  | "standby_minus"
+  | "sequence_plus"
+  | "sequence_minus"
+  | "start_clock"
+  | "stop_clock"
+  | "zero_clock"
+  | "set_clock"
+  | "mtc_chase_on"
+  | "mtc_chase_off"
+  | "open_cue_list"
+  | "close_cue_list"
+  | "open_cue_path"
+  | "close_cue_path";
+export type MscCommandFormat =
+  | "all"
+  | "lighting"
+  | "moving_lights"
+  | "color_changers"
+  | "strobes"
+  | "lasers"
+  | "chasers"
+  | "sound"
+  | "music"
+  | "cd_players"
+  | "eprom_playback"
+  | "audio_tape_machines"
+  | "intercoms"
+  | "amplifiers"
+  | "audio_effects"
+  | "equalizers"
+  | "machinery"
+  | "rigging"
+  | "flys"
+  | "lifts"
+  | "turntables"
+  | "trusses"
+  | "robots"
+  | "animation"
+  | "floats"
+  | "breakaways"
+  | "barges"
+  | "video"
+  | "video_tape_machines"
+  | "video_cassette_machines"
+  | "video_disc_players"
+  | "video_switchers"
+  | "video_effects"
+  | "video_character_generators"
+  | "video_still_stores"
+  | "video_monitors"
+  | "projection"
+  | "film_projectors"
+  | "slide_projectors"
+  | "video_projectors"
+  | "dissolvers"
+  | "shutter_controls"
+  | "process_control"
+  | "hydraulic_oil"
+  | "h2o"
+  | "co2"
+  | "compressed_air"
+  | "natural_gas"
+  | "fog"
+  | "smoke"
+  | "cracked_haze"
+  | "pyro"
+  | "fireworks"
+  | "explosions"
+  | "flame"
+  | "smoke_pots";
+
+export const MSC_COMMANDS: { value: MscCommand; label: string }[] = [
+  { value: "go", label: "GO" },
+  { value: "stop", label: "STOP" },
+  { value: "resume", label: "RESUME" },
+  { value: "timed_go", label: "TIMED_GO" },
+  { value: "load", label: "LOAD" },
+  { value: "set", label: "SET" },
+  { value: "fire", label: "FIRE" },
+  { value: "all_off", label: "ALL_OFF" },
+  { value: "restore", label: "RESTORE" },
+  { value: "reset", label: "RESET" },
+  { value: "go_off", label: "GO_OFF" },
+  { value: "go_jam_clock", label: "GO/JAM_CLOCK" },
+  { value: "standby_plus", label: "STANDBY_+" },
+  { value: "standby_minus", label: "STANDBY_-" },
+  { value: "sequence_plus", label: "SEQUENCE_+" },
+  { value: "sequence_minus", label: "SEQUENCE_-" },
+  { value: "start_clock", label: "START_CLOCK" },
+  { value: "stop_clock", label: "STOP_CLOCK" },
+  { value: "zero_clock", label: "ZERO_CLOCK" },
+  { value: "set_clock", label: "SET_CLOCK" },
+  { value: "mtc_chase_on", label: "MTC_CHASE_ON" },
+  { value: "mtc_chase_off", label: "MTC_CHASE_OFF" },
+  { value: "open_cue_list", label: "OPEN_CUE_LIST" },
+  { value: "close_cue_list", label: "CLOSE_CUE_LIST" },
+  { value: "open_cue_path", label: "OPEN_CUE_PATH" },
+  { value: "close_cue_path", label: "CLOSE_CUE_PATH" },
+];
+
+export const MSC_COMMAND_FORMATS: { value: MscCommandFormat; label: string }[] = [
+  { value: "all", label: "All Types" },
+  { value: "lighting", label: "Lighting" },
+  { value: "moving_lights", label: "Moving Lights" },
+  { value: "color_changers", label: "Color Changers" },
+  { value: "strobes", label: "Strobes" },
+  { value: "lasers", label: "Lasers" },
+  { value: "chasers", label: "Chasers" },
+  { value: "sound", label: "Sound" },
+  { value: "music", label: "Music" },
+  { value: "cd_players", label: "CD Players" },
+  { value: "eprom_playback", label: "EPROM Playback" },
+  { value: "audio_tape_machines", label: "Audio Tape Machines" },
+  { value: "intercoms", label: "Intercoms" },
+  { value: "amplifiers", label: "Amplifiers" },
+  { value: "audio_effects", label: "Audio Effects" },
+  { value: "equalizers", label: "Equalizers" },
+  { value: "machinery", label: "Machinery" },
+  { value: "rigging", label: "Rigging" },
+  { value: "flys", label: "Flys" },
+  { value: "lifts", label: "Lifts" },
+  { value: "turntables", label: "Turntables" },
+  { value: "trusses", label: "Trusses" },
+  { value: "robots", label: "Robots" },
+  { value: "animation", label: "Animation" },
+  { value: "floats", label: "Floats" },
+  { value: "breakaways", label: "Breakaways" },
+  { value: "barges", label: "Barges" },
+  { value: "video", label: "Video" },
+  { value: "video_tape_machines", label: "Video Tape Machines" },
+  { value: "video_cassette_machines", label: "Video Cassette Machines" },
+  { value: "video_disc_players", label: "Video Disc Players" },
+  { value: "video_switchers", label: "Video Switchers" },
+  { value: "video_effects", label: "Video Effects" },
+  { value: "video_character_generators", label: "Video Character Generators" },
+  { value: "video_still_stores", label: "Video Still Stores" },
+  { value: "video_monitors", label: "Video Monitors" },
+  { value: "projection", label: "Projection" },
+  { value: "film_projectors", label: "Film Projectors" },
+  { value: "slide_projectors", label: "Slide Projectors" },
+  { value: "video_projectors", label: "Video Projectors" },
+  { value: "dissolvers", label: "Dissolvers" },
+  { value: "shutter_controls", label: "Shutter Controls" },
+  { value: "process_control", label: "Process Control" },
+  { value: "hydraulic_oil", label: "Hydraulic Oil" },
+  { value: "h2o", label: "H2O" },
+  { value: "co2", label: "CO2" },
+  { value: "compressed_air", label: "Compressed Air" },
+  { value: "natural_gas", label: "Natural Gas" },
+  { value: "fog", label: "Fog" },
+  { value: "smoke", label: "Smoke" },
+  { value: "cracked_haze", label: "Cracked Haze" },
+  { value: "pyro", label: "Pyro" },
+  { value: "fireworks", label: "Fireworks" },
+  { value: "explosions", label: "Explosions" },
+  { value: "flame", label: "Flame" },
+  { value: "smoke_pots", label: "Smoke Pots" },
+];
+
+export type MscCueSource =
+  | { type: "static"; value: string }
+  | { type: "osc_arg"; index: number }
+  | { type: "capture"; index: number };
+export type OscArgType =
+  | "int"
+  | "float"
+  | "string"
+  | "blob"
+  | "bool"
+  | "nil"
+  | "impulse"
+  | "double"
+  | "int64"
+  | "timetag"
+  | "char"
+  | "color"
+  | "midi";
+
+export const OSC_ARG_TYPES: { value: OscArgType; label: string }[] = [
+  { value: "float", label: "float" },
+  { value: "int", label: "int" },
+  { value: "string", label: "string" },
+  { value: "double", label: "double" },
+  { value: "int64", label: "int64" },
+  { value: "bool", label: "bool" },
+  { value: "char", label: "char" },
+  { value: "blob", label: "blob" },
+  { value: "color", label: "color" },
+  { value: "midi", label: "midi" },
+  { value: "timetag", label: "timetag" },
+  { value: "nil", label: "nil" },
+  { value: "impulse", label: "impulse" },
+];
+export type OscArgMatch = "any" | "exact" | "prefix";
 
 export type ValueSource =
   | { type: "static"; value: number }
-  | { type: "osc_arg"; index: number };
+  | { type: "osc_arg"; index: number }
+  | { type: "capture"; index: number };
+
+/** A fixed channel or note/CC, or one read from each OSC message */
+export type MidiField = number | ValueSource;
 
 export interface OscArgDef {
   type: OscArgType;
@@ -73,12 +434,19 @@ export interface OscArgDef {
 }
 
 export type OscArgSource =
-  | { type: "static"; value: number | string }
+  | { type: "static"; value: number | string | number[] }
   | { type: "midi_value" }
   | { type: "midi_note" }
   | { type: "msc_cue_number"; template?: string }
   | { type: "msc_cue_list"; template?: string }
-  | { type: "msc_cue_path"; template?: string };
+  | { type: "msc_cue_path"; template?: string }
+  | { type: "msc_time" }
+  | { type: "msc_set_control" }
+  | { type: "msc_set_value" }
+  | { type: "bpm" }
+  | { type: "beat" }
+  | { type: "bar" }
+  | { type: "transport" };
 
 export interface MidiPort {
   name: string;
@@ -90,6 +458,16 @@ export interface EngineStatus {
   error?: string;
 }
 
+export type OscConnectionState = "connecting" | "connected" | "disconnected";
+
+export interface OscConnectionStatus {
+  destination: string;
+  host: string;
+  port: number;
+  state: OscConnectionState;
+  error?: string;
+}
+
 export interface MappingActivityEvent {
   timestamp: string;
   input_protocol: "osc" | "midi";
@@ -103,6 +481,7 @@ export interface UnmatchedMessageEvent {
   timestamp: string;
   protocol: "osc" | "midi";
   display: string;
+  reason?: string;
 }
 
 export function defaultMapping(): Mapping {
@@ -112,16 +491,36 @@ export function defaultMapping(): Mapping {
     direction: "osc_to_midi",
     osc_address: "",
     osc_arg_types: [],
+    osc_arg_match: "any",
     midi_message_type: "note_on",
     midi_channel: 1,
     midi_note_or_cc: 60,
     midi_velocity_or_value: { type: "static", value: 127 },
     midi_input_velocity: null,
+    midi_input_port: null,
+    midi_output_port: null,
     osc_args: [],
     osc_output_address: "",
     osc_transform: null,
+    osc_destinations: [],
     msc_device_id: null,
     msc_command_format: null,
     msc_command: null,
+    msc_cue_number: null,
+    msc_cue_list: null,
+    msc_cue_path: null,
+    cc_mode: "seven_bit",
+    midi_parameter: 0,
+    clock_input: null,
+    transport_state: null,
+    midi_remap: { message_type: null, channel: null, number: null },
+    conditions: [],
+    mode: "momentary",
+    state_group: null,
+    persist_state: false,
+    slew: null,
+    output_filter: { max_rate_hz: null, dedupe: false, deadband: 0 },
+    steps: [],
+    retrigger: "cancel",
   };
 }